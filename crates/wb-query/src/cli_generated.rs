@@ -46,6 +46,27 @@ pub(crate) enum Commands {
         /// Allow %env(NAME) to read environment variables
     #[arg(long)]
 
+    allow_env: bool,
+    },
+    /// Annotate every line of a generated file with its chunk, literate
+    /// source location, confidence and macro span kind.
+    Blame {
+        /// Generated file path.
+        out_file: String,
+        /// Output format: text, json or html.
+        #[arg(long, default_value = "text")]
+        format: weaveback_api::lookup::BlameFormat,
+        /// Macro sigil character
+    #[arg(long, default_value = "%")]
+
+    sigil: char,
+        /// Include paths for %include/%import (colon-separated on Unix)
+    #[arg(long, default_value = ".")]
+
+    include: String,
+        /// Allow %env(NAME) to read environment variables
+    #[arg(long)]
+
     allow_env: bool,
     },
     /// Compute transitive impact of changes to a chunk.
@@ -53,12 +74,30 @@ pub(crate) enum Commands {
         /// Chunk name (e.g. "my-chunk" or "@file foo/bar.rs").
         chunk: String,
     },
-    /// Export chunk dependency graph as Graphviz DOT.
+    /// Export chunk dependency graph as Graphviz DOT, Mermaid, D2 or JSON.
     Graph {
         /// Restrict to the subgraph reachable from this chunk
     #[arg(long)]
 
     chunk: Option<String>,
+        /// Output format: dot, mermaid, d2 or json
+    #[arg(long, default_value = "dot")]
+
+    format: weaveback_api::query::GraphFormat,
+    },
+    /// Report cycles, fan-in/fan-out, longest expansion chains and chunks
+    /// no `@file` reaches in the chunk dependency graph.
+    GraphStats {
+        /// Length of the ranked lists (fan-in, fan-out, chains).
+        #[arg(long, default_value = "10")]
+        top: usize,
+    },
+    /// List chunks that nothing references and macros that nothing calls,
+    /// across all passes.
+    Unused {
+        /// Print the report as JSON instead of `file:line: message` lines.
+        #[arg(long)]
+        json: bool,
     },
     /// Tag prose blocks with LLM-generated tags, then rebuild the FTS index.
     Tag {
@@ -204,7 +243,7 @@ pub(crate) enum Commands {
         #[command(subcommand)]
         cmd: LspCommands,
     },
-    /// Full-text search over tangled source content.
+    /// Full-text search over prose and chunk bodies.
     Search {
         /// Search query (FTS5 syntax: AND, OR, NOT, phrase "...", prefix foo*)
 
@@ -214,6 +253,42 @@ pub(crate) enum Commands {
 
     limit: usize,
     },
+    /// Query chunks, files, deps, blocks and tags with a filter expression,
+    /// e.g. `chunks where file = "src/db.wvb" and not prose_before`.
+    Query {
+        /// The query: `<entity> [fields] [where <expr>] [order by <field>] [limit <n>]`.
+        query: String,
+        /// Print the rows as JSON instead of a table.
+        #[arg(long)]
+        json: bool,
+        /// LCOV tracefile that `coverage` fields are computed from.
+        #[arg(long)]
+        lcov: Option<PathBuf>,
+    },
+    /// Inspect and maintain the database itself.
+    Db {
+        #[command(subcommand)]
+        cmd: DbCommands,
+    },
+    /// Write the database's provenance as a portable JSON Lines bundle
+    /// (gzip-compressed when OUTPUT ends in `.gz`).
+    Export {
+        /// Bundle file to write.
+        output: PathBuf,
+        /// Project root that paths are made portable against
+        /// (default: the directory of --db).
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+    /// Load a bundle written by `export` into a new database at --db.
+    Import {
+        /// Bundle file to read (plain or gzip-compressed).
+        bundle: PathBuf,
+        /// Project root that portable paths are rebased onto
+        /// (default: the directory of --db).
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -237,3 +312,33 @@ pub(crate) enum LspCommands {
         col: u32,
     },
 }
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum DbCommands {
+    /// Show the schema version, pending migrations and table sizes.
+    Info,
+    /// Apply pending schema migrations.
+    Migrate {
+        /// List the pending migrations without applying them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Remove rows of files that no longer exist or are no longer produced.
+    Gc {
+        /// Report what would be removed without removing it.
+        #[arg(long)]
+        dry_run: bool,
+        /// Project root that stored paths are resolved against
+        /// (default: the directory of --db).
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+    /// Validate foreign keys, line ranges and baselines against the files
+    /// on disk; exits non-zero when an issue is found.
+    Check {
+        /// Project root that stored paths are resolved against
+        /// (default: the directory of --db).
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+}
@@ -2,7 +2,7 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 mod cli_generated;
-use cli_generated::{Cli, Commands, LspCommands};
+use cli_generated::{Cli, Commands, DbCommands, LspCommands};
 use clap::Parser;
 use std::path::PathBuf;
 use miette::Diagnostic;
@@ -39,6 +39,9 @@ enum Error {
         #[source]
         source: weaveback_api::lookup::LookupError,
     },
+    #[error("database check found {issues} issue(s)")]
+    #[diagnostic(code(weaveback::db_check))]
+    DbCheck { issues: usize },
     #[error("lint failed: {message}")]
     #[diagnostic(code(weaveback::lint))]
     Lint { message: String },
@@ -117,7 +120,7 @@ fn run_tag_only(
     match weaveback_tangle::db::WeavebackDb::open(&db_path) {
         Ok(mut db) => {
             tag::run_auto_tag(&mut db, &tag_cfg);
-            if let Err(e) = db.rebuild_prose_fts(None) {
+            if let Err(e) = db.rebuild_fts(None) {
                 eprintln!("warning: FTS index rebuild failed: {e}");
             }
         }
@@ -168,14 +171,36 @@ fn run(cli: Cli) -> Result<(), Error> {
             }
         }
 
+        Commands::Blame { out_file, format, sigil, include, allow_env } => {
+            let eval_config = build_eval_config(sigil, include, allow_env);
+            let db = WeavebackDb::open_read_only(&cli.db)?;
+            let resolver = PathResolver::new(PathBuf::from("."), cli.gen_dir);
+            let blame = weaveback_api::lookup::perform_blame(&out_file, &db, &resolver, eval_config)?;
+            println!("{}", blame.render(format));
+        }
+
         Commands::Impact { chunk } => {
             let v = weaveback_api::query::impact_analysis(&chunk, &cli.db)?;
             println!("{}", serde_json::to_string_pretty(&v).unwrap());
         }
 
-        Commands::Graph { chunk } => {
-            let dot = weaveback_api::query::chunk_graph_dot(chunk.as_deref(), &cli.db)?;
-            print!("{dot}");
+        Commands::Graph { chunk, format } => {
+            let graph = weaveback_api::query::chunk_graph(chunk.as_deref(), format, &cli.db)?;
+            println!("{graph}");
+        }
+
+        Commands::GraphStats { top } => {
+            let stats = weaveback_api::query::graph_stats(top, &cli.db)?;
+            println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+        }
+
+        Commands::Unused { json } => {
+            let report = weaveback_api::query::find_unused(&cli.db)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            } else {
+                print!("{}", report.to_text());
+            }
         }
 
         Commands::Tag { config, backend, model, endpoint, batch_size } => {
@@ -233,6 +258,59 @@ fn run(cli: Cli) -> Result<(), Error> {
         Commands::Search { query, limit } => {
             weaveback_api::coverage::run_search(query, limit, cli.db)?;
         }
+
+        Commands::Query { query, json, lcov } => {
+            let result = weaveback_api::query::evaluate_query(&query, &cli.db, lcov.as_deref())?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&result).unwrap());
+            } else {
+                print!("{}", result.to_table());
+            }
+        }
+
+        Commands::Db { cmd } => {
+            let resolver = |root: Option<PathBuf>| -> Result<PathResolver, Error> {
+                let root = match root {
+                    Some(root) => root,
+                    None => weaveback_api::db_admin::project_root_of(&cli.db)?,
+                };
+                Ok(PathResolver::new(root, cli.gen_dir.clone()))
+            };
+            let v = match cmd {
+                DbCommands::Info => weaveback_api::db_admin::db_info(&cli.db)?,
+                DbCommands::Migrate { dry_run } => weaveback_api::db_admin::migrate_db(&cli.db, dry_run)?,
+                DbCommands::Gc { dry_run, root } => {
+                    weaveback_api::db_admin::gc_db(&cli.db, &resolver(root)?, dry_run)?
+                }
+                DbCommands::Check { root } => weaveback_api::db_admin::check_db(&cli.db, &resolver(root)?)?,
+            };
+            println!("{}", serde_json::to_string_pretty(&v).unwrap());
+            if let Some(issues) = v.get("issues").and_then(|issues| issues.as_array())
+                && !issues.is_empty()
+            {
+                return Err(Error::DbCheck { issues: issues.len() });
+            }
+        }
+
+        Commands::Export { output, root } => {
+            let root = match root {
+                Some(root) => root,
+                None => weaveback_api::db_admin::project_root_of(&cli.db)?,
+            };
+            let resolver = PathResolver::new(root, cli.gen_dir);
+            let v = weaveback_api::db_admin::export_db(&cli.db, &output, &resolver)?;
+            println!("{}", serde_json::to_string_pretty(&v).unwrap());
+        }
+
+        Commands::Import { bundle, root } => {
+            let root = match root {
+                Some(root) => root,
+                None => weaveback_api::db_admin::project_root_of(&cli.db)?,
+            };
+            let resolver = PathResolver::new(root, cli.gen_dir);
+            let v = weaveback_api::db_admin::import_db(&bundle, &cli.db, &resolver)?;
+            println!("{}", serde_json::to_string_pretty(&v).unwrap());
+        }
     }
     Ok(())
 }
@@ -93,6 +93,40 @@ fn run_where_success() {
     assert!(res.is_ok());
 }
 
+#[test]
+fn run_blame_success_and_unmapped_file() {
+    let mut ws = TestWorkspace::new();
+    let mut db = ws.open_db();
+    db.set_noweb_entries(
+        "test.rs",
+        &[(0, weaveback_tangle::db::NowebMapEntry {
+            src_file: "test.adoc".to_string(),
+            chunk_name: "test".to_string(),
+            src_line: 0,
+            indent: String::new(),
+            confidence: weaveback_tangle::db::Confidence::Exact,
+        })],
+    ).unwrap();
+    drop(db);
+
+    let blame = |out_file: &str| Cli {
+        db: ws.db(),
+        gen_dir: ws.gen_dir(),
+        command: Commands::Blame {
+            out_file: out_file.to_string(),
+            format: weaveback_api::lookup::BlameFormat::Json,
+            sigil: '%',
+            include: String::new(),
+            allow_env: false,
+        },
+    };
+    assert!(run(blame("test.rs")).is_ok());
+    match run(blame("other.rs")).unwrap_err() {
+        Error::Lookup { source } => assert!(source.to_string().contains("no source map")),
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
 #[test]
 fn run_impact_success() {
     let mut ws = TestWorkspace::new();
@@ -117,12 +151,37 @@ fn run_graph_success() {
         gen_dir: ws.gen_dir(),
         command: Commands::Graph {
             chunk: None,
+            format: weaveback_api::query::GraphFormat::Mermaid,
         },
     };
     let res = run(cli);
     assert!(res.is_ok());
 }
 
+#[test]
+fn run_graph_stats_success() {
+    let mut ws = TestWorkspace::new();
+    ws.open_db();
+    let cli = Cli {
+        db: ws.db(),
+        gen_dir: ws.gen_dir(),
+        command: Commands::GraphStats { top: 5 },
+    };
+    assert!(run(cli).is_ok());
+}
+
+#[test]
+fn run_unused_success() {
+    let mut ws = TestWorkspace::new();
+    ws.open_db();
+    let cli = Cli {
+        db: ws.db(),
+        gen_dir: ws.gen_dir(),
+        command: Commands::Unused { json: true },
+    };
+    assert!(run(cli).is_ok());
+}
+
 #[test]
 fn run_tags_success() {
     let mut ws = TestWorkspace::new();
@@ -155,6 +214,20 @@ fn run_search_success() {
     assert!(res.is_ok());
 }
 
+#[test]
+fn run_query_reports_parse_errors() {
+    let mut ws = TestWorkspace::new();
+    ws.open_db();
+    let query = |query: &str| Cli {
+        db: ws.db(),
+        gen_dir: ws.gen_dir(),
+        command: Commands::Query { query: query.to_string(), json: false, lcov: None },
+    };
+    run(query("chunks where lines > 1 order by name")).unwrap();
+    let err = run(query("chunks where lines > 'x'")).unwrap_err();
+    assert!(matches!(err, Error::Api { .. }), "{err}");
+}
+
 #[test]
 fn run_tag_success() {
     let mut ws = TestWorkspace::new();
@@ -235,3 +308,60 @@ fn run_coverage_success() {
     let res = run(cli);
     assert!(res.is_ok());
 }
+
+#[test]
+fn run_db_info_and_migrate() {
+    let mut ws = TestWorkspace::new();
+    drop(ws.open_db());
+    for cmd in [
+        DbCommands::Info,
+        DbCommands::Migrate { dry_run: true },
+        DbCommands::Migrate { dry_run: false },
+    ] {
+        let cli = Cli { db: ws.db(), gen_dir: ws.gen_dir(), command: Commands::Db { cmd } };
+        run(cli).unwrap();
+    }
+    assert_eq!(ws.open_db().schema_version().unwrap(), weaveback_tangle::db::SCHEMA_VERSION);
+}
+
+#[test]
+fn run_db_info_missing_db() {
+    let ws = TestWorkspace::new();
+    let cli = Cli { db: ws.db(), gen_dir: ws.gen_dir(), command: Commands::Db { cmd: DbCommands::Info } };
+    assert!(run(cli).unwrap_err().to_string().contains("query operation failed"));
+}
+
+#[test]
+fn run_db_check_fails_until_gc_drops_the_missing_baseline() {
+    let mut ws = TestWorkspace::new();
+    ws.open_db().set_baseline("gone.rs", b"x\n").unwrap();
+    let db = |cmd| Cli { db: ws.db(), gen_dir: ws.gen_dir(), command: Commands::Db { cmd } };
+    let err = run(db(DbCommands::Check { root: None })).unwrap_err();
+    assert!(err.to_string().contains("1 issue(s)"), "{err}");
+    run(db(DbCommands::Gc { dry_run: false, root: None })).unwrap();
+    run(db(DbCommands::Check { root: None })).unwrap();
+}
+
+#[test]
+fn run_export_then_import_into_a_new_db() {
+    let mut ws = TestWorkspace::new();
+    ws.open_db().set_baseline("out.rs", b"x\n").unwrap();
+    let bundle = ws.root.join("bundle.jsonl");
+    let cli = Cli {
+        db: ws.db(),
+        gen_dir: ws.gen_dir(),
+        command: Commands::Export { output: bundle.clone(), root: None },
+    };
+    run(cli).unwrap();
+
+    let copy = ws.root.join("copy.db");
+    let import = |db: PathBuf| Cli {
+        db,
+        gen_dir: ws.gen_dir(),
+        command: Commands::Import { bundle: bundle.clone(), root: None },
+    };
+    run(import(copy.clone())).unwrap();
+    let db = weaveback_tangle::db::WeavebackDb::open_read_only(&copy).unwrap();
+    assert_eq!(db.get_baseline("out.rs").unwrap().as_deref(), Some(&b"x\n"[..]));
+    assert!(run(import(ws.db())).is_err());
+}
@@ -17,6 +17,49 @@ pub(crate) struct Cli {
     #[arg(long)]
 
     pub(crate) force_generated: bool,
+        /// Delete generated files whose @file chunk no longer exists, if they
+        /// still match their stored baseline, and drop their database records.
+    #[arg(long)]
+
+    pub(crate) prune: bool,
+        /// Run every pass into staging only and report generated files that are
+        /// stale, modified externally or orphaned, with a unified diff for each.
+        /// Writes no files and leaves the database untouched; exits non-zero on drift.
+    #[arg(long, conflicts_with_all = ["force_generated", "prune"])]
+
+    pub(crate) check: bool,
+        /// Three-way merge hand-edited generated files (stored baseline, file on
+        /// disk, new output) instead of refusing to overwrite them. Surviving edits
+        /// are reported for apply-back; conflicts get markers and a non-zero exit.
+    #[arg(long, conflicts_with_all = ["force_generated", "check"])]
+
+    pub(crate) merge: bool,
+        /// Build profile whose `@if(profile=NAME)` chunk guards hold. With a config,
+        /// NAME must be a `[profiles.NAME]` section; passes tangle into its own `gen`
+        /// directory and `weaveback.NAME.db`.
+    #[arg(long, value_name = "NAME")]
+
+    pub(crate) profile: Option<String>,
+        /// Run every pass once, then watch sources, preludes and includes and
+        /// re-run only the affected passes on each change until interrupted.
+    #[arg(long, conflicts_with_all = ["check", "directory", "inputs"])]
+
+    pub(crate) watch: bool,
+        /// Run only the `[[pass]]` with this `name`, plus every pass that depends
+        /// on it. Repeatable.
+    #[arg(long = "pass", value_name = "NAME", conflicts_with_all = ["watch", "directory", "inputs"])]
+
+    pub(crate) pass_names: Vec<String>,
+        /// Run only passes whose sources, preludes or includes changed since the
+        /// last run (per the database snapshots), plus every pass that depends on them.
+    #[arg(long, conflicts_with_all = ["watch", "directory", "inputs"])]
+
+    pub(crate) only_changed: bool,
+        /// Print the resolved `weaveback.toml` (defaults, templates and globbed
+        /// passes expanded) as TOML and exit.
+    #[arg(long, conflicts_with_all = ["watch", "check", "directory", "inputs"])]
+
+    pub(crate) print_config: bool,
 
     #[command(subcommand)]
     pub(crate) command: Option<Commands>,
@@ -142,9 +185,9 @@ pub(crate) struct SinglePassCli {
     #[arg(long)]
 
     pub(crate) stamp: Option<PathBuf>,
-    /// Skip rebuilding the prose full-text search index after this run.
+    /// Skip rebuilding the prose and code full-text search indexes after this run.
     /// Used internally by `wb-tangle` to avoid concurrent FTS rebuilds;
-    /// the tangle command rebuilds the index once after all passes complete.
+    /// the tangle command rebuilds the indexes once after all passes complete.
     #[arg(long, hide = true)]
 
     pub(crate) no_fts: bool,
@@ -40,12 +40,36 @@ enum Error {
 mod cli_generated;
 use cli_generated::{Cli, Commands, SinglePassCli};
 use clap::Parser;
-fn run_multi_pass(config: &std::path::Path, force_generated: bool) -> Result<(), Error> {
-    weaveback_api::tangle::run_tangle_all(config, force_generated)
+use weaveback_api::tangle::{PassSelection, TangleRunOptions};
+fn run_multi_pass(config: &std::path::Path, opts: &TangleRunOptions) -> Result<(), Error> {
+    weaveback_api::tangle::run_tangle_all(config, opts)
         .map_err(|source| Error::Io { source })
 }
 
-fn run_single_pass_from_cli(s: SinglePassCli, force_generated: bool) -> Result<(), Error> {
+fn run_selected_passes(
+    config: &std::path::Path,
+    opts: &TangleRunOptions,
+    selection: &PassSelection,
+) -> Result<(), Error> {
+    weaveback_api::tangle::run_tangle_selected(config, opts, selection)
+        .map_err(|source| Error::Io { source })
+}
+
+fn run_watch_mode(config: &std::path::Path, opts: &TangleRunOptions) -> Result<(), Error> {
+    weaveback_api::tangle::run_watch(config, opts)
+        .map_err(|source| Error::Io { source })
+}
+
+fn print_config(config: &std::path::Path, opts: &TangleRunOptions) -> Result<(), Error> {
+    let cfg = weaveback_api::tangle::load_profiled_cfg(config, opts)
+        .map_err(|source| Error::Io { source })?;
+    let rendered = weaveback_api::tangle::render_tangle_cfg(&cfg)
+        .map_err(|source| Error::Io { source })?;
+    print!("{rendered}");
+    Ok(())
+}
+
+fn run_single_pass_from_cli(s: SinglePassCli, opts: &TangleRunOptions) -> Result<(), Error> {
     use weaveback_api::process::{SinglePassArgs, run_single_pass};
     run_single_pass(SinglePassArgs {
         inputs:          s.inputs,
@@ -63,21 +87,27 @@ fn run_single_pass_from_cli(s: SinglePassCli, force_generated: bool) -> Result<(
         expanded_adoc_dir: s.expanded_adoc_dir,
         expanded_md_dir: s.expanded_md_dir,
         macro_only:      s.macro_only,
+        projections:     Vec::new(),
         dry_run:         s.dry_run,
         db:              s.db,
         depfile:         s.depfile,
         stamp:           s.stamp,
         strict:          s.strict,
+        profile:         opts.profile.clone(),
         warn_unused:     s.warn_unused,
         allow_env:       s.allow_env,
         allow_home:      s.allow_home,
-        force_generated,
+        force_generated: opts.force_generated,
+        prune:           opts.prune,
+        check:           opts.check,
+        merge:           opts.merge,
         sigil:           s.sigil,
         include:         s.include,
         formatter:       s.formatter,
         no_fts:          s.no_fts,
         dump_expanded:   s.dump_expanded,
         project_root:    None,
+        pass_name:       None,
     })?;
     Ok(())
 }
@@ -116,15 +146,31 @@ fn run_apply_back(
 
 fn main() -> miette::Result<()> {
     let cli = Cli::parse();
+    let opts = TangleRunOptions {
+        force_generated: cli.force_generated,
+        prune:           cli.prune,
+        check:           cli.check,
+        merge:           cli.merge,
+        profile:         cli.profile,
+    };
 
     let result: Result<(), Error> = match cli.command {
         Some(Commands::ApplyBack { files, dry_run }) => {
             run_apply_back(files, dry_run, &cli.single)
         }
         None if cli.single.directory.is_some() || !cli.single.inputs.is_empty() => {
-            run_single_pass_from_cli(cli.single, cli.force_generated)
+            run_single_pass_from_cli(cli.single, &opts)
+        }
+        None if cli.print_config => print_config(&cli.config, &opts),
+        None if cli.watch => run_watch_mode(&cli.config, &opts),
+        None if !cli.pass_names.is_empty() || cli.only_changed => {
+            let selection = PassSelection {
+                names: cli.pass_names,
+                only_changed: cli.only_changed,
+            };
+            run_selected_passes(&cli.config, &opts, &selection)
         }
-        None => run_multi_pass(&cli.config, cli.force_generated),
+        None => run_multi_pass(&cli.config, &opts),
     };
 
     result?;
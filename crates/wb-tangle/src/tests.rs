@@ -91,7 +91,7 @@ fn test_bin_run_single_pass() {
     single.directory = Some(ws.root.clone());
 
     println!("Running single pass on {:?}", ws.root);
-    run_single_pass_from_cli(single, false).expect("single pass failed");
+    run_single_pass_from_cli(single, &TangleRunOptions::default()).expect("single pass failed");
 
     let out = ws.gen_file("test.rs");
     println!("Checking output path: {:?}", out);
@@ -116,7 +116,7 @@ fn test_bin_run_multi_pass_error() {
     let ws = TestWorkspace::new();
     let config = ws.root.join("weaveback.toml");
     // Missing config should error
-    let res = run_multi_pass(&config, false);
+    let res = run_multi_pass(&config, &TangleRunOptions::default());
     assert!(res.is_err());
 }
 
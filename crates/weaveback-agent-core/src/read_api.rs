@@ -20,6 +20,19 @@ pub struct SearchHit {
     pub tags: Vec<String>,
     pub score: f64,
     pub channels: Vec<String>,
+    /// `"prose"` for a prose block, `"code"` for a chunk body.
+    #[serde(default = "prose_kind")]
+    pub kind: String,
+    /// The chunk whose body matched, for code hits.
+    #[serde(default)]
+    pub chunk: Option<String>,
+    /// Generated files the matched chunk was tangled into, for code hits.
+    #[serde(default)]
+    pub generated_files: Vec<String>,
+}
+
+fn prose_kind() -> String {
+    "prose".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
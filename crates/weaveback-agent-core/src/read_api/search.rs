@@ -131,6 +131,7 @@ pub(in crate::read_api) fn embed_query(db: &WeavebackDb, query: &str) -> Result<
             let base = endpoint.as_deref().filter(|v| !v.is_empty()).unwrap_or("http://localhost:11434/v1");
             call_openai_embeddings(None, base, &model, &[query.to_string()])?
         }
+        "local" => weaveback_core::embedding::local_embedder(&model)?.embed(&[query.to_string()])?,
         "anthropic" => return Ok(None),
         _ => {
             let key = std::env::var("OPENAI_API_KEY").ok();
@@ -150,6 +151,8 @@ pub fn search(config: &WorkspaceConfig, query: &str, limit: usize) -> Result<Vec
         .flatten()
         .and_then(|query_embedding| db.search_prose_by_embedding(&query_embedding, limit.saturating_mul(4)).ok())
         .unwrap_or_default();
+    // A database written before `code_fts` existed has no code index.
+    let code = db.search_code(&fts_query, limit.saturating_mul(4)).unwrap_or_default();
 
     let mut merged: std::collections::BTreeMap<(String, String, usize, usize), SearchHit> = std::collections::BTreeMap::new();
 
@@ -175,6 +178,9 @@ pub fn search(config: &WorkspaceConfig, query: &str, limit: usize) -> Result<Vec
                 .collect(),
             score: 0.0,
             channels: Vec::new(),
+            kind: prose_kind(),
+            chunk: None,
+            generated_files: Vec::new(),
         });
         entry.score += reciprocal_rank(idx + 1);
         if !entry.channels.iter().any(|channel| channel == "fts") {
@@ -204,6 +210,9 @@ pub fn search(config: &WorkspaceConfig, query: &str, limit: usize) -> Result<Vec
                 .collect(),
             score: 0.0,
             channels: Vec::new(),
+            kind: prose_kind(),
+            chunk: None,
+            generated_files: Vec::new(),
         });
         entry.score += reciprocal_rank(idx + 1) + f64::from(result.score.max(0.0)) * 0.25;
         if !entry.channels.iter().any(|channel| channel == "semantic") {
@@ -211,6 +220,32 @@ pub fn search(config: &WorkspaceConfig, query: &str, limit: usize) -> Result<Vec
         }
     }
 
+    for (idx, result) in code.into_iter().enumerate() {
+        let key = (
+            result.src_file.clone(),
+            "code".to_string(),
+            result.line_start as usize,
+            result.line_end as usize,
+        );
+        let entry = merged.entry(key).or_insert_with(|| SearchHit {
+            src_file: result.src_file.clone(),
+            block_type: "code".to_string(),
+            line_start: result.line_start as usize,
+            line_end: result.line_end as usize,
+            snippet: result.snippet.clone(),
+            tags: Vec::new(),
+            score: 0.0,
+            channels: Vec::new(),
+            kind: "code".to_string(),
+            chunk: Some(result.chunk_name.clone()),
+            generated_files: result.generated_files.clone(),
+        });
+        entry.score += reciprocal_rank(idx + 1);
+        if !entry.channels.iter().any(|channel| channel == "fts") {
+            entry.channels.push("fts".to_string());
+        }
+    }
+
     let mut hits: Vec<SearchHit> = merged.into_values().collect();
     hits.sort_by(|lhs, rhs| {
         rhs.score
@@ -212,6 +212,84 @@ fn search_returns_ranked_fts_hits_with_tags() {
     assert!(hits[0].score > 0.0);
 }
 
+#[test]
+fn search_fuses_local_embeddings_without_network() {
+    let workspace = TestWorkspace::new();
+    let source = "= Alpha\n\nBaselines guard generated files.\n\nMacros expand recursively.\n";
+    workspace.write_source("docs/alpha.adoc", source);
+
+    let mut db = workspace.open_db();
+    db.set_src_snapshot("docs/alpha.adoc", source.as_bytes()).unwrap();
+    db.set_source_blocks(
+        "docs/alpha.adoc",
+        &[block(0, "section", 1, 1), block(1, "para", 3, 3), block(2, "para", 5, 5)],
+    )
+    .unwrap();
+    db.set_run_config("semantic.backend", "local").unwrap();
+    db.set_run_config("semantic.model", "hashed-ngram").unwrap();
+    let embedder = weaveback_core::embedding::local_embedder("hashed-ngram").unwrap();
+    for (index, line) in [(1, 3), (2, 5)] {
+        let text = source.lines().nth(line - 1).unwrap().to_string();
+        let vector = embedder.embed(&[text]).unwrap().remove(0);
+        db.set_block_embedding("docs/alpha.adoc", index, &tags_hash(index as u8), "hashed-ngram", &vector)
+            .unwrap();
+    }
+    db.rebuild_prose_fts(None).unwrap();
+    drop(db);
+
+    let hits = search(&workspace.config(), "generated baselines", 5).unwrap();
+    assert_eq!(hits[0].line_start, 3);
+    assert_eq!(hits[0].channels, vec!["fts", "semantic"]);
+}
+
+#[test]
+fn search_returns_code_hits_with_chunk_and_outputs() {
+    let workspace = TestWorkspace::new();
+    let source = "= Alpha\n\nOpening the database.\n\n// <<open-db>>=\nconn.busy_timeout(TIMEOUT)?;\n// @\n";
+    workspace.write_source("docs/alpha.adoc", source);
+
+    let mut db = workspace.open_db();
+    db.set_src_snapshot("docs/alpha.adoc", source.as_bytes()).unwrap();
+    db.set_source_blocks("docs/alpha.adoc", &[block(0, "section", 1, 1), block(1, "para", 3, 3)])
+        .unwrap();
+    db.set_chunk_defs(&[ChunkDefEntry {
+        src_file: "docs/alpha.adoc".to_string(),
+        chunk_name: "open-db".to_string(),
+        nth: 0,
+        def_start: 5,
+        def_end: 7,
+    }])
+    .unwrap();
+    db.set_noweb_entries(
+        "gen/db.rs",
+        &[(
+            0,
+            NowebMapEntry {
+                src_file: "docs/alpha.adoc".to_string(),
+                chunk_name: "open-db".to_string(),
+                src_line: 5,
+                indent: String::new(),
+                confidence: Confidence::Exact,
+            },
+        )],
+    )
+    .unwrap();
+    db.rebuild_fts(None).unwrap();
+    drop(db);
+
+    let hits = search(&workspace.config(), "busy_timeout", 5).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].kind, "code");
+    assert_eq!(hits[0].chunk.as_deref(), Some("open-db"));
+    assert_eq!(hits[0].generated_files, vec!["gen/db.rs"]);
+    assert_eq!((hits[0].line_start, hits[0].line_end), (6, 6));
+    assert_eq!(hits[0].channels, vec!["fts"]);
+
+    let hits = search(&workspace.config(), "database", 5).unwrap();
+    assert_eq!(hits[0].kind, "prose");
+    assert!(hits[0].chunk.is_none());
+}
+
 #[test]
 fn chunk_context_reads_source_breadcrumbs_deps_and_outputs() {
     let workspace = TestWorkspace::new();
@@ -34,6 +34,9 @@ impl From<crate::query::ApiError> for CoverageApiError {
         match e {
             crate::query::ApiError::Db(e) => CoverageApiError::Noweb(WeavebackError::Db(e)),
             crate::query::ApiError::Io(e) => CoverageApiError::Io(e),
+            crate::query::ApiError::Query(e) => CoverageApiError::Io(
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()),
+            ),
         }
     }
 }
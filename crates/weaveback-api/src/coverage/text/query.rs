@@ -39,7 +39,15 @@ pub fn run_search(query: String, limit: usize, db_path: PathBuf) -> Result<(), C
         } else {
             format!(" via {}", r.channels.join("+"))
         };
-        if r.tags.is_empty() {
+        if let Some(chunk) = &r.chunk {
+            println!(
+                "{}:{}-{} [{}] {chunk}{channels}",
+                r.src_file,
+                r.line_start,
+                r.line_end,
+                r.kind,
+            );
+        } else if r.tags.is_empty() {
             println!(
                 "{}:{}-{} [{}]{channels}",
                 r.src_file,
@@ -58,6 +66,9 @@ pub fn run_search(query: String, limit: usize, db_path: PathBuf) -> Result<(), C
             );
         }
         println!("  {}", r.snippet);
+        if !r.generated_files.is_empty() {
+            println!("  -> {}", r.generated_files.join(", "));
+        }
         println!();
     }
     Ok(())
@@ -3,6 +3,7 @@
 
 pub mod apply_back;
 pub mod coverage;
+pub mod db_admin;
 pub mod lint;
 pub mod lsp_runner;
 pub mod lookup;
@@ -3,7 +3,7 @@
 
 use std::fs;
 use std::path::{Path, PathBuf};
-use weaveback_tangle::NowebSyntax;
+use weaveback_tangle::{NowebSyntax, parse_front_matter};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 pub enum LintRule {
@@ -59,7 +59,7 @@ mod run;
 pub use run::run_lint;
 
 #[cfg(test)]
-use config::{lint_syntaxes_for_file, load_lint_syntaxes_from};
+use config::{front_matter_syntaxes, lint_syntaxes_for_file, load_lint_syntaxes_from};
 #[cfg(test)]
 use fs_scan::collect_literate_files;
 #[cfg(test)]
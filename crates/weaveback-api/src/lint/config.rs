@@ -95,3 +95,11 @@ pub(in crate::lint) fn lint_syntaxes_for_file<'a>(file: &Path, syntaxes: &'a [Li
 
     matched
 }
+
+/// `file_syntaxes` with the chunk-syntax overrides of `text`'s front matter
+/// applied, or `None` when it has none (or it is malformed; tangling reports
+/// that).
+pub(in crate::lint) fn front_matter_syntaxes(text: &str, file_syntaxes: &[&NowebSyntax]) -> Option<Vec<NowebSyntax>> {
+    let front_matter = parse_front_matter(text).ok().filter(|fm| fm.overrides_syntax())?;
+    Some(file_syntaxes.iter().map(|syntax| syntax.with_overrides(&front_matter)).collect())
+}
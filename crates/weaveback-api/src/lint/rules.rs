@@ -2,7 +2,7 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 use super::*;
-use super::config::{lint_syntaxes_for_file, load_lint_syntaxes};
+use super::config::{front_matter_syntaxes, lint_syntaxes_for_file, load_lint_syntaxes};
 use super::fs_scan::{collect_literate_files, is_prelude_file, is_wvb_file};
 
 pub(in crate::lint) fn parse_chunk_definition_name(line: &str, syntaxes: &[&NowebSyntax]) -> Option<String> {
@@ -284,6 +284,11 @@ pub(in crate::lint) fn lint_paths(
     for file in source_files {
         let text = fs::read_to_string(&file).map_err(|e| format!("{}: {e}", file.display()))?;
         let file_syntaxes = lint_syntaxes_for_file(&file, &syntaxes);
+        let overridden = front_matter_syntaxes(&text, &file_syntaxes);
+        let file_syntaxes = match &overridden {
+            Some(overridden) => overridden.iter().collect(),
+            None => file_syntaxes,
+        };
         if rule_filter.is_none() || rule_filter == Some(LintRule::ChunkBodyOutsideFence) {
             violations.extend(lint_chunk_body_outside_fence(&file, &text, &file_syntaxes));
         }
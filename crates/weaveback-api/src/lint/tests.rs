@@ -196,6 +196,20 @@ comment_markers = "//"
     );
 }
 
+#[test]
+fn lint_applies_front_matter_syntax_overrides() {
+    let default = NowebSyntax::new("<<", ">>", "@", &["//".to_string()]);
+    let text = ":weaveback-open-delim: <[\n:weaveback-close-delim: ]>\n\n// <[alpha]>=\nbody\n// @\n";
+    let overridden = front_matter_syntaxes(text, &[&default]).unwrap();
+    let overridden = overridden.iter().collect::<Vec<_>>();
+
+    assert_eq!(
+        parse_chunk_definition_name("// <[alpha]>=", &overridden).as_deref(),
+        Some("alpha")
+    );
+    assert!(front_matter_syntaxes("= Title\n", &[&default]).is_none());
+}
+
 #[test]
 fn collect_literate_files_skips_generated_dirs_and_includes_wvb() {
     let temp = TempDir::new().unwrap();
@@ -1,11 +1,13 @@
 // weaveback-api/src/lookup.rs
 // I'd Really Rather You Didn't edit this generated file.
 
+mod blame;
 mod context;
 mod span;
 mod trace;
 mod where_lookup;
 
+pub use blame::{Blame, BlameFormat, BlameLine, BlameOrigin, perform_blame};
 pub use context::build_source_context_value;
 pub use trace::{load_source_text, perform_trace, perform_trace_coarse};
 pub use where_lookup::perform_where;
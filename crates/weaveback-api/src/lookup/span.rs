@@ -41,6 +41,17 @@ pub(in crate::lookup) fn span_at_line<'a>(
     PreciseTracingOutput::span_at_byte(ranges, line_start + byte_col)
 }
 
+/// The variant name of `kind`, as reported in the `kind` field.
+pub(in crate::lookup) fn span_kind_name(kind: &SpanKind) -> &'static str {
+    match kind {
+        SpanKind::Literal => "Literal",
+        SpanKind::MacroBody { .. } => "MacroBody",
+        SpanKind::MacroArg { .. } => "MacroArg",
+        SpanKind::VarBinding { .. } => "VarBinding",
+        SpanKind::Computed => "Computed",
+    }
+}
+
 /// Append macro-level fields to `result` from `span`.
 pub(in crate::lookup) fn append_span_fields(
     result: &mut Value,
@@ -65,14 +76,7 @@ pub(in crate::lookup) fn append_span_fields(
     obj.insert("src_col".into(), Value::Number(src_col_1.into()));
     append_source_context(obj, &src_content, src_line_1 as usize);
 
-    let kind_str = match &span.kind {
-        SpanKind::Literal => "Literal",
-        SpanKind::MacroBody { .. } => "MacroBody",
-        SpanKind::MacroArg { .. } => "MacroArg",
-        SpanKind::VarBinding { .. } => "VarBinding",
-        SpanKind::Computed => "Computed",
-    };
-    obj.insert("kind".into(), Value::String(kind_str.to_string()));
+    obj.insert("kind".into(), Value::String(span_kind_name(&span.kind).to_string()));
 
     match &span.kind {
         SpanKind::MacroBody { macro_name } => {
@@ -24,7 +24,7 @@ use super::helpers::{send_error, send_response, send_text};
 use super::tools::tools_list_result;
 use data::{
     handle_chunk_context, handle_coverage, handle_find_chunk, handle_list_chunks,
-    handle_list_tags, handle_search,
+    handle_list_tags, handle_query, handle_search,
 };
 use lsp::{
     handle_lsp_definition, handle_lsp_diagnostics, handle_lsp_hover, handle_lsp_references,
@@ -280,6 +280,10 @@ pub fn run_mcp<R: BufRead, W: Write>(
                         handle_coverage(&mut writer, id, input, &db_path, &resolver);
                     }
 
+                    Some("weaveback_query") => {
+                        handle_query(&mut writer, id, input, &db_path);
+                    }
+
                     other => send_error(&mut writer, id, &format!("Unknown tool: {:?}", other)),
                 }
             }
@@ -140,10 +140,15 @@ pub(super) fn handle_search<W: Write>(
                     "snippet":    r.snippet,
                     "score":      r.score,
                     "channels":   r.channels,
+                    "kind":       r.kind,
                 });
                 if !r.tags.is_empty() {
                     obj["tags"] = json!(r.tags);
                 }
+                if let Some(chunk) = &r.chunk {
+                    obj["chunk"] = json!(chunk);
+                    obj["generated_files"] = json!(r.generated_files);
+                }
                 obj
             }).collect();
             send_text(writer, id, &serde_json::to_string_pretty(&arr).unwrap());
@@ -213,3 +218,27 @@ pub(super) fn handle_coverage<W: Write>(
         (_, Err(e)) => send_error(writer, id, &format!("Database error: {e:?}")),
     }
 }
+
+pub(super) fn handle_query<W: Write>(
+    writer: &mut W,
+    id: Option<Value>,
+    input: Option<&serde_json::Map<String, Value>>,
+    db_path: &std::path::Path,
+) {
+    let query = input
+        .and_then(|v| v.get("query"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    if query.is_empty() {
+        send_error(writer, id, "query is required");
+        return;
+    }
+    let lcov_path = input
+        .and_then(|v| v.get("lcov_path"))
+        .and_then(|v| v.as_str())
+        .map(std::path::Path::new);
+    match crate::query::evaluate_query(query, db_path, lcov_path) {
+        Ok(result) => send_text(writer, id, &serde_json::to_string_pretty(&result).unwrap()),
+        Err(e) => send_error(writer, id, &e.to_string()),
+    }
+}
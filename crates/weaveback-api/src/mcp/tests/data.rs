@@ -233,6 +233,27 @@ fn mcp_coverage_reports_stats() {
     assert!(out.contains("attributed_records"), "Coverage report missing or invalid. Output: {out}");
 }
 #[test]
+fn mcp_query_returns_rows_and_reports_errors() {
+    let ws = McpWorkspace::new();
+    {
+        let mut db = ws.open_db();
+        db.set_chunk_defs(&[weaveback_tangle::db::ChunkDefEntry {
+            src_file:   "src/lib.adoc".to_string(),
+            chunk_name: "query-target".to_string(),
+            nth:        0,
+            def_start:  1,
+            def_end:    5,
+        }]).unwrap();
+    }
+    let req = r#"{"jsonrpc":"2.0","id":39,"method":"tools/call","params":{"name":"weaveback_query","arguments":{"query":"chunks where name ~ 'query-*'"}}}"#;
+    let out = mcp_drive(&ws, req);
+    assert!(out.contains("query-target"), "output was: {out}");
+
+    let req = r#"{"jsonrpc":"2.0","id":40,"method":"tools/call","params":{"name":"weaveback_query","arguments":{"query":"chunks where size > 1"}}}"#;
+    let out = mcp_drive(&ws, req);
+    assert!(out.contains("column 14"), "output was: {out}");
+}
+#[test]
 fn mcp_list_chunks_success_path() {
     let ws = McpWorkspace::new();
     ws.open_db();
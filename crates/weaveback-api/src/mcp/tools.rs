@@ -147,7 +147,7 @@ pub(super) fn tools_list_result() -> Value {
                             },
                                         {
                                             "name": "weaveback_search",
-                                            "description": "Hybrid search over the prose in all literate source files. FTS5 and tags are always used; if prose embeddings were generated during tangle, semantic reranking is also applied. Returns ranked excerpts with file path, line range, tags, score, and contributing channels. Use this to discover which chunks or sections are relevant to a concept before calling weaveback_chunk_context. Supports FTS5 query syntax: AND, OR, NOT, phrase \"...\", prefix foo*.",
+                                            "description": "Hybrid search over the prose and chunk bodies in all literate source files. FTS5 and tags are always used; if prose embeddings were generated during tangle, semantic reranking is also applied. Returns ranked excerpts with file path, line range, tags, score, contributing channels, and kind (prose or code); code hits also name the chunk and its generated files. Use this to discover which chunks or sections are relevant to a concept before calling weaveback_chunk_context. Supports FTS5 query syntax: AND, OR, NOT, phrase \"...\", prefix foo*.",
                                             "inputSchema": {
                                                 "type": "object",
                                                 "properties": {
@@ -176,6 +176,18 @@ pub(super) fn tools_list_result() -> Value {
                                                     "lcov_path": { "type": "string", "description": "Path to the lcov.info file (defaults to lcov.info in the root directory)" }
                                                 }
                                             }
+                                        },
+                                        {
+                                            "name": "weaveback_query",
+                                            "description": "Run a structured query over the provenance graph: chunks, files, deps, blocks or tags, filtered with where, sorted with order by and cut with limit. Example: files where role = 'output' and chunks has 'open-db' and coverage < 50. Returns the entity, the selected columns and one JSON object per row. Fields: chunks(name, file, nth, line_start, line_end, lines, deps, used_by, outputs, prose_before, coverage, tags), files(path, role, chunks, sources, outputs, coverage), deps(from, to, file), blocks(file, index, type, line_start, line_end, tags), tags(tag, file, block, type, line_start). coverage needs lcov_path.",
+                                            "inputSchema": {
+                                                "type": "object",
+                                                "properties": {
+                                                    "query": { "type": "string", "description": "The query, e.g. chunks where not prose_before order by lines desc limit 10" },
+                                                    "lcov_path": { "type": "string", "description": "Optional: LCOV tracefile for the coverage field" }
+                                                },
+                                                "required": ["query"]
+                                            }
                                         }
                                     ]
                                 })
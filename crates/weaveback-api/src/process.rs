@@ -2,16 +2,25 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 mod args;
+mod check;
+mod context;
 mod expanded_paths;
 mod fs;
 mod macro_prelude;
 mod markdown_normalize;
+mod merge;
+mod orphans;
 mod run;
 mod skip;
 
-pub use args::{ProcessError, SinglePassArgs};
+pub use args::{ProcessError, Projection, SinglePassArgs};
+pub use check::{DriftKind, DriftedOutput, check_generated_outputs, check_orphans, finish_check};
+pub use context::PassContext;
 pub use fs::{find_files, write_depfile};
-pub use run::run_single_pass;
+pub(crate) use markdown_normalize::is_markdown_ext;
+pub use merge::report_merges;
+pub use orphans::{OrphanStatus, OrphanedOutput, describe_orphan, pass_key, prune_orphans, report_orphans};
+pub use run::{run_single_pass, run_single_pass_with};
 pub use skip::compute_skip_set;
 
 #[cfg(test)]
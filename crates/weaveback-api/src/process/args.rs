@@ -1,12 +1,14 @@
 // weaveback-api/src/process/args.rs
 // I'd Really Rather You Didn't edit this generated file.
 
+use super::markdown_normalize::is_markdown_ext;
+
 use std::path::PathBuf;
 
 use miette::Diagnostic;
 use weaveback_macro::evaluator::EvalError;
 use weaveback_tangle::db::DbError;
-use weaveback_tangle::WeavebackError;
+use weaveback_tangle::{FrontMatterError, WeavebackError};
 
 /// Combined error type for a single tangle pass.
 #[derive(Debug, thiserror::Error, Diagnostic)]
@@ -59,6 +61,26 @@ pub enum ProcessError {
         source: EvalError,
     },
 
+    #[error("{count} generated file(s) out of date")]
+    #[diagnostic(code(weaveback::process::out_of_date))]
+    OutOfDate { count: usize },
+
+    #[error("{count} generated file(s) left with merge conflicts")]
+    #[diagnostic(code(weaveback::process::merge_conflicts))]
+    MergeConflicts { count: usize },
+
+    #[error("invalid projections: {reason}")]
+    #[diagnostic(code(weaveback::process::projections))]
+    Projections { reason: String },
+
+    #[error("invalid front matter in `{path}`")]
+    #[diagnostic(code(weaveback::process::front_matter))]
+    FrontMatter {
+        path: PathBuf,
+        #[source]
+        source: FrontMatterError,
+    },
+
     #[error("failed to write expanded document `{path}`")]
     #[diagnostic(code(weaveback::process::expanded_write))]
     ExpandedWrite {
@@ -69,6 +91,27 @@ pub enum ProcessError {
 }
 
 
+/// One expanded output of a macro-enabled pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Projection {
+    /// Prelude files evaluated before each source for this output.
+    pub macro_prelude: Vec<PathBuf>,
+    /// Extension of the expanded documents (`None` keeps the source's).
+    pub expanded_ext: Option<String>,
+    /// Where expanded documents are written; `None` writes none.
+    pub expanded_dir: Option<PathBuf>,
+    /// Feed this output to the tangler.
+    pub tangle: bool,
+}
+
+impl Projection {
+    /// The projection whose evaluation drives include discovery: the tangled
+    /// one, else the first.
+    pub fn primary(projections: &[Projection]) -> Option<&Projection> {
+        projections.iter().find(|p| p.tangle).or_else(|| projections.first())
+    }
+}
+
 /// All arguments needed for one tangle pass.
 ///
 /// Constructed by the CLI layer from parsed `clap` args and passed to
@@ -104,6 +147,10 @@ pub struct SinglePassArgs {
     pub expanded_md_dir: PathBuf,
     /// Stop after macro expansion and write expanded documents.
     pub macro_only: bool,
+    /// Expanded outputs from one parse of each source; when non-empty these
+    /// replace `macro_prelude`, `expanded_ext`, the expanded dirs and
+    /// `macro_only`.
+    pub projections: Vec<Projection>,
     /// Print discovered `@file` chunk names and exit (no writes).
     pub dry_run: bool,
     /// Path to the weaveback SQLite database.
@@ -114,6 +161,8 @@ pub struct SinglePassArgs {
     pub stamp: Option<PathBuf>,
     /// Treat undefined chunk references as errors.
     pub strict: bool,
+    /// Build profile that `@if(profile=…)` chunk guards are evaluated against.
+    pub profile: Option<String>,
     /// Warn about defined-but-unused chunks.
     pub warn_unused: bool,
     /// Allow `%%env(NAME)` builtins to read environment variables.
@@ -122,6 +171,12 @@ pub struct SinglePassArgs {
     pub allow_home: bool,
     /// Overwrite generated files even if they were externally modified.
     pub force_generated: bool,
+    /// Delete orphaned generated files that still match their baseline.
+    pub prune: bool,
+    /// Compare outputs with `gen/` instead of writing them; touch no files.
+    pub check: bool,
+    /// Three-way merge hand-edited generated files instead of refusing them.
+    pub merge: bool,
     /// Macro sigil character (default `%%`).
     pub sigil: char,
     /// Path separator-separated include search paths.
@@ -134,9 +189,38 @@ pub struct SinglePassArgs {
     pub dump_expanded:  bool,
     /// Override project root (defaults to CWD).
     pub project_root:   Option<PathBuf>,
+    /// `name` of the `[[pass]]` this run belongs to, recorded with its
+    /// outputs; not part of the pass key.
+    pub pass_name:      Option<String>,
 }
 
 impl SinglePassArgs {
+    /// The pass's expanded outputs: `projections`, or the single projection
+    /// described by `macro_prelude`, `expanded_ext`, the expanded dirs and
+    /// `macro_only`.
+    pub fn effective_projections(&self) -> Vec<Projection> {
+        if !self.projections.is_empty() {
+            return self.projections.clone();
+        }
+        let expanded_ext = self.expanded_ext.as_deref();
+        let expanded_dir = if is_markdown_ext(expanded_ext) {
+            &self.expanded_md_dir
+        } else {
+            &self.expanded_adoc_dir
+        };
+        vec![Projection {
+            macro_prelude: self.macro_prelude.clone(),
+            expanded_ext: self.expanded_ext.clone(),
+            expanded_dir: (self.macro_only || expanded_ext.is_some()).then(|| expanded_dir.clone()),
+            tangle: !self.macro_only,
+        }]
+    }
+
+    /// Whether the pass tangles anything into `gen_dir`.
+    pub fn tangles(&self) -> bool {
+        self.effective_projections().iter().any(|p| p.tangle)
+    }
+
     #[cfg(test)]
     pub fn default_for_test() -> Self {
         Self {
@@ -155,21 +239,27 @@ impl SinglePassArgs {
             expanded_adoc_dir: PathBuf::from("expanded-adoc"),
             expanded_md_dir: PathBuf::from("expanded-md"),
             macro_only: false,
+            projections: vec![],
             dry_run: false,
             db: PathBuf::new(),
             depfile: None,
             stamp: None,
             strict: false,
+            profile: None,
             warn_unused: false,
             allow_env: false,
             allow_home: true,
             force_generated: false,
+            prune: false,
+            check: false,
+            merge: false,
             sigil: '%',
             include: String::new(),
             formatter: vec![],
             no_fts: true,
             dump_expanded: false,
             project_root: None,
+            pass_name: None,
         }
     }
 }
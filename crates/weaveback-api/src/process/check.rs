@@ -0,0 +1,137 @@
+// weaveback-api/src/process/check.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use similar::TextDiff;
+use weaveback_tangle::db::WeavebackDb;
+
+use super::args::ProcessError;
+use super::orphans::OrphanedOutput;
+
+/// Why an output fails `--check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriftKind {
+    /// The sources now produce different content than the file on disk.
+    Stale,
+    /// The file on disk was edited since it was last generated.
+    ModifiedExternally,
+    /// The file's `@file` chunk no longer exists.
+    Orphaned,
+}
+
+impl DriftKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            DriftKind::Stale => "stale",
+            DriftKind::ModifiedExternally => "modified externally",
+            DriftKind::Orphaned => "orphaned",
+        }
+    }
+}
+
+/// An output whose on-disk content differs from what a real run would write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftedOutput {
+    /// Path as shown to the user (under `--gen`, or the expanded document).
+    pub path: PathBuf,
+    pub kind: DriftKind,
+    /// Unified diff from the on-disk content to the expected content.
+    pub diff: String,
+}
+
+/// Compare the outputs staged in check mode with `gen/` and the baselines
+/// recorded in `prev_db`.
+pub fn check_generated_outputs(
+    staged: &BTreeMap<String, Vec<u8>>,
+    prev_db: &Option<WeavebackDb>,
+    gen_base: &Path,
+    gen_dir: &Path,
+) -> Result<Vec<DriftedOutput>, ProcessError> {
+    let mut drift = Vec::new();
+    for (key, expected) in staged {
+        let current = read_if_exists(&gen_base.join(key))?;
+        if current.as_deref() == Some(expected.as_slice()) {
+            continue;
+        }
+        let baseline = prev_db
+            .as_ref()
+            .and_then(|db| db.get_baseline(key).ok().flatten());
+        let kind = match (&current, &baseline) {
+            (Some(current), Some(baseline)) if current != baseline => {
+                DriftKind::ModifiedExternally
+            }
+            _ => DriftKind::Stale,
+        };
+        let path = gen_dir.join(key);
+        let diff = unified_diff(&path, current.as_deref(), Some(expected));
+        drift.push(DriftedOutput { path, kind, diff });
+    }
+    Ok(drift)
+}
+
+/// Report orphans that still exist on disk as drift.
+pub fn check_orphans(
+    orphans: &[OrphanedOutput],
+    gen_base: &Path,
+    gen_dir: &Path,
+) -> Result<Vec<DriftedOutput>, ProcessError> {
+    let mut drift = Vec::new();
+    for orphan in orphans {
+        let Some(current) = read_if_exists(&gen_base.join(&orphan.path))? else {
+            continue;
+        };
+        let path = gen_dir.join(&orphan.path);
+        let diff = unified_diff(&path, Some(&current), None);
+        drift.push(DriftedOutput { path, kind: DriftKind::Orphaned, diff });
+    }
+    Ok(drift)
+}
+
+/// Compare one expanded document with the copy on disk.
+pub(super) fn check_expanded_document(
+    out_path: PathBuf,
+    expected: &str,
+) -> Result<Option<DriftedOutput>, ProcessError> {
+    let current = read_if_exists(&out_path)?;
+    if current.as_deref() == Some(expected.as_bytes()) {
+        return Ok(None);
+    }
+    let diff = unified_diff(&out_path, current.as_deref(), Some(expected.as_bytes()));
+    Ok(Some(DriftedOutput { path: out_path, kind: DriftKind::Stale, diff }))
+}
+
+/// Print every drifted output and fail the pass if there is any.
+pub fn finish_check(drift: &[DriftedOutput]) -> Result<(), ProcessError> {
+    for d in drift {
+        eprintln!("{}: {}", d.kind.label(), d.path.display());
+        print!("{}", d.diff);
+    }
+    if drift.is_empty() {
+        Ok(())
+    } else {
+        Err(ProcessError::OutOfDate { count: drift.len() })
+    }
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>, ProcessError> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Unified diff from `old` to `new`; `None` stands for an absent file.
+fn unified_diff(path: &Path, old: Option<&[u8]>, new: Option<&[u8]>) -> String {
+    let old_text = old.map(String::from_utf8_lossy).unwrap_or_default();
+    let new_text = new.map(String::from_utf8_lossy).unwrap_or_default();
+    let shown = path.display();
+    let old_header = if old.is_some() { format!("a/{shown}") } else { "/dev/null".to_string() };
+    let new_header = if new.is_some() { format!("b/{shown}") } else { "/dev/null".to_string() };
+    TextDiff::from_lines(old_text.as_ref(), new_text.as_ref())
+        .unified_diff()
+        .header(&old_header, &new_header)
+        .to_string()
+}
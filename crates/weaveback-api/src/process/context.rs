@@ -0,0 +1,63 @@
+// weaveback-api/src/process/context.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use weaveback_macro::evaluator::{EvalConfig, Evaluator};
+
+use super::args::ProcessError;
+use super::macro_prelude::evaluate_macro_preludes;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct PreludeKey {
+    preludes: Vec<PathBuf>,
+    sigil: char,
+    include_paths: Vec<PathBuf>,
+    allow_env: bool,
+}
+
+/// State shared by tangle passes running in the same process.
+#[derive(Default)]
+pub struct PassContext {
+    preludes: Mutex<HashMap<PreludeKey, Evaluator>>,
+    db_write: Mutex<()>,
+}
+
+impl PassContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A fresh evaluator for `config` with `preludes` already evaluated.
+    pub fn evaluator(
+        &self,
+        config: &EvalConfig,
+        preludes: &[PathBuf],
+    ) -> Result<Evaluator, ProcessError> {
+        let key = PreludeKey {
+            preludes: preludes.to_vec(),
+            sigil: config.sigil,
+            include_paths: config.include_paths.clone(),
+            allow_env: config.allow_env,
+        };
+        if let Some(cached) = self.lock_preludes().get(&key) {
+            return Ok(cached.clone());
+        }
+        // Evaluate outside the lock so passes with other preludes are not held up.
+        let mut evaluator = Evaluator::new(config.clone());
+        evaluate_macro_preludes(&mut evaluator, preludes)?;
+        self.lock_preludes().insert(key, evaluator.clone());
+        Ok(evaluator)
+    }
+
+    /// Serialize writes to the persistent database across passes.
+    pub fn lock_db(&self) -> MutexGuard<'_, ()> {
+        self.db_write.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_preludes(&self) -> MutexGuard<'_, HashMap<PreludeKey, Evaluator>> {
+        self.preludes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
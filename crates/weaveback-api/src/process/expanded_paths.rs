@@ -4,7 +4,6 @@
 use std::path::{Path, PathBuf};
 
 use super::args::ProcessError;
-use super::markdown_normalize::is_markdown_ext;
 
 
 fn with_replaced_extension(path: &Path, expanded_ext: Option<&str>) -> PathBuf {
@@ -28,7 +27,7 @@ pub(super) fn expanded_source_key(
     }
 }
 
-fn expanded_output_path(
+pub(super) fn expanded_output_path(
     full_path: &Path,
     base_dir: &Path,
     expanded_dir: &Path,
@@ -44,25 +43,15 @@ fn expanded_output_path(
     expanded_dir.join(with_replaced_extension(&rel, expanded_ext))
 }
 
-pub(super) fn write_expanded_document(
-    full_path: &Path,
-    base_dir: &Path,
-    expanded_adoc_dir: &Path,
-    expanded_md_dir: &Path,
-    expanded_ext: Option<&str>,
-    expanded: &str,
-) -> Result<PathBuf, ProcessError> {
-    let expanded_dir = if is_markdown_ext(expanded_ext) { expanded_md_dir } else { expanded_adoc_dir };
-    let out_path = expanded_output_path(full_path, base_dir, expanded_dir, expanded_ext);
+pub(super) fn write_expanded_document(out_path: &Path, expanded: &str) -> Result<(), ProcessError> {
     if let Some(parent) = out_path.parent() {
         std::fs::create_dir_all(parent).map_err(|source| ProcessError::ExpandedWrite {
-            path: out_path.clone(),
+            path: out_path.to_path_buf(),
             source,
         })?;
     }
-    std::fs::write(&out_path, expanded).map_err(|source| ProcessError::ExpandedWrite {
-        path: out_path.clone(),
+    std::fs::write(out_path, expanded).map_err(|source| ProcessError::ExpandedWrite {
+        path: out_path.to_path_buf(),
         source,
-    })?;
-    Ok(out_path)
+    })
 }
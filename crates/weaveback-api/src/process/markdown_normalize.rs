@@ -1,7 +1,7 @@
 // weaveback-api/src/process/markdown_normalize.rs
 // I'd Really Rather You Didn't edit this generated file.
 
-pub(super) fn is_markdown_ext(expanded_ext: Option<&str>) -> bool {
+pub(crate) fn is_markdown_ext(expanded_ext: Option<&str>) -> bool {
     matches!(
         expanded_ext.unwrap_or_default().trim_start_matches('.'),
         "md" | "markdown"
@@ -0,0 +1,141 @@
+// weaveback-api/src/process/orphans.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use weaveback_tangle::Clip;
+use weaveback_tangle::db::WeavebackDb;
+
+use super::args::{ProcessError, SinglePassArgs};
+
+/// On-disk state of a generated file whose `@file` chunk disappeared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanStatus {
+    /// The file still matches its stored baseline.
+    Unchanged,
+    /// The file differs from its baseline, or there is no baseline.
+    ModifiedExternally,
+    /// The file no longer exists.
+    Missing,
+}
+
+/// A generated file produced by the previous run of a pass but not this one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanedOutput {
+    /// Path relative to the pass's `gen` directory (the baseline key).
+    pub path: String,
+    pub status: OrphanStatus,
+    /// Whether the file and its database rows were removed in this run.
+    pub pruned: bool,
+    /// `name` of the pass that owns the file: the configured one, or the
+    /// one its last run recorded.
+    pub pass_name: Option<String>,
+}
+
+/// Stable key identifying a pass in `pass_outputs`.
+pub fn pass_key(args: &SinglePassArgs) -> String {
+    let sources = match &args.directory {
+        Some(dir) => dir.to_string_lossy().into_owned(),
+        None => args
+            .inputs
+            .iter()
+            .map(|p| args.input_dir.join(p).to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join(","),
+    };
+    format!("{sources}|{}|{}", args.ext.join(","), args.gen_dir.to_string_lossy())
+}
+
+/// Compare `produced` with the outputs recorded for `pass_key` in `prev_db`,
+/// classify every orphan, and (when `prune` is set) stage the ones that are
+/// safe to delete with `clip`, whose `finish` deletes them in the same
+/// commit as the generated files.
+///
+/// Records the new output set and the pruned orphans in the clip's database
+/// so that `merge_into` can update the persistent database atomically.
+pub fn prune_orphans(
+    pass_key: &str,
+    pass_name: Option<&str>,
+    produced: &[String],
+    prev_db: &Option<WeavebackDb>,
+    clip: &mut Clip,
+    prune: bool,
+) -> Result<Vec<OrphanedOutput>, ProcessError> {
+    let Some(prev) = prev_db.as_ref() else {
+        clip.db_mut().set_pass_outputs(pass_key, pass_name, produced)?;
+        return Ok(Vec::new());
+    };
+    let gen_base = clip.gen_base().to_path_buf();
+    let owner = match pass_name {
+        Some(name) => Some(name.to_string()),
+        None => prev.get_pass_name(pass_key).ok().flatten(),
+    };
+
+    let produced_set: HashSet<&str> = produced.iter().map(String::as_str).collect();
+    let mut recorded: Vec<String> = produced.to_vec();
+    let mut orphans = Vec::new();
+
+    for path in prev.get_pass_outputs(pass_key).unwrap_or_default() {
+        if produced_set.contains(path.as_str()) {
+            continue;
+        }
+        let on_disk = gen_base.join(&path);
+        let status = if !on_disk.is_file() {
+            OrphanStatus::Missing
+        } else {
+            match prev.get_baseline(&path).ok().flatten() {
+                Some(baseline) if std::fs::read(&on_disk)? == baseline => OrphanStatus::Unchanged,
+                _ => OrphanStatus::ModifiedExternally,
+            }
+        };
+
+        let pruned = prune && status != OrphanStatus::ModifiedExternally;
+        if pruned {
+            if status == OrphanStatus::Unchanged {
+                clip.remove_output(&on_disk)?;
+            }
+            clip.db_mut().record_pruned_output(pass_key, &path, &on_disk.to_string_lossy())?;
+        } else {
+            recorded.push(path.clone());
+        }
+        orphans.push(OrphanedOutput { path, status, pruned, pass_name: owner.clone() });
+    }
+
+    clip.db_mut().set_pass_outputs(pass_key, pass_name, &recorded)?;
+    Ok(orphans)
+}
+
+/// Print one line per orphan to stderr.
+pub fn report_orphans(orphans: &[OrphanedOutput], gen_dir: &Path) {
+    for o in orphans {
+        eprintln!("{}", describe_orphan(o, gen_dir));
+    }
+}
+
+/// The report line of one orphan, prefixed with its pass's name if it has one.
+pub fn describe_orphan(o: &OrphanedOutput, gen_dir: &Path) -> String {
+    let shown = gen_dir.join(&o.path);
+    let shown = shown.display();
+    let text = match (o.status, o.pruned) {
+        (OrphanStatus::Unchanged, true) => format!("pruned orphaned generated file {shown}"),
+        (OrphanStatus::Unchanged, false) => {
+            format!("orphaned generated file {shown} (use --prune to delete it)")
+        }
+        (OrphanStatus::Missing, true) => {
+            format!("dropped records of deleted generated file {shown}")
+        }
+        (OrphanStatus::Missing, false) => format!(
+            "orphaned generated file {shown} is already deleted \
+             (use --prune to drop its records)"
+        ),
+        (OrphanStatus::ModifiedExternally, _) => format!(
+            "orphaned generated file {shown} was modified externally; \
+             keeping it"
+        ),
+    };
+    match &o.pass_name {
+        Some(name) => format!("{name}: {text}"),
+        None => text,
+    }
+}
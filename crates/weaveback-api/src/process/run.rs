@@ -5,24 +5,67 @@ use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 use weaveback_macro::evaluator::{EvalConfig, Evaluator};
-use weaveback_macro::macro_api::{discover_includes_in_string, process_string};
+use weaveback_macro::macro_api::{
+    ParsedSource, discover_includes_in_parsed, parse_source, process_parsed,
+};
 use weaveback_tangle::{Clip, SafeFileWriter, SafeWriterConfig, WeavebackError};
+use weaveback_tangle::{FrontMatter, parse_front_matter};
 use weaveback_tangle::db::WeavebackDb;
+use weaveback_tangle::safe_writer::recover_commit;
 
-use super::args::{ProcessError, SinglePassArgs};
-use super::expanded_paths::{expanded_source_key, write_expanded_document};
+use super::args::{ProcessError, Projection, SinglePassArgs};
+use super::check::{check_expanded_document, check_generated_outputs, check_orphans, finish_check};
+use super::expanded_paths::{expanded_output_path, expanded_source_key, write_expanded_document};
+use super::context::PassContext;
 use super::fs::{find_files, write_depfile};
-use super::macro_prelude::evaluate_macro_preludes;
 use super::markdown_normalize::normalize_expanded_document;
+use super::merge::report_merges;
+use super::orphans::{pass_key, prune_orphans, report_orphans};
 use super::skip::compute_skip_set;
 
+/// Every file any projection's evaluator read, in first-seen order.
+fn evaluated_files(evaluators: &[Evaluator]) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    evaluators
+        .iter()
+        .flat_map(|e| e.source_files())
+        .filter(|p| seen.insert(*p))
+        .cloned()
+        .collect()
+}
+
+fn read_front_matter(path: &std::path::Path, text: &str) -> Result<FrontMatter, ProcessError> {
+    parse_front_matter(text).map_err(|source| ProcessError::FrontMatter {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
 /// Run one tangle pass with the given arguments.
 ///
 /// Returns `Err` on file I/O errors, macro evaluation failures, or tangle
 /// errors.  Caller is responsible for printing a human-readable error.
 pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
+    run_single_pass_with(args, &PassContext::new())
+}
+
+/// Run one tangle pass, sharing evaluated preludes and the database write
+/// lock with other passes that use the same `ctx`.
+pub fn run_single_pass_with(args: SinglePassArgs, ctx: &PassContext) -> Result<(), ProcessError> {
     let project_root = args.project_root.clone().unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
-    let _db = WeavebackDb::open(&args.db)?;
+    let _db = if args.check {
+        if args.db.exists() {
+            // Refuse an un-migrated database rather than checking without its records.
+            WeavebackDb::open_read_only(&args.db)?;
+        }
+        None
+    } else {
+        let _lock = ctx.lock_db();
+        if let Some(recovery) = recover_commit(&args.db).map_err(WeavebackError::from)? {
+            eprintln!("{}: {recovery}", args.db.display());
+        }
+        Some(WeavebackDb::open(&args.db)?)
+    };
     let pathsep: String = if cfg!(windows) { ";".to_string() } else { ":".to_string() };
     let include_paths: Vec<PathBuf> = args.include.split(&pathsep).map(PathBuf::from).collect();
 
@@ -32,10 +75,31 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
         allow_env: args.allow_env,
         ..EvalConfig::default()
     };
-    let mut evaluator = Evaluator::new(eval_config.clone());
-    if !args.no_macros {
-        evaluate_macro_preludes(&mut evaluator, &args.macro_prelude)?;
+    let projections = args.effective_projections();
+    if !args.projections.is_empty() {
+        let tangled = projections.iter().filter(|p| p.tangle).count();
+        if tangled > 1 {
+            return Err(ProcessError::Projections {
+                reason: format!("{tangled} projections set `tangle`; at most one may"),
+            });
+        }
+        if args.no_macros {
+            return Err(ProcessError::Projections {
+                reason: "projections need macro expansion, but `no_macros` is set".to_string(),
+            });
+        }
     }
+    let tangles = projections.iter().any(|p| p.tangle);
+    let primary = Projection::primary(&projections);
+    let discovery_prelude = primary.map_or(&[][..], |p| p.macro_prelude.as_slice());
+    let mut evaluators: Vec<Evaluator> = if args.no_macros {
+        Vec::new()
+    } else {
+        projections
+            .iter()
+            .map(|p| ctx.evaluator(&eval_config, &p.macro_prelude))
+            .collect::<Result<_, _>>()?
+    };
 
     let comment_markers: Vec<String> = args
         .comment_markers
@@ -49,15 +113,21 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
         .filter_map(|s| s.split_once('=').map(|(e, c)| (e.to_string(), c.to_string())))
         .collect();
 
-    let safe_writer = SafeFileWriter::with_config(
+    let mut safe_writer = SafeFileWriter::with_config(
         &args.gen_dir,
         SafeWriterConfig {
             formatters,
             allow_home: args.allow_home,
             force_generated: args.force_generated,
+            check: args.check,
+            merge: args.merge,
+            atomic_commit: true,
             ..SafeWriterConfig::default()
         },
     ).map_err(WeavebackError::from)?;
+    if args.db.exists() && let Ok(prior) = WeavebackDb::open_read_only(&args.db) {
+        safe_writer.set_prior_db(prior);
+    }
     let mut clip = Clip::new(
         safe_writer,
         &args.open_delim,
@@ -67,7 +137,10 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
     );
     clip.set_strict_undefined(args.strict);
     clip.set_warn_unused(args.warn_unused);
+    clip.set_profile(args.profile.clone());
+    clip.set_project_root(&project_root);
 
+    let mut parsed_sources: HashMap<PathBuf, ParsedSource> = HashMap::new();
     let (drivers, all_adoc): (Vec<PathBuf>, Vec<PathBuf>) = if let Some(ref dir) = args.directory {
         let mut all = Vec::new();
         find_files(dir, &args.ext, &mut all)?;
@@ -76,15 +149,23 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
         let mut included: HashSet<PathBuf> = HashSet::new();
         for adoc in &all {
             if let Ok(text) = std::fs::read_to_string(adoc) {
-                let mut disc = Evaluator::new(eval_config.clone());
-                if evaluate_macro_preludes(&mut disc, &args.macro_prelude).is_err() {
+                let Ok(front_matter) = parse_front_matter(&text) else {
                     continue;
-                }
-                if let Ok(paths) = discover_includes_in_string(&text, Some(adoc), &mut disc) {
+                };
+                let sigil = front_matter.sigil.unwrap_or(args.sigil);
+                let Ok(parsed) = parse_source(&text, adoc, sigil) else {
+                    continue;
+                };
+                let Ok(mut disc) = ctx.evaluator(&eval_config, discovery_prelude) else {
+                    continue;
+                };
+                disc.set_sigil(sigil);
+                if let Ok(paths) = discover_includes_in_parsed(&parsed, &mut disc) {
                     for p in paths {
                         included.insert(p.canonicalize().unwrap_or(p));
                     }
                 }
+                parsed_sources.insert(adoc.clone(), parsed);
             }
         }
         let drivers = all
@@ -114,57 +195,76 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
             p.to_string_lossy().to_string()
         }
     };
-    let expanded_ext = args.expanded_ext.as_deref();
+    let key_ext = primary.and_then(|p| p.expanded_ext.as_deref());
+    let base_dir = args.directory.as_deref().unwrap_or(&args.input_dir);
 
     let mut source_contents: HashMap<String, String> = HashMap::new();
+    let mut drift = Vec::new();
     for full_path in &drivers {
-        let content = std::fs::read_to_string(full_path)?;
         let src_key = if args.no_macros {
             normalize_path(full_path)
         } else {
-            expanded_source_key(full_path, &project_root, expanded_ext)
+            expanded_source_key(full_path, &project_root, key_ext)
         };
 
-        let tangle_cfg = weaveback_tangle::db::TangleConfig {
+        let pass_cfg = weaveback_tangle::db::TangleConfig {
             sigil: args.sigil,
             open_delim: args.open_delim.clone(),
             close_delim: args.close_delim.clone(),
             chunk_end: args.chunk_end.clone(),
             comment_markers: comment_markers.clone(),
         };
-        clip.db().set_source_config(&src_key, &tangle_cfg)?;
 
         if args.no_macros {
+            let content = std::fs::read_to_string(full_path)?;
+            let tangle_cfg = read_front_matter(full_path, &content)?.apply(&pass_cfg);
+            clip.db().set_source_config(&src_key, &tangle_cfg)?;
             source_contents.insert(src_key.clone(), content.clone());
             clip.read(&content, &src_key);
-        } else {
-            let expanded = process_string(&content, Some(full_path), &mut evaluator)?;
+            continue;
+        }
+
+        let parsed = match parsed_sources.remove(full_path) {
+            Some(parsed) => parsed,
+            None => {
+                let text = std::fs::read_to_string(full_path)?;
+                let sigil = read_front_matter(full_path, &text)?.sigil.unwrap_or(args.sigil);
+                parse_source(&text, full_path, sigil)?
+            }
+        };
+        let tangle_cfg = read_front_matter(full_path, parsed.text())?.apply(&pass_cfg);
+        clip.db().set_source_config(&src_key, &tangle_cfg)?;
+        for (projection, evaluator) in projections.iter().zip(evaluators.iter_mut()) {
+            let expanded_ext = projection.expanded_ext.as_deref();
+            let pass_sigil = evaluator.set_sigil(tangle_cfg.sigil);
+            let expanded = process_parsed(&parsed, evaluator);
+            evaluator.set_sigil(pass_sigil);
+            let expanded = expanded?;
             let expanded_str = normalize_expanded_document(expanded_ext, &expanded);
             if args.dump_expanded {
-                eprintln!("=== expanded: {} ===", src_key);
+                let key = expanded_source_key(full_path, &project_root, expanded_ext);
+                eprintln!("=== expanded: {} ===", key);
                 eprintln!("{}", expanded_str);
-                eprintln!("=== end: {} ===", src_key);
+                eprintln!("=== end: {} ===", key);
             }
-            if args.macro_only || args.expanded_ext.is_some() {
-                let base_dir = args.directory.as_deref().unwrap_or(&args.input_dir);
-                write_expanded_document(
-                    full_path,
-                    base_dir,
-                    &args.expanded_adoc_dir,
-                    &args.expanded_md_dir,
-                    expanded_ext,
-                    &expanded_str,
-                )?;
+            if let Some(expanded_dir) = &projection.expanded_dir {
+                let out_path = expanded_output_path(full_path, base_dir, expanded_dir, expanded_ext);
+                if args.check {
+                    drift.extend(check_expanded_document(out_path, &expanded_str)?);
+                } else {
+                    write_expanded_document(&out_path, &expanded_str)?;
+                }
             }
-            if args.macro_only {
+            let var_defs = evaluator.drain_var_defs();
+            let macro_defs = evaluator.drain_macro_defs();
+            let macro_calls = evaluator.drain_macro_calls();
+            if !projection.tangle {
                 continue;
             }
             source_contents.insert(src_key.clone(), expanded_str.clone());
             clip.read(&expanded_str, &src_key);
 
             let src_files = evaluator.sources().source_files().to_vec();
-            let var_defs = evaluator.drain_var_defs();
-            let macro_defs = evaluator.drain_macro_defs();
             for vd in var_defs {
                 if let Some(path) = src_files.get(vd.src as usize) {
                     let k = normalize_path(path);
@@ -177,6 +277,12 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
                     clip.db().record_macro_def(&md.macro_name, &k, md.pos, md.length)?;
                 }
             }
+            for mc in macro_calls {
+                if let Some(path) = src_files.get(mc.src as usize) {
+                    let k = normalize_path(path);
+                    clip.db().record_macro_call(&mc.macro_name, &k, mc.pos, mc.length)?;
+                }
+            }
         }
     }
 
@@ -187,8 +293,40 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
         return Ok(());
     }
 
-    if args.macro_only {
-        return Ok(());
+    if !tangles {
+        return if args.check { finish_check(&drift) } else { Ok(()) };
+    }
+
+    // Check mode: stage every output (no skip set), compare, and stop before
+    // anything is written or merged.
+    if args.check {
+        clip.write_files_incremental(&HashSet::new())?;
+        let gen_base = clip.gen_base().to_path_buf();
+        let orphans = prune_orphans(
+            &pass_key(&args),
+            args.pass_name.as_deref(),
+            &clip.output_file_keys(),
+            &prev_db,
+            &mut clip,
+            false,
+        )?;
+        drift.extend(check_generated_outputs(
+            clip.staged_outputs(),
+            &prev_db,
+            &gen_base,
+            &args.gen_dir,
+        )?);
+        drift.extend(check_orphans(&orphans, &gen_base, &args.gen_dir)?);
+        return finish_check(&drift);
+    }
+
+    // Files transcluded with `<<@from …>>` are hashed like sources so that an
+    // edit in one of their regions dirties the chunks that use it.
+    let transcluded = clip.transcluded_files();
+    for path in &transcluded {
+        if let Ok(content) = std::fs::read_to_string(project_root.join(path)) {
+            source_contents.entry(path.clone()).or_insert(content);
+        }
     }
 
     let skip_set = if args.force_generated {
@@ -197,12 +335,24 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
         compute_skip_set(&source_contents, &prev_db, clip.db_mut(), &args.gen_dir)
     };
     clip.write_files_incremental(&skip_set)?;
+    let conflicted = report_merges(clip.merged_outputs());
+
+    let produced = clip.output_file_keys();
+    let orphans = prune_orphans(
+        &pass_key(&args),
+        args.pass_name.as_deref(),
+        &produced,
+        &prev_db,
+        &mut clip,
+        args.prune,
+    )?;
+    report_orphans(&orphans, &args.gen_dir);
 
     {
         let paths: Vec<PathBuf> = if args.no_macros {
             drivers.clone()
         } else {
-            evaluator.source_files().to_vec()
+            evaluated_files(&evaluators)
         };
         for path in &paths {
             if let Ok(content) = std::fs::read(path) {
@@ -212,28 +362,35 @@ pub fn run_single_pass(args: SinglePassArgs) -> Result<(), ProcessError> {
         }
     }
 
+    let db_lock = ctx.lock_db();
     clip.finish(&args.db)?;
 
     // Re-open for final configs and FTS rebuild
     if let Ok(mut db) = weaveback_tangle::db::WeavebackDb::open(&args.db) {
         let _ = db.set_run_config("gen_dir", &args.gen_dir.to_string_lossy());
-        if !args.no_fts && let Err(e) = db.rebuild_prose_fts(Some(&project_root)) {
+        if !args.no_fts && let Err(e) = db.rebuild_fts(Some(&project_root)) {
             eprintln!("warning: FTS index rebuild failed: {e}");
         }
     }
+    drop(db_lock);
 
     if let Some(ref depfile_path) = args.depfile {
-        let deps: Vec<PathBuf> = if args.directory.is_some() {
+        let mut deps: Vec<PathBuf> = if args.directory.is_some() {
             all_adoc
         } else if args.no_macros {
             drivers
         } else {
-            evaluator.source_files().to_vec()
+            evaluated_files(&evaluators)
         };
+        deps.extend(transcluded.iter().map(|path| project_root.join(path)));
         let stamp_path = args.stamp.clone().unwrap_or_else(|| depfile_path.clone());
         write_depfile(depfile_path, &stamp_path, &deps)?;
     }
 
+    if conflicted > 0 {
+        return Err(ProcessError::MergeConflicts { count: conflicted });
+    }
+
     if let Some(ref stamp_path) = args.stamp {
         std::fs::write(stamp_path, b"")?;
     }
@@ -27,6 +27,7 @@ pub fn compute_skip_set(
         .collect();
 
     let mut dirty_chunks: HashSet<String> = HashSet::new();
+    let mut any_changed = false;
 
     for (path, new_blocks) in &parsed {
         if let Err(e) = current_db.set_source_blocks(path, new_blocks) {
@@ -48,6 +49,7 @@ pub fn compute_skip_set(
                 .map(|old| old.as_slice() != blk.content_hash.as_slice())
                 .unwrap_or(true);
 
+            any_changed |= changed;
             if changed
                 && let Some(db) = prev
                 && let Ok(chunk_defs) = db.query_chunk_defs_overlapping(path, blk.line_start, blk.line_end) {
@@ -58,6 +60,17 @@ pub fn compute_skip_set(
         }
     }
 
+    if any_changed
+        && let Some(db) = prev_db.as_ref()
+        && let Ok(deps) = db.list_all_chunk_deps()
+    {
+        for (from_chunk, to_chunk, _src_file) in deps {
+            if weaveback_tangle::is_collection_reference(&to_chunk) {
+                dirty_chunks.insert(from_chunk);
+            }
+        }
+    }
+
     if let Some(db) = prev_db.as_ref() {
         let mut queue: Vec<String> = dirty_chunks.iter().cloned().collect();
         while let Some(chunk) = queue.pop() {
@@ -1,7 +1,11 @@
 // weaveback-api/src/process/tests.rs
 // I'd Really Rather You Didn't edit this generated file.
 
+mod check;
+mod context;
 mod filesystem;
+mod merge;
+mod orphans;
 mod run_basic;
 mod run_macros;
 mod skip;
@@ -0,0 +1,131 @@
+// weaveback-api/src/process/tests/check.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::super::{
+    DriftKind, ProcessError, SinglePassArgs, check_generated_outputs, run_single_pass,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+use weaveback_tangle::db::WeavebackDb;
+
+fn check_args(root: &Path, check: bool) -> SinglePassArgs {
+    SinglePassArgs {
+        inputs: vec![PathBuf::from("input.adoc")],
+        input_dir: root.to_path_buf(),
+        gen_dir: root.join("gen"),
+        db: root.join("wb.db"),
+        check,
+        no_fts: true,
+        ..SinglePassArgs::default_for_test()
+    }
+}
+
+fn out_of_date_count(result: Result<(), ProcessError>) -> usize {
+    match result {
+        Err(ProcessError::OutOfDate { count }) => count,
+        other => panic!("expected OutOfDate, got {other:?}"),
+    }
+}
+
+#[test]
+fn check_passes_when_outputs_are_up_to_date() {
+    let tmp = tempdir().unwrap();
+    fs::write(tmp.path().join("input.adoc"), "<<@file out.txt>>=\nhello\n@\n").unwrap();
+    run_single_pass(check_args(tmp.path(), false)).unwrap();
+
+    run_single_pass(check_args(tmp.path(), true)).unwrap();
+}
+
+#[test]
+fn check_fails_on_stale_output_without_writing() {
+    let tmp = tempdir().unwrap();
+    fs::write(tmp.path().join("input.adoc"), "<<@file out.txt>>=\nhello\n@\n").unwrap();
+    run_single_pass(check_args(tmp.path(), false)).unwrap();
+    fs::write(tmp.path().join("input.adoc"), "<<@file out.txt>>=\nchanged\n@\n").unwrap();
+
+    assert_eq!(out_of_date_count(run_single_pass(check_args(tmp.path(), true))), 1);
+
+    assert_eq!(fs::read_to_string(tmp.path().join("gen/out.txt")).unwrap(), "hello\n");
+    let db = WeavebackDb::open_read_only(tmp.path().join("wb.db")).unwrap();
+    assert_eq!(db.get_baseline("out.txt").unwrap().as_deref(), Some("hello\n".as_bytes()));
+}
+
+#[test]
+fn check_on_fresh_tree_creates_nothing() {
+    let tmp = tempdir().unwrap();
+    fs::write(
+        tmp.path().join("input.adoc"),
+        "<<@file a.txt>>=\na\n@\n<<@file sub/b.txt>>=\nb\n@\n",
+    )
+    .unwrap();
+
+    assert_eq!(out_of_date_count(run_single_pass(check_args(tmp.path(), true))), 2);
+
+    assert!(!tmp.path().join("gen").exists());
+    assert!(!tmp.path().join("wb.db").exists());
+}
+
+#[test]
+fn check_reports_orphan_and_keeps_it() {
+    let tmp = tempdir().unwrap();
+    fs::write(
+        tmp.path().join("input.adoc"),
+        "<<@file keep.txt>>=\nkeep\n@\n<<@file old.txt>>=\nold\n@\n",
+    )
+    .unwrap();
+    run_single_pass(check_args(tmp.path(), false)).unwrap();
+    fs::write(tmp.path().join("input.adoc"), "<<@file keep.txt>>=\nkeep\n@\n").unwrap();
+
+    assert_eq!(out_of_date_count(run_single_pass(check_args(tmp.path(), true))), 1);
+
+    assert!(tmp.path().join("gen/old.txt").exists());
+}
+
+#[test]
+fn check_generated_outputs_classifies_drift() {
+    let tmp = tempdir().unwrap();
+    let gen_dir = tmp.path().join("gen");
+    fs::create_dir_all(&gen_dir).unwrap();
+    fs::write(gen_dir.join("same.txt"), "same\n").unwrap();
+    fs::write(gen_dir.join("stale.txt"), "old\n").unwrap();
+    fs::write(gen_dir.join("edited.txt"), "hand edit\n").unwrap();
+    fs::write(gen_dir.join("unknown.txt"), "old\n").unwrap();
+
+    let db = WeavebackDb::open_temp().unwrap();
+    db.set_baseline("stale.txt", b"old\n").unwrap();
+    db.set_baseline("edited.txt", b"old\n").unwrap();
+    let prev_db = Some(db);
+
+    let staged: BTreeMap<String, Vec<u8>> = [
+        ("same.txt", "same\n"),
+        ("stale.txt", "new\n"),
+        ("edited.txt", "new\n"),
+        ("unknown.txt", "new\n"),
+        ("missing.txt", "new\n"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
+    .collect();
+
+    let drift = check_generated_outputs(&staged, &prev_db, &gen_dir, Path::new("gen")).unwrap();
+    let kinds: Vec<(String, DriftKind)> = drift
+        .iter()
+        .map(|d| (d.path.to_string_lossy().into_owned(), d.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("gen/edited.txt".to_string(), DriftKind::ModifiedExternally),
+            ("gen/missing.txt".to_string(), DriftKind::Stale),
+            ("gen/stale.txt".to_string(), DriftKind::Stale),
+            ("gen/unknown.txt".to_string(), DriftKind::Stale),
+        ]
+    );
+    let stale = &drift[2];
+    assert!(stale.diff.contains("--- a/gen/stale.txt"));
+    assert!(stale.diff.contains("-old"));
+    assert!(stale.diff.contains("+new"));
+    assert!(drift[1].diff.contains("--- /dev/null"));
+}
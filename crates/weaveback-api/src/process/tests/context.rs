@@ -0,0 +1,43 @@
+// weaveback-api/src/process/tests/context.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::super::{PassContext, ProcessError};
+use std::fs;
+use tempfile::tempdir;
+use weaveback_macro::evaluator::EvalConfig;
+use weaveback_macro::macro_api::process_string;
+
+#[test]
+fn evaluator_starts_from_cached_prelude() {
+    let tmp = tempdir().unwrap();
+    let prelude = tmp.path().join("prelude.txt");
+    fs::write(&prelude, "%def(greet, name, %{hello %(name)%})").unwrap();
+    let ctx = PassContext::new();
+    let config = EvalConfig::default();
+
+    let mut first = ctx.evaluator(&config, std::slice::from_ref(&prelude)).unwrap();
+    assert_eq!(process_string("%greet(a)", None, &mut first).unwrap(), b"hello a");
+
+    // The second evaluator comes from the cache, not from the deleted file,
+    // and does not see definitions made in the first one.
+    fs::remove_file(&prelude).unwrap();
+    process_string("%def(bye, bye)", None, &mut first).unwrap();
+    let mut second = ctx.evaluator(&config, std::slice::from_ref(&prelude)).unwrap();
+    assert_eq!(process_string("%greet(b)", None, &mut second).unwrap(), b"hello b");
+    assert!(process_string("%bye()", None, &mut second).is_err());
+}
+
+#[test]
+fn failed_prelude_is_reported_and_not_cached() {
+    let tmp = tempdir().unwrap();
+    let prelude = tmp.path().join("missing.txt");
+    let ctx = PassContext::new();
+    let config = EvalConfig::default();
+
+    assert!(matches!(
+        ctx.evaluator(&config, std::slice::from_ref(&prelude)),
+        Err(ProcessError::PreludeRead { .. })
+    ));
+    fs::write(&prelude, "%def(x, y)").unwrap();
+    assert!(ctx.evaluator(&config, std::slice::from_ref(&prelude)).is_ok());
+}
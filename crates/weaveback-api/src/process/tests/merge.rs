@@ -0,0 +1,88 @@
+// weaveback-api/src/process/tests/merge.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::super::{ProcessError, SinglePassArgs, run_single_pass};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+use weaveback_tangle::db::WeavebackDb;
+
+fn merge_args(root: &Path, merge: bool) -> SinglePassArgs {
+    SinglePassArgs {
+        inputs: vec![PathBuf::from("input.adoc")],
+        input_dir: root.to_path_buf(),
+        gen_dir: root.join("gen"),
+        db: root.join("wb.db"),
+        merge,
+        no_fts: true,
+        ..SinglePassArgs::default_for_test()
+    }
+}
+
+fn tangle(root: &Path, body: &str, merge: bool) -> Result<(), ProcessError> {
+    fs::write(root.join("input.adoc"), format!("<<@file out.txt>>=\n{body}@\n")).unwrap();
+    run_single_pass(merge_args(root, merge))
+}
+
+#[test]
+fn source_change_without_hand_edit_regenerates() {
+    let tmp = tempdir().unwrap();
+    tangle(tmp.path(), "one\n", false).unwrap();
+
+    tangle(tmp.path(), "two\n", false).unwrap();
+
+    assert_eq!(fs::read_to_string(tmp.path().join("gen/out.txt")).unwrap(), "two\n");
+}
+
+#[test]
+fn hand_edit_is_refused_without_merge() {
+    let tmp = tempdir().unwrap();
+    tangle(tmp.path(), "one\ntwo\nthree\n", false).unwrap();
+    fs::write(tmp.path().join("gen/out.txt"), "one\nTWO\nthree\n").unwrap();
+
+    assert!(tangle(tmp.path(), "one\ntwo\nthree\nfour\n", false).is_err());
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("gen/out.txt")).unwrap(),
+        "one\nTWO\nthree\n"
+    );
+}
+
+#[test]
+fn merge_keeps_hand_edit_and_records_tangled_baseline() {
+    let tmp = tempdir().unwrap();
+    tangle(tmp.path(), "one\ntwo\nthree\n", false).unwrap();
+    fs::write(tmp.path().join("gen/out.txt"), "one\nTWO\nthree\n").unwrap();
+
+    tangle(tmp.path(), "one\ntwo\nthree\nfour\n", true).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("gen/out.txt")).unwrap(),
+        "one\nTWO\nthree\nfour\n"
+    );
+    let db = WeavebackDb::open_read_only(tmp.path().join("wb.db")).unwrap();
+    assert_eq!(
+        db.get_baseline("out.txt").unwrap().as_deref(),
+        Some("one\ntwo\nthree\nfour\n".as_bytes())
+    );
+}
+
+#[test]
+fn merge_conflict_writes_markers_and_fails() {
+    let tmp = tempdir().unwrap();
+    tangle(tmp.path(), "one\ntwo\n", false).unwrap();
+    fs::write(tmp.path().join("gen/out.txt"), "one\nhand\n").unwrap();
+
+    match tangle(tmp.path(), "one\nsource\n", true) {
+        Err(ProcessError::MergeConflicts { count }) => assert_eq!(count, 1),
+        other => panic!("expected MergeConflicts, got {other:?}"),
+    }
+
+    let content = fs::read_to_string(tmp.path().join("gen/out.txt")).unwrap();
+    assert!(content.contains("<<<<<<< "));
+    assert!(content.contains(">>>>>>> "));
+    let db = WeavebackDb::open_read_only(tmp.path().join("wb.db")).unwrap();
+    assert_eq!(
+        db.get_baseline("out.txt").unwrap().as_deref(),
+        Some("one\nsource\n".as_bytes())
+    );
+}
@@ -32,21 +32,27 @@ fn run_single_pass_writes_output_file() {
         expanded_adoc_dir: PathBuf::from("expanded-adoc"),
         expanded_md_dir: PathBuf::from("expanded-md"),
         macro_only: false,
+        projections: vec![],
         dry_run: false,
         db: db_path,
         depfile: None,
         stamp: None,
         strict: false,
+        profile: None,
         warn_unused: false,
         allow_env: false,
         allow_home: true,
         force_generated: false,
+        prune: false,
+        check: false,
+        merge: false,
         sigil: '%',
         include: String::new(),
         formatter: vec![],
         no_fts: true,
         dump_expanded: false,
         project_root: None,
+        pass_name: None,
     };
     run_single_pass(args).unwrap();
     let out = fs::read_to_string(gen_dir.join("output.txt")).unwrap();
@@ -80,21 +86,27 @@ fn test_run_single_pass_force_generated() {
         expanded_adoc_dir: PathBuf::from("expanded-adoc"),
         expanded_md_dir: PathBuf::from("expanded-md"),
         macro_only: false,
+        projections: vec![],
         dry_run: false,
         db: db_path,
         depfile: None,
         stamp: None,
         strict: false,
+        profile: None,
         warn_unused: false,
         allow_env: false,
         allow_home: true,
         force_generated: true, // Force!
+        prune: false,
+        check: false,
+        merge: false,
         sigil: '%',
         include: String::new(),
         formatter: vec![],
         no_fts: true,
         dump_expanded: false,
         project_root: None,
+        pass_name: None,
     };
     run_single_pass(args).unwrap();
     assert_eq!(fs::read_to_string(&out_file).unwrap().trim(), "content");
@@ -128,21 +140,27 @@ fn run_single_pass_force_generated_bypasses_incremental_skip() {
         expanded_adoc_dir: PathBuf::from("expanded-adoc"),
         expanded_md_dir: PathBuf::from("expanded-md"),
         macro_only: false,
+        projections: vec![],
         dry_run: false,
         db: db_path.clone(),
         depfile: None,
         stamp: None,
         strict: false,
+        profile: None,
         warn_unused: false,
         allow_env: false,
         allow_home: true,
         force_generated,
+        prune: false,
+        check: false,
+        merge: false,
         sigil: '%',
         include: String::new(),
         formatter: vec![],
         no_fts: true,
         dump_expanded: false,
         project_root: None,
+        pass_name: None,
     };
 
     run_single_pass(make_args(false)).unwrap();
@@ -286,3 +304,93 @@ fn run_single_pass_bench_no_fts() {
     };
     run_single_pass(args).unwrap();
 }
+#[test]
+fn run_single_pass_tangles_the_profile_variant_into_its_gen_dir() {
+    let tmp = tempdir().unwrap();
+    fs::write(
+        tmp.path().join("input.adoc"),
+        "<<@file config.h>>= @if(profile=embedded)\n#define HEAP 4096\n@\n\
+         <<@file config.h>>= @if(profile!=embedded)\n#define HEAP 0\n@\n",
+    )
+    .unwrap();
+
+    for (profile, heap, src_line) in [("embedded", "4096", 1), ("hosted", "0", 4)] {
+        let gen_dir = tmp.path().join("gen").join(profile);
+        let db_path = tmp.path().join(format!("weaveback.{profile}.db"));
+        run_single_pass(SinglePassArgs {
+            inputs: vec![PathBuf::from("input.adoc")],
+            input_dir: tmp.path().to_path_buf(),
+            gen_dir: gen_dir.clone(),
+            db: db_path.clone(),
+            profile: Some(profile.to_string()),
+            no_fts: true,
+            ..SinglePassArgs::default_for_test()
+        })
+        .unwrap();
+
+        let out = fs::read_to_string(gen_dir.join("config.h")).unwrap();
+        assert_eq!(out, format!("#define HEAP {heap}\n"));
+        let db = weaveback_tangle::db::WeavebackDb::open(&db_path).unwrap();
+        let entry = db
+            .get_noweb_entry_by_suffix(&format!("{profile}/config.h"), 0)
+            .unwrap()
+            .expect("source map recorded under the profile's gen dir");
+        assert_eq!(entry.src_line, src_line);
+    }
+}
+#[test]
+fn run_single_pass_retangles_outputs_when_a_transcluded_region_changes() {
+    let tmp = tempdir().unwrap();
+    fs::write(
+        tmp.path().join("input.adoc"),
+        "<<@file lib.rs>>=\n<<@from legacy.rs#body>>\n@\n<<@file other.rs>>=\nother\n@\n",
+    )
+    .unwrap();
+    let legacy = tmp.path().join("legacy.rs");
+    fs::write(&legacy, "// tag::body[]\nfn old() {}\n// end::body[]\n").unwrap();
+    let gen_dir = tmp.path().join("gen");
+    let db_path = tmp.path().join("weaveback.db");
+    let args = || SinglePassArgs {
+        inputs: vec![PathBuf::from("input.adoc")],
+        input_dir: tmp.path().to_path_buf(),
+        gen_dir: gen_dir.clone(),
+        db: db_path.clone(),
+        project_root: Some(tmp.path().to_path_buf()),
+        ..SinglePassArgs::default_for_test()
+    };
+
+    run_single_pass(args()).unwrap();
+    assert_eq!(fs::read_to_string(gen_dir.join("lib.rs")).unwrap(), "fn old() {}\n");
+
+    fs::write(&legacy, "// tag::body[]\nfn new() {}\n// end::body[]\n").unwrap();
+    run_single_pass(args()).unwrap();
+    assert_eq!(fs::read_to_string(gen_dir.join("lib.rs")).unwrap(), "fn new() {}\n");
+
+    let db = weaveback_tangle::db::WeavebackDb::open(&db_path).unwrap();
+    let entry = db.get_noweb_entry_by_suffix("lib.rs", 0).unwrap().unwrap();
+    assert_eq!((entry.src_file.as_str(), entry.src_line), ("legacy.rs", 1));
+}
+#[test]
+fn run_single_pass_failing_output_leaves_gen_and_db_untouched() {
+    let tmp = tempdir().unwrap();
+    fs::write(
+        tmp.path().join("input.adoc"),
+        "<<@file a.txt>>=\na\n@\n<<@file b.rs>>=\nfn b() {}\n@\n",
+    )
+    .unwrap();
+    let gen_dir = tmp.path().join("gen");
+    let db_path = tmp.path().join("weaveback.db");
+    let result = run_single_pass(SinglePassArgs {
+        inputs: vec![PathBuf::from("input.adoc")],
+        input_dir: tmp.path().to_path_buf(),
+        gen_dir: gen_dir.clone(),
+        db: db_path.clone(),
+        formatter: vec!["rs=false".to_string()],
+        ..SinglePassArgs::default_for_test()
+    });
+
+    assert!(result.is_err());
+    assert!(!gen_dir.join("a.txt").exists());
+    let db = weaveback_tangle::db::WeavebackDb::open(&db_path).unwrap();
+    assert!(db.get_baseline("a.txt").unwrap().is_none());
+}
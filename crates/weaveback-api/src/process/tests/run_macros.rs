@@ -1,7 +1,7 @@
 // weaveback-api/src/process/tests/run_macros.rs
 // I'd Really Rather You Didn't edit this generated file.
 
-use super::super::{run_single_pass, SinglePassArgs};
+use super::super::{run_single_pass, ProcessError, Projection, SinglePassArgs};
 use std::fs;
 use std::path::PathBuf;
 use tempfile::tempdir;
@@ -254,6 +254,53 @@ fn run_single_pass_normalizes_adoc_tables_in_markdown_expanded_documents() {
     assert!(!expanded_adoc_dir.join("input.md").exists());
 }
 #[test]
+fn run_single_pass_projections_evaluate_each_prelude_and_tangle_one() {
+    let tmp = tempdir().unwrap();
+    let adoc_prelude = tmp.path().join("adoc.txt");
+    let md_prelude = tmp.path().join("md.txt");
+    fs::write(&adoc_prelude, "%def(title, t, %{= %(t)%})").unwrap();
+    fs::write(&md_prelude, "%def(title, t, %{# %(t)%})").unwrap();
+    fs::write(tmp.path().join("input.wvb"), "%title(Hello)\n<<@file out.txt>>=\n%title(code)\n@\n").unwrap();
+
+    let gen_dir = tmp.path().join("gen");
+    let adoc_dir = tmp.path().join("expanded-adoc");
+    let md_dir = tmp.path().join("expanded-md");
+    fs::create_dir(&gen_dir).unwrap();
+    let projection = |prelude: PathBuf, ext: &str, dir: &PathBuf, tangle| Projection {
+        macro_prelude: vec![prelude],
+        expanded_ext: Some(ext.to_string()),
+        expanded_dir: Some(dir.clone()),
+        tangle,
+    };
+    let args = |projections| SinglePassArgs {
+        inputs: vec![PathBuf::from("input.wvb")],
+        input_dir: tmp.path().to_path_buf(),
+        gen_dir: gen_dir.clone(),
+        db: tmp.path().join("wb.db"),
+        no_macros: false,
+        projections,
+        ..SinglePassArgs::default_for_test()
+    };
+
+    run_single_pass(args(vec![
+        projection(adoc_prelude.clone(), "adoc", &adoc_dir, true),
+        projection(md_prelude.clone(), "md", &md_dir, false),
+    ]))
+    .unwrap();
+    let adoc = fs::read_to_string(adoc_dir.join("input.adoc")).unwrap();
+    let md = fs::read_to_string(md_dir.join("input.md")).unwrap();
+    assert!(adoc.starts_with("= Hello"), "adoc: {adoc}");
+    assert!(md.starts_with("# Hello"), "md: {md}");
+    assert_eq!(fs::read_to_string(gen_dir.join("out.txt")).unwrap().trim(), "= code");
+
+    let err = run_single_pass(args(vec![
+        projection(adoc_prelude, "adoc", &adoc_dir, true),
+        projection(md_prelude, "md", &md_dir, true),
+    ]))
+    .unwrap_err();
+    assert!(matches!(err, ProcessError::Projections { .. }), "{err}");
+}
+#[test]
 fn run_single_pass_with_macro_expansion() {
     let tmp = tempdir().unwrap();
     let src = tmp.path().join("input.adoc");
@@ -298,3 +345,49 @@ fn run_single_pass_with_var_defs_recording() {
     assert!(!vars.is_empty(), "Should have recorded MYVAR definition");
     assert!(vars[0].0.contains("input.adoc"));
 }
+#[test]
+fn run_single_pass_applies_and_records_front_matter_overrides() {
+    let tmp = tempdir().unwrap();
+    fs::write(
+        tmp.path().join("input.adoc"),
+        ":weaveback-sigil: ^^\n:weaveback-open-delim: [[\n:weaveback-close-delim: ]]\n\n^set(V, expanded)\n[[@file out.txt]]=\n^(V) %(V)\n@\n",
+    )
+    .unwrap();
+
+    let db_path = tmp.path().join("wb.db");
+    let args = SinglePassArgs {
+        inputs: vec![PathBuf::from("input.adoc")],
+        input_dir: tmp.path().to_path_buf(),
+        gen_dir: tmp.path().join("gen"),
+        db: db_path.clone(),
+        project_root: Some(tmp.path().to_path_buf()),
+        no_macros: false,
+        no_fts: true,
+        ..SinglePassArgs::default_for_test()
+    };
+    run_single_pass(args).unwrap();
+
+    let out = fs::read_to_string(tmp.path().join("gen/out.txt")).unwrap();
+    assert_eq!(out.trim(), "expanded %(V)");
+    let db = weaveback_tangle::db::WeavebackDb::open(&db_path).unwrap();
+    let cfg = db.get_source_config("input.adoc").unwrap().expect("config recorded");
+    assert_eq!(cfg.sigil, '^');
+    assert_eq!((cfg.open_delim.as_str(), cfg.close_delim.as_str()), ("[[", "]]"));
+}
+#[test]
+fn run_single_pass_rejects_malformed_front_matter() {
+    let tmp = tempdir().unwrap();
+    fs::write(tmp.path().join("input.adoc"), ":weaveback-sigil: ab\n").unwrap();
+
+    let err = run_single_pass(SinglePassArgs {
+        inputs: vec![PathBuf::from("input.adoc")],
+        input_dir: tmp.path().to_path_buf(),
+        gen_dir: tmp.path().join("gen"),
+        db: tmp.path().join("wb.db"),
+        no_macros: false,
+        no_fts: true,
+        ..SinglePassArgs::default_for_test()
+    })
+    .unwrap_err();
+    assert!(matches!(err, ProcessError::FrontMatter { .. }), "{err}");
+}
@@ -92,3 +92,32 @@ fn test_compute_skip_set_with_dependencies() {
     assert!(!skip_set.contains("base"));
     assert!(!skip_set.contains("dep"));
 }
+#[test]
+fn compute_skip_set_retangles_collection_consumers_on_any_change() {
+    let tmp = tempdir().unwrap();
+    let mut prev_db = WeavebackDb::open(tmp.path().join("prev.db")).unwrap();
+    let def = |chunk: &str, start: u32| weaveback_tangle::db::ChunkDefEntry {
+        src_file: "main.adoc".into(),
+        chunk_name: chunk.into(),
+        nth: 0,
+        def_start: start,
+        def_end: start + 2,
+    };
+    prev_db.set_chunk_defs(&[def("@file all.txt", 1), def("@file other.txt", 5)]).unwrap();
+    prev_db
+        .set_chunk_deps(&[("@file all.txt".into(), "@glob parts/*".into(), "main.adoc".into())])
+        .unwrap();
+    for out in ["all.txt", "other.txt"] {
+        prev_db.set_baseline(out, b"old\n").unwrap();
+        std::fs::write(tmp.path().join(out), "old\n").unwrap();
+    }
+
+    // A new source may define a `parts/…` chunk the previous run never saw.
+    let mut source_contents = HashMap::new();
+    source_contents.insert("new.adoc".to_string(), "= New\n".to_string());
+    let mut current_db = WeavebackDb::open_temp().unwrap();
+    let skip = compute_skip_set(&source_contents, &Some(prev_db), &mut current_db, tmp.path());
+
+    assert!(!skip.contains("@file all.txt"));
+    assert!(skip.contains("@file other.txt"));
+}
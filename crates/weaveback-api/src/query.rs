@@ -10,12 +10,26 @@ pub enum ApiError {
     Db(#[from] weaveback_tangle::db::DbError),
     #[error("{0}")]
     Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Query(#[from] QueryError),
 }
 
 /// Open the weaveback SQLite database at `db_path` in read-only mode.
 ///
-/// Returns a descriptive error if the file does not exist.
+/// Returns a descriptive error if the file does not exist or its schema
+/// still needs migrating.
 pub fn open_db(db_path: &Path) -> Result<WeavebackDb, ApiError> {
+    require_db(db_path)?;
+    Ok(WeavebackDb::open_read_only(db_path)?)
+}
+
+/// `open_db` for a database that may still need migrating.
+pub fn open_db_unmigrated(db_path: &Path) -> Result<WeavebackDb, ApiError> {
+    require_db(db_path)?;
+    Ok(WeavebackDb::open_read_only_unmigrated(db_path)?)
+}
+
+fn require_db(db_path: &Path) -> Result<(), ApiError> {
     if !db_path.exists() {
         return Err(ApiError::Io(std::io::Error::new(
             std::io::ErrorKind::NotFound,
@@ -25,7 +39,7 @@ pub fn open_db(db_path: &Path) -> Result<WeavebackDb, ApiError> {
             ),
         )));
     }
-    Ok(WeavebackDb::open_read_only(db_path)?)
+    Ok(())
 }
 /// Compute the transitive impact of changing `chunk`.
 ///
@@ -77,36 +91,7 @@ pub fn chunk_graph_dot(
     chunk: Option<&str>,
     db_path: &Path,
 ) -> Result<String, ApiError> {
-    let db = open_db(db_path)?;
-
-    let edges: Vec<(String, String)> = if let Some(root) = chunk {
-        let mut visited: std::collections::HashSet<String> = std::collections::HashSet::new();
-        let mut queue: std::collections::VecDeque<String> = std::collections::VecDeque::new();
-        visited.insert(root.to_string());
-        queue.push_back(root.to_string());
-        let mut sub: Vec<(String, String)> = Vec::new();
-        while let Some(current) = queue.pop_front() {
-            for (child, _) in db.query_chunk_deps(&current)? {
-                sub.push((current.clone(), child.clone()));
-                if visited.insert(child.clone()) {
-                    queue.push_back(child);
-                }
-            }
-        }
-        sub
-    } else {
-        db.query_all_chunk_deps()?
-            .into_iter()
-            .map(|(f, t, _)| (f, t))
-            .collect()
-    };
-
-    let mut out = String::from("digraph chunk_deps {\n");
-    for (from, to) in &edges {
-        out.push_str(&format!("  {} -> {};\n", dot_id(from), dot_id(to)));
-    }
-    out.push('}');
-    Ok(out)
+    chunk_graph(chunk, GraphFormat::Dot, db_path)
 }
 /// List source blocks that have LLM-generated tags, optionally filtered by file.
 pub fn list_block_tags(
@@ -117,5 +102,16 @@ pub fn list_block_tags(
         .map_err(|e| ApiError::Io(std::io::Error::other(e.to_string())))?;
     Ok(db.list_block_tags(file)?)
 }
+
+mod graph;
+mod lang;
+mod unused;
+
+pub use graph::{
+    ChunkDegree, ChunkGraph, GraphFormat, GraphLink, GraphNode, GraphStats, chunk_graph, graph_stats,
+};
+pub use lang::{QueryError, QueryResult, evaluate_query};
+pub use unused::{DefSite, UnusedItem, UnusedReport, find_unused};
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,953 @@
+// weaveback-api/src/query/lang.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::{ApiError, open_db};
+use std::path::Path;
+use weaveback_core::glob::glob_match;
+use weaveback_tangle::db::WeavebackDb;
+
+/// A query the parser or evaluator rejected, with the 1-based column of the
+/// offending token.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("query error at column {column}: {message}")]
+pub struct QueryError {
+    pub column: usize,
+    pub message: String,
+}
+
+fn query_error<T>(column: usize, message: impl Into<String>) -> Result<T, QueryError> {
+    Err(QueryError { column, message: message.into() })
+}
+
+/// Rows of an evaluated query.  Every row is a JSON object with one member
+/// per column.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct QueryResult {
+    pub entity: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    Text,
+    Number,
+    Flag,
+    List,
+}
+
+impl FieldType {
+    fn describe(self) -> &'static str {
+        match self {
+            FieldType::Text => "text",
+            FieldType::Number => "number",
+            FieldType::Flag => "flag",
+            FieldType::List => "list",
+        }
+    }
+}
+
+/// A field value of one row.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Flag(bool),
+    Number(f64),
+    Text(String),
+    List(Vec<String>),
+}
+
+impl Value {
+    fn is_set(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Flag(flag) => *flag,
+            Value::Number(n) => *n != 0.0,
+            Value::Text(text) => !text.is_empty(),
+            Value::List(items) => !items.is_empty(),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::Null => serde_json::Value::Null,
+            Value::Flag(flag) => serde_json::json!(flag),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 9e15 => serde_json::json!(*n as i64),
+            Value::Number(n) => serde_json::json!(n),
+            Value::Text(text) => serde_json::json!(text),
+            Value::List(items) => serde_json::json!(items),
+        }
+    }
+}
+
+type Row = std::collections::HashMap<&'static str, Value>;
+
+#[derive(Debug)]
+struct Entity {
+    name: &'static str,
+    /// `(field, type, default column)`.
+    fields: &'static [(&'static str, FieldType, bool)],
+}
+
+const ENTITIES: &[Entity] = &[
+    Entity {
+        name: "chunks",
+        fields: &[
+            ("name", FieldType::Text, true),
+            ("file", FieldType::Text, true),
+            ("nth", FieldType::Number, false),
+            ("line_start", FieldType::Number, true),
+            ("line_end", FieldType::Number, true),
+            ("lines", FieldType::Number, false),
+            ("deps", FieldType::List, false),
+            ("used_by", FieldType::List, false),
+            ("outputs", FieldType::List, false),
+            ("prose_before", FieldType::Flag, false),
+            ("coverage", FieldType::Number, false),
+            ("tags", FieldType::List, false),
+        ],
+    },
+    Entity {
+        name: "files",
+        fields: &[
+            ("path", FieldType::Text, true),
+            ("role", FieldType::Text, true),
+            ("chunks", FieldType::List, false),
+            ("sources", FieldType::List, false),
+            ("outputs", FieldType::List, false),
+            ("coverage", FieldType::Number, false),
+        ],
+    },
+    Entity {
+        name: "deps",
+        fields: &[
+            ("from", FieldType::Text, true),
+            ("to", FieldType::Text, true),
+            ("file", FieldType::Text, true),
+        ],
+    },
+    Entity {
+        name: "blocks",
+        fields: &[
+            ("file", FieldType::Text, true),
+            ("index", FieldType::Number, true),
+            ("type", FieldType::Text, true),
+            ("line_start", FieldType::Number, true),
+            ("line_end", FieldType::Number, true),
+            ("tags", FieldType::List, false),
+        ],
+    },
+    Entity {
+        name: "tags",
+        fields: &[
+            ("tag", FieldType::Text, true),
+            ("file", FieldType::Text, true),
+            ("block", FieldType::Number, false),
+            ("type", FieldType::Text, false),
+            ("line_start", FieldType::Number, true),
+        ],
+    },
+];
+
+impl Entity {
+    fn field(&self, name: &str) -> Option<(&'static str, FieldType)> {
+        self.fields
+            .iter()
+            .find(|(field, _, _)| *field == name)
+            .map(|(field, ty, _)| (*field, *ty))
+    }
+
+    fn field_names(&self) -> String {
+        self.fields.iter().map(|(field, _, _)| *field).collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Glob,
+    Has,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Glob => "~",
+            Op::Has => "has",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Number(f64),
+    Op(Op),
+    Open,
+    Close,
+    Comma,
+    End,
+}
+
+/// Split `input` into tokens, each with its 1-based column.
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let (token, len) = match c {
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            ',' => (Token::Comma, 1),
+            '~' => (Token::Op(Op::Glob), 1),
+            '=' if chars.get(i + 1) == Some(&'=') => (Token::Op(Op::Eq), 2),
+            '=' => (Token::Op(Op::Eq), 1),
+            '!' if chars.get(i + 1) == Some(&'=') => (Token::Op(Op::Ne), 2),
+            '<' if chars.get(i + 1) == Some(&'>') => (Token::Op(Op::Ne), 2),
+            '<' if chars.get(i + 1) == Some(&'=') => (Token::Op(Op::Le), 2),
+            '<' => (Token::Op(Op::Lt), 1),
+            '>' if chars.get(i + 1) == Some(&'=') => (Token::Op(Op::Ge), 2),
+            '>' => (Token::Op(Op::Gt), 1),
+            '\'' | '"' => {
+                let mut text = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => return query_error(column, "unterminated text literal"),
+                        Some(&q) if q == c && chars.get(j + 1) == Some(&c) => {
+                            text.push(c);
+                            j += 2;
+                        }
+                        Some(&q) if q == c => break,
+                        Some(&other) => {
+                            text.push(other);
+                            j += 1;
+                        }
+                    }
+                }
+                (Token::Text(text), j + 1 - i)
+            }
+            c if c.is_ascii_digit() || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) => {
+                let len = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit() || **c == '.')
+                    .count()
+                    + 1;
+                let literal: String = chars[i..i + len].iter().collect();
+                match literal.parse() {
+                    Ok(n) => (Token::Number(n), len),
+                    Err(_) => return query_error(column, format!("invalid number `{literal}`")),
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .count();
+                let word: String = chars[i..i + len].iter().collect();
+                if word.eq_ignore_ascii_case("has") {
+                    (Token::Op(Op::Has), len)
+                } else {
+                    (Token::Word(word), len)
+                }
+            }
+            other => return query_error(column, format!("unexpected character `{other}`")),
+        };
+        tokens.push((token, column));
+        i += len;
+    }
+    tokens.push((Token::End, chars.len() + 1));
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Null,
+    Flag(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl Literal {
+    fn describe(&self) -> &'static str {
+        match self {
+            Literal::Null => "null",
+            Literal::Flag(_) => "a flag",
+            Literal::Number(_) => "a number",
+            Literal::Text(_) => "text",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Set(&'static str),
+    Compare(&'static str, Op, Literal),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug)]
+struct Query {
+    entity: &'static Entity,
+    columns: Vec<&'static str>,
+    filter: Option<Expr>,
+    order: Option<(&'static str, bool)>,
+    limit: Option<usize>,
+    /// Column of the first use of `coverage`, which needs LCOV data.
+    coverage_at: Option<usize>,
+}
+
+const KEYWORDS: &[&str] = &["where", "order", "by", "asc", "desc", "limit", "and", "or", "not"];
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn column(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if token != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.at_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), QueryError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            query_error(self.column(), format!("expected `{keyword}`"))
+        }
+    }
+
+    fn field(
+        &mut self,
+        entity: &'static Entity,
+        coverage: &mut Option<usize>,
+    ) -> Result<(&'static str, FieldType), QueryError> {
+        let column = self.column();
+        match self.next() {
+            Token::Word(word) if !KEYWORDS.iter().any(|k| word.eq_ignore_ascii_case(k)) => {
+                let Some((field, ty)) = entity.field(&word) else {
+                    return query_error(
+                        column,
+                        format!("`{}` has no field `{word}`; fields: {}", entity.name, entity.field_names()),
+                    );
+                };
+                if field == "coverage" && coverage.is_none() {
+                    *coverage = Some(column);
+                }
+                Ok((field, ty))
+            }
+            _ => query_error(column, "expected a field name"),
+        }
+    }
+
+    fn literal(&mut self) -> Result<Literal, QueryError> {
+        let column = self.column();
+        match self.next() {
+            Token::Text(text) => Ok(Literal::Text(text)),
+            Token::Number(n) => Ok(Literal::Number(n)),
+            Token::Word(word) if word.eq_ignore_ascii_case("true") => Ok(Literal::Flag(true)),
+            Token::Word(word) if word.eq_ignore_ascii_case("false") => Ok(Literal::Flag(false)),
+            Token::Word(word) if word.eq_ignore_ascii_case("null") => Ok(Literal::Null),
+            _ => query_error(column, "expected a literal: text, number, true, false or null"),
+        }
+    }
+
+    fn expr(&mut self, entity: &'static Entity, coverage: &mut Option<usize>) -> Result<Expr, QueryError> {
+        let mut lhs = self.and(entity, coverage)?;
+        while self.eat_keyword("or") {
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and(entity, coverage)?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self, entity: &'static Entity, coverage: &mut Option<usize>) -> Result<Expr, QueryError> {
+        let mut lhs = self.unary(entity, coverage)?;
+        while self.eat_keyword("and") {
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary(entity, coverage)?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self, entity: &'static Entity, coverage: &mut Option<usize>) -> Result<Expr, QueryError> {
+        if self.eat_keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary(entity, coverage)?)));
+        }
+        if *self.peek() == Token::Open {
+            self.next();
+            let inner = self.expr(entity, coverage)?;
+            if *self.peek() != Token::Close {
+                return query_error(self.column(), "expected `)`");
+            }
+            self.next();
+            return Ok(inner);
+        }
+        let (field, ty) = self.field(entity, coverage)?;
+        let &Token::Op(op) = self.peek() else {
+            return Ok(Expr::Set(field));
+        };
+        let op_column = self.column();
+        self.next();
+        let literal = self.literal()?;
+        check_operator(field, ty, op, &literal).or_else(|message| query_error(op_column, message))?;
+        Ok(Expr::Compare(field, op, literal))
+    }
+}
+
+/// Whether `op` applies to a `ty` field and `literal`; the message if not.
+fn check_operator(field: &str, ty: FieldType, op: Op, literal: &Literal) -> Result<(), String> {
+    let applies = match (op, literal) {
+        (Op::Eq | Op::Ne, Literal::Null) => true,
+        (Op::Eq | Op::Ne, Literal::Text(_)) => ty == FieldType::Text,
+        (Op::Eq | Op::Ne, Literal::Number(_)) => ty == FieldType::Number,
+        (Op::Eq | Op::Ne, Literal::Flag(_)) => ty == FieldType::Flag,
+        (Op::Lt | Op::Le | Op::Gt | Op::Ge, Literal::Text(_)) => ty == FieldType::Text,
+        (Op::Lt | Op::Le | Op::Gt | Op::Ge, Literal::Number(_)) => ty == FieldType::Number,
+        (Op::Glob | Op::Has, Literal::Text(_)) => matches!(ty, FieldType::Text | FieldType::List),
+        _ => false,
+    };
+    if applies {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{}` does not compare {} field `{field}` with {}",
+            op.symbol(),
+            ty.describe(),
+            literal.describe()
+        ))
+    }
+}
+
+fn parse_query(input: &str) -> Result<Query, QueryError> {
+    let mut parser = Parser { tokens: tokenize(input)?, pos: 0 };
+    let column = parser.column();
+    let entity = match parser.next() {
+        Token::Word(word) => match ENTITIES.iter().find(|entity| entity.name == word) {
+            Some(entity) => entity,
+            None => {
+                let names: Vec<&str> = ENTITIES.iter().map(|entity| entity.name).collect();
+                return query_error(column, format!("unknown entity `{word}`; use one of {}", names.join(", ")));
+            }
+        },
+        _ => return query_error(column, "a query starts with an entity: chunks, files, deps, blocks or tags"),
+    };
+
+    let mut coverage_at = None;
+    let mut columns = Vec::new();
+    if matches!(parser.peek(), Token::Word(word) if !KEYWORDS.iter().any(|k| word.eq_ignore_ascii_case(k))) {
+        loop {
+            columns.push(parser.field(entity, &mut coverage_at)?.0);
+            if *parser.peek() != Token::Comma {
+                break;
+            }
+            parser.next();
+        }
+    }
+
+    let filter = if parser.eat_keyword("where") {
+        Some(parser.expr(entity, &mut coverage_at)?)
+    } else {
+        None
+    };
+
+    let order = if parser.eat_keyword("order") {
+        parser.expect_keyword("by")?;
+        let (field, _) = parser.field(entity, &mut coverage_at)?;
+        let descending = if parser.eat_keyword("desc") {
+            true
+        } else {
+            parser.eat_keyword("asc");
+            false
+        };
+        Some((field, descending))
+    } else {
+        None
+    };
+
+    let limit = if parser.eat_keyword("limit") {
+        let column = parser.column();
+        match parser.next() {
+            Token::Number(n) if n >= 0.0 && n.fract() == 0.0 => Some(n as usize),
+            _ => return query_error(column, "`limit` takes a whole number"),
+        }
+    } else {
+        None
+    };
+
+    if *parser.peek() != Token::End {
+        return query_error(parser.column(), "unexpected input after the query");
+    }
+
+    if columns.is_empty() {
+        columns = entity
+            .fields
+            .iter()
+            .filter(|(_, _, default)| *default)
+            .map(|(field, _, _)| *field)
+            .collect();
+        let mut used = Vec::new();
+        if let Some(filter) = &filter {
+            filter.fields(&mut used);
+        }
+        if let Some((field, _)) = order {
+            used.push(field);
+        }
+        for field in used {
+            if !columns.contains(&field) {
+                columns.push(field);
+            }
+        }
+    }
+
+    Ok(Query { entity, columns, filter, order, limit, coverage_at })
+}
+
+impl Expr {
+    /// The fields the expression reads, in order of appearance.
+    fn fields(&self, out: &mut Vec<&'static str>) {
+        match self {
+            Expr::Set(field) | Expr::Compare(field, _, _) => out.push(*field),
+            Expr::Not(inner) => inner.fields(out),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.fields(out);
+                rhs.fields(out);
+            }
+        }
+    }
+}
+
+fn compare(value: &Value, op: Op, literal: &Literal) -> bool {
+    use std::cmp::Ordering;
+    let ordering = |ordering: Option<Ordering>| match (op, ordering) {
+        (_, None) => false,
+        (Op::Eq, Some(o)) => o == Ordering::Equal,
+        (Op::Ne, Some(o)) => o != Ordering::Equal,
+        (Op::Lt, Some(o)) => o == Ordering::Less,
+        (Op::Le, Some(o)) => o != Ordering::Greater,
+        (Op::Gt, Some(o)) => o == Ordering::Greater,
+        (Op::Ge, Some(o)) => o != Ordering::Less,
+        (Op::Glob | Op::Has, _) => false,
+    };
+    match (value, literal) {
+        (value, Literal::Null) => (op == Op::Eq) == (*value == Value::Null),
+        (Value::Null, _) => false,
+        (Value::Text(text), Literal::Text(pattern)) if op == Op::Glob => glob_match(pattern, text),
+        (Value::Text(text), Literal::Text(needle)) if op == Op::Has => text.contains(needle.as_str()),
+        (Value::List(items), Literal::Text(pattern)) if op == Op::Glob => {
+            items.iter().any(|item| glob_match(pattern, item))
+        }
+        (Value::List(items), Literal::Text(needle)) if op == Op::Has => items.contains(needle),
+        (Value::Text(text), Literal::Text(other)) => ordering(Some(text.as_str().cmp(other))),
+        (Value::Number(n), Literal::Number(other)) => ordering(n.partial_cmp(other)),
+        (Value::Flag(flag), Literal::Flag(other)) => ordering(Some(flag.cmp(other))),
+        _ => false,
+    }
+}
+
+fn matches(row: &Row, expr: &Expr) -> bool {
+    match expr {
+        Expr::Set(field) => row[field].is_set(),
+        Expr::Compare(field, op, literal) => compare(&row[field], *op, literal),
+        Expr::Not(inner) => !matches(row, inner),
+        Expr::And(lhs, rhs) => matches(row, lhs) && matches(row, rhs),
+        Expr::Or(lhs, rhs) => matches(row, lhs) || matches(row, rhs),
+    }
+}
+
+/// Sort order of `order by`; missing values sort last either way.
+fn order_values(lhs: &Value, rhs: &Value, descending: bool) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    let ordering = match (lhs, rhs) {
+        (Value::Null, Value::Null) => return Ordering::Equal,
+        (Value::Null, _) => return Ordering::Greater,
+        (_, Value::Null) => return Ordering::Less,
+        (Value::Flag(a), Value::Flag(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Value::Text(a), Value::Text(b)) => a.cmp(b),
+        (Value::List(a), Value::List(b)) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+        _ => Ordering::Equal,
+    };
+    if descending { ordering.reverse() } else { ordering }
+}
+
+/// Run `query` against the database at `db_path`.  `lcov` is the LCOV
+/// tracefile that `coverage` fields are computed from.
+pub fn evaluate_query(
+    query: &str,
+    db_path: &Path,
+    lcov: Option<&Path>,
+) -> Result<QueryResult, ApiError> {
+    let query = parse_query(query)?;
+    let lcov_records = match (query.coverage_at, lcov) {
+        (Some(column), None) => {
+            return Err(QueryError {
+                column,
+                message: "`coverage` needs an LCOV tracefile".to_string(),
+            }
+            .into());
+        }
+        (Some(_), Some(lcov)) => Some(crate::coverage::parse_lcov_records(&std::fs::read_to_string(lcov)?)),
+        (None, _) => None,
+    };
+    let db = open_db(db_path)?;
+    let graph = Provenance::load(&db, lcov_records.as_deref())?;
+
+    let mut rows: Vec<Row> = graph
+        .rows(query.entity.name)
+        .into_iter()
+        .filter(|row| query.filter.as_ref().is_none_or(|filter| matches(row, filter)))
+        .collect();
+    if let Some((field, descending)) = query.order {
+        rows.sort_by(|lhs, rhs| order_values(&lhs[field], &rhs[field], descending));
+    }
+    if let Some(limit) = query.limit {
+        rows.truncate(limit);
+    }
+
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let object: serde_json::Map<String, serde_json::Value> = query
+                .columns
+                .iter()
+                .map(|column| (column.to_string(), row[column].to_json()))
+                .collect();
+            serde_json::Value::Object(object)
+        })
+        .collect();
+    Ok(QueryResult {
+        entity: query.entity.name.to_string(),
+        columns: query.columns.iter().map(|column| column.to_string()).collect(),
+        rows,
+    })
+}
+
+/// LCOV hits per generated file of the database, by 0-indexed line.
+type LineHits = std::collections::HashMap<String, std::collections::BTreeMap<u32, u64>>;
+
+struct Provenance {
+    defs: Vec<weaveback_tangle::db::ChunkDefEntry>,
+    deps: Vec<(String, String, String)>,
+    ranges: Vec<(String, u32, u32, weaveback_tangle::db::NowebMapEntry)>,
+    blocks: Vec<(String, weaveback_tangle::db::StoredBlockInfo)>,
+    tags: std::collections::HashMap<(String, u32), Vec<String>>,
+    hits: Option<LineHits>,
+}
+
+/// `hit` out of `instrumented` in percent, to one decimal; missing when
+/// nothing was instrumented.
+fn percent(hit: usize, instrumented: usize) -> Value {
+    if instrumented == 0 {
+        return Value::Null;
+    }
+    Value::Number((hit as f64 * 1000.0 / instrumented as f64).round() / 10.0)
+}
+
+fn sorted_list<'a>(items: impl IntoIterator<Item = &'a str>) -> Value {
+    let set: std::collections::BTreeSet<&str> = items.into_iter().collect();
+    Value::List(set.into_iter().map(str::to_string).collect())
+}
+
+impl Provenance {
+    fn load(db: &WeavebackDb, lcov: Option<&[(String, u32, u64)]>) -> Result<Self, ApiError> {
+        let mut defs = db.list_all_chunk_defs()?;
+        defs.sort_by(|lhs, rhs| (&lhs.src_file, lhs.def_start).cmp(&(&rhs.src_file, rhs.def_start)));
+        let ranges = db.list_noweb_ranges()?;
+        let mut tags: std::collections::HashMap<(String, u32), Vec<String>> = Default::default();
+        for block in db.list_block_tags(None)? {
+            let list = block.tags.split(',').map(str::trim).filter(|tag| !tag.is_empty());
+            tags.insert((block.src_file, block.block_index), list.map(str::to_string).collect());
+        }
+        let hits = lcov.map(|records| {
+            let outputs: std::collections::BTreeSet<&str> =
+                ranges.iter().map(|(out_file, ..)| out_file.as_str()).collect();
+            let mut hits = LineHits::new();
+            for (file, line, count) in records {
+                let file = file.replace('\\', "/");
+                let owner = outputs
+                    .iter()
+                    .filter(|out| file == **out || file.ends_with(&format!("/{out}")))
+                    .max_by_key(|out| out.len());
+                if let (Some(owner), Some(line)) = (owner, line.checked_sub(1)) {
+                    *hits.entry(owner.to_string()).or_default().entry(line).or_default() += count;
+                }
+            }
+            hits
+        });
+        Ok(Provenance {
+            defs,
+            deps: db.query_all_chunk_deps()?,
+            ranges,
+            blocks: db.list_source_blocks()?,
+            tags,
+            hits,
+        })
+    }
+
+    /// Coverage of the generated lines in `ranges`.
+    fn coverage<'a>(
+        &self,
+        ranges: impl IntoIterator<Item = &'a (String, u32, u32, weaveback_tangle::db::NowebMapEntry)>,
+    ) -> Value {
+        let Some(hits) = &self.hits else {
+            return Value::Null;
+        };
+        let (mut hit, mut instrumented) = (0, 0);
+        for (out_file, out_start, out_end, _) in ranges {
+            if let Some(lines) = hits.get(out_file) {
+                for (_, count) in lines.range(out_start..=out_end) {
+                    instrumented += 1;
+                    hit += usize::from(*count > 0);
+                }
+            }
+        }
+        percent(hit, instrumented)
+    }
+
+    fn rows(&self, entity: &str) -> Vec<Row> {
+        match entity {
+            "chunks" => self.chunk_rows(),
+            "files" => self.file_rows(),
+            "deps" => self
+                .deps
+                .iter()
+                .map(|(from, to, file)| {
+                    Row::from([
+                        ("from", Value::Text(from.clone())),
+                        ("to", Value::Text(to.clone())),
+                        ("file", Value::Text(file.clone())),
+                    ])
+                })
+                .collect(),
+            "blocks" => self
+                .blocks
+                .iter()
+                .map(|(file, block)| {
+                    let tags = self.tags.get(&(file.clone(), block.block_index)).cloned().unwrap_or_default();
+                    Row::from([
+                        ("file", Value::Text(file.clone())),
+                        ("index", Value::Number(block.block_index.into())),
+                        ("type", Value::Text(block.block_type.clone())),
+                        ("line_start", Value::Number(block.line_start.into())),
+                        ("line_end", Value::Number(block.line_end.into())),
+                        ("tags", Value::List(tags)),
+                    ])
+                })
+                .collect(),
+            _ => self
+                .blocks
+                .iter()
+                .flat_map(|(file, block)| {
+                    let tags = self.tags.get(&(file.clone(), block.block_index)).cloned().unwrap_or_default();
+                    tags.into_iter().map(move |tag| {
+                        Row::from([
+                            ("tag", Value::Text(tag)),
+                            ("file", Value::Text(file.clone())),
+                            ("block", Value::Number(block.block_index.into())),
+                            ("type", Value::Text(block.block_type.clone())),
+                            ("line_start", Value::Number(block.line_start.into())),
+                        ])
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn chunk_rows(&self) -> Vec<Row> {
+        let mut deps: std::collections::HashMap<&str, Vec<&str>> = Default::default();
+        let mut used_by: std::collections::HashMap<&str, Vec<&str>> = Default::default();
+        for (from, to, _) in &self.deps {
+            deps.entry(from).or_default().push(to);
+            used_by.entry(to).or_default().push(from);
+        }
+        let mut ranges: std::collections::HashMap<(&str, &str), Vec<_>> = Default::default();
+        for range in &self.ranges {
+            let entry = &range.3;
+            ranges.entry((entry.src_file.as_str(), entry.chunk_name.as_str())).or_default().push(range);
+        }
+        let mut prose: std::collections::HashMap<&str, Vec<(u32, u32, u32)>> = Default::default();
+        for (file, block) in &self.blocks {
+            if block.block_type == "para" {
+                prose.entry(file).or_default().push((block.line_start, block.line_end, block.block_index));
+            }
+        }
+
+        let mut rows = Vec::with_capacity(self.defs.len());
+        let mut previous_end: (&str, u32) = ("", 0);
+        for def in &self.defs {
+            if previous_end.0 != def.src_file {
+                previous_end = (def.src_file.as_str(), 0);
+            }
+            let own: Vec<_> = ranges
+                .get(&(def.src_file.as_str(), def.chunk_name.as_str()))
+                .into_iter()
+                .flatten()
+                .filter(|(_, _, _, entry)| entry.src_line >= def.def_start && entry.src_line + 1 < def.def_end)
+                .copied()
+                .collect();
+            let before: Vec<u32> = prose
+                .get(def.src_file.as_str())
+                .into_iter()
+                .flatten()
+                .filter(|(start, end, _)| *start > previous_end.1 && *end < def.def_start)
+                .map(|(_, _, index)| *index)
+                .collect();
+            let tags = before
+                .iter()
+                .filter_map(|index| self.tags.get(&(def.src_file.clone(), *index)))
+                .flatten()
+                .map(String::as_str);
+            rows.push(Row::from([
+                ("name", Value::Text(def.chunk_name.clone())),
+                ("file", Value::Text(def.src_file.clone())),
+                ("nth", Value::Number(def.nth.into())),
+                ("line_start", Value::Number(def.def_start.into())),
+                ("line_end", Value::Number(def.def_end.into())),
+                ("lines", Value::Number(def.def_end.saturating_sub(def.def_start + 1).into())),
+                ("deps", sorted_list(deps.get(def.chunk_name.as_str()).into_iter().flatten().copied())),
+                ("used_by", sorted_list(used_by.get(def.chunk_name.as_str()).into_iter().flatten().copied())),
+                ("outputs", sorted_list(own.iter().map(|(out_file, ..)| out_file.as_str()))),
+                ("prose_before", Value::Flag(!before.is_empty())),
+                ("coverage", self.coverage(own.iter().copied())),
+                ("tags", sorted_list(tags)),
+            ]));
+            previous_end.1 = previous_end.1.max(def.def_end);
+        }
+        rows
+    }
+
+    fn file_rows(&self) -> Vec<Row> {
+        let mut sources: std::collections::BTreeMap<&str, Vec<&str>> = Default::default();
+        for def in &self.defs {
+            sources.entry(&def.src_file).or_default().push(&def.chunk_name);
+        }
+        for (file, _) in &self.blocks {
+            sources.entry(file).or_default();
+        }
+        let mut from_source: std::collections::HashMap<&str, Vec<_>> = Default::default();
+        let mut into_output: std::collections::BTreeMap<&str, Vec<_>> = Default::default();
+        for range in &self.ranges {
+            sources.entry(&range.3.src_file).or_default();
+            from_source.entry(range.3.src_file.as_str()).or_default().push(range);
+            into_output.entry(range.0.as_str()).or_default().push(range);
+        }
+
+        let mut rows = Vec::new();
+        for (path, chunks) in &sources {
+            let ranges = from_source.get(path).map(Vec::as_slice).unwrap_or_default();
+            rows.push(Row::from([
+                ("path", Value::Text(path.to_string())),
+                ("role", Value::Text("source".to_string())),
+                ("chunks", sorted_list(chunks.iter().copied())),
+                ("sources", Value::List(Vec::new())),
+                ("outputs", sorted_list(ranges.iter().map(|(out_file, ..)| out_file.as_str()))),
+                ("coverage", self.coverage(ranges.iter().copied())),
+            ]));
+        }
+        for (path, ranges) in &into_output {
+            let coverage = match self.hits.as_ref().map(|hits| hits.get(*path)) {
+                None => Value::Null,
+                Some(None) => Value::Null,
+                Some(Some(lines)) => percent(lines.values().filter(|count| **count > 0).count(), lines.len()),
+            };
+            rows.push(Row::from([
+                ("path", Value::Text(path.to_string())),
+                ("role", Value::Text("output".to_string())),
+                ("chunks", sorted_list(ranges.iter().map(|(_, _, _, entry)| entry.chunk_name.as_str()))),
+                ("sources", sorted_list(ranges.iter().map(|(_, _, _, entry)| entry.src_file.as_str()))),
+                ("outputs", Value::List(Vec::new())),
+                ("coverage", coverage),
+            ]));
+        }
+        rows
+    }
+}
+
+impl QueryResult {
+    /// The rows as an aligned text table with a header line.
+    pub fn to_table(&self) -> String {
+        let cell = |value: &serde_json::Value| match value {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Array(items) => items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string).unwrap_or_else(|| item.to_string()))
+                .collect::<Vec<_>>()
+                .join(","),
+            other => other.to_string(),
+        };
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| self.columns.iter().map(|column| cell(&row[column])).collect())
+            .collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                cells.iter().map(|row| row[i].chars().count()).chain([column.len()]).max().unwrap_or(0)
+            })
+            .collect();
+        let line = |values: &[String]| {
+            let padded: Vec<String> = values
+                .iter()
+                .zip(&widths)
+                .map(|(value, width)| format!("{value:<width$}"))
+                .collect();
+            format!("{}\n", padded.join("  ").trim_end())
+        };
+        let mut out = line(&self.columns);
+        out.push_str(&line(&widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>()));
+        for row in &cells {
+            out.push_str(&line(row));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests;
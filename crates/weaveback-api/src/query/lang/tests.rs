@@ -0,0 +1,157 @@
+// weaveback-api/src/query/lang/tests.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+use tempfile::TempDir;
+use weaveback_tangle::block_parser::SourceBlockEntry;
+use weaveback_tangle::db::{ChunkDefEntry, Confidence, NowebMapEntry};
+
+fn block(block_index: u32, block_type: &str, line_start: u32, line_end: u32) -> SourceBlockEntry {
+    SourceBlockEntry {
+        block_index,
+        block_type: block_type.to_string(),
+        line_start,
+        line_end,
+        content_hash: [0u8; 32],
+    }
+}
+
+fn mapped(chunk_name: &str, src_line: u32) -> NowebMapEntry {
+    NowebMapEntry {
+        src_file: "src/db.adoc".to_string(),
+        chunk_name: chunk_name.to_string(),
+        src_line,
+        indent: String::new(),
+        confidence: Confidence::Exact,
+    }
+}
+
+/// The database and an LCOV file in which `db-open` ran one of its two
+/// lines and `db-close` none.
+fn project() -> (TempDir, std::path::PathBuf, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let db_path = dir.path().join("weaveback.db");
+    let mut db = WeavebackDb::open(&db_path).unwrap();
+    db.set_source_blocks(
+        "src/db.adoc",
+        &[block(0, "section", 1, 1), block(1, "para", 3, 3), block(2, "code", 5, 12)],
+    )
+    .unwrap();
+    db.set_block_tags("src/db.adoc", 1, &[0u8; 32], "storage, sqlite").unwrap();
+    let def = |chunk_name: &str, def_start, def_end| ChunkDefEntry {
+        src_file: "src/db.adoc".to_string(),
+        chunk_name: chunk_name.to_string(),
+        nth: 0,
+        def_start,
+        def_end,
+    };
+    db.set_chunk_defs(&[def("db-open", 5, 8), def("db-close", 10, 12)]).unwrap();
+    db.set_chunk_deps(&[
+        ("@file src/db.rs".into(), "db-open".into(), "src/db.adoc".into()),
+        ("@file src/db.rs".into(), "db-close".into(), "src/db.adoc".into()),
+    ])
+    .unwrap();
+    db.set_noweb_entries(
+        "src/db.rs",
+        &[(0, mapped("db-open", 5)), (1, mapped("db-open", 6)), (2, mapped("db-close", 10))],
+    )
+    .unwrap();
+    drop(db);
+
+    let lcov = dir.path().join("lcov.info");
+    let project = dir.path().display().to_string().replace('\\', "/");
+    std::fs::write(
+        &lcov,
+        format!("SF:{project}/src/db.rs\nDA:1,3\nDA:2,0\nDA:3,0\nend_of_record\n"),
+    )
+    .unwrap();
+    (dir, db_path, lcov)
+}
+
+fn column(result: &QueryResult, column: &str) -> Vec<serde_json::Value> {
+    result.rows.iter().map(|row| row[column].clone()).collect()
+}
+
+#[test]
+fn parse_errors_point_at_the_offending_token() {
+    let err = parse_query("chunk where name = 'x'").unwrap_err();
+    assert_eq!(err.column, 1);
+    assert!(err.message.contains("unknown entity `chunk`"), "{err}");
+
+    let err = parse_query("chunks where size > 3").unwrap_err();
+    assert_eq!(err.column, 14);
+    assert!(err.message.contains("no field `size`"), "{err}");
+
+    let err = parse_query("chunks where name < 3").unwrap_err();
+    assert_eq!(err.column, 19);
+    assert!(err.message.contains("text field `name` with a number"), "{err}");
+
+    assert!(parse_query("chunks where name = 'open").is_err());
+    assert!(parse_query("chunks where (deps has 'x'").is_err());
+    assert!(parse_query("chunks limit 2 extra").is_err());
+}
+
+#[test]
+fn default_columns_add_the_fields_a_query_uses() {
+    let query = parse_query("chunks where lines > 1 order by nth desc").unwrap();
+    assert_eq!(query.columns, ["name", "file", "line_start", "line_end", "lines", "nth"]);
+    let query = parse_query("chunks name, outputs where lines > 1").unwrap();
+    assert_eq!(query.columns, ["name", "outputs"]);
+}
+
+#[test]
+fn chunks_without_prose_before_them() {
+    let (_dir, db_path, _) = project();
+    let result = evaluate_query("chunks where file = 'src/db.adoc' and not prose_before", &db_path, None).unwrap();
+    assert_eq!(column(&result, "name"), ["db-close"]);
+    assert_eq!(result.columns.last().map(String::as_str), Some("prose_before"));
+
+    let result = evaluate_query("chunks name, tags, used_by where prose_before", &db_path, None).unwrap();
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0]["name"], "db-open");
+    assert_eq!(result.rows[0]["tags"], serde_json::json!(["sqlite", "storage"]));
+    assert_eq!(result.rows[0]["used_by"], serde_json::json!(["@file src/db.rs"]));
+}
+
+#[test]
+fn files_depending_on_a_chunk_with_low_coverage() {
+    let (_dir, db_path, lcov) = project();
+    let query = "files where role = 'output' and chunks has 'db-open' and coverage < 50";
+
+    let err = evaluate_query(query, &db_path, None).unwrap_err();
+    assert!(err.to_string().contains("LCOV"), "{err}");
+
+    let result = evaluate_query(query, &db_path, Some(&lcov)).unwrap();
+    assert_eq!(column(&result, "path"), ["src/db.rs"]);
+    assert_eq!(result.rows[0]["coverage"], 33.3);
+
+    let result = evaluate_query("chunks name, coverage order by coverage desc", &db_path, Some(&lcov)).unwrap();
+    assert_eq!(column(&result, "name"), ["db-open", "db-close"]);
+    assert_eq!(column(&result, "coverage"), [serde_json::json!(50), serde_json::json!(0)]);
+}
+
+#[test]
+fn deps_blocks_and_tags_entities() {
+    let (_dir, db_path, _) = project();
+    let result = evaluate_query("deps where to ~ 'db-*' order by to limit 1", &db_path, None).unwrap();
+    assert_eq!(column(&result, "to"), ["db-close"]);
+
+    let result = evaluate_query("blocks where type = 'para' or tags has 'sqlite'", &db_path, None).unwrap();
+    assert_eq!(column(&result, "line_start"), [3]);
+
+    let result = evaluate_query("tags", &db_path, None).unwrap();
+    assert_eq!(column(&result, "tag"), ["storage", "sqlite"]);
+}
+
+#[test]
+fn to_table_aligns_columns() {
+    let (_dir, db_path, _) = project();
+    let result = evaluate_query("chunks name, outputs, nth order by name", &db_path, None).unwrap();
+    assert_eq!(
+        result.to_table(),
+        "name      outputs    nth\n\
+         --------  ---------  ---\n\
+         db-close  src/db.rs  0\n\
+         db-open   src/db.rs  0\n"
+    );
+}
@@ -0,0 +1,115 @@
+// weaveback-api/src/query/unused.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::{ApiError, open_db};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use weaveback_tangle::db::WeavebackDb;
+use weaveback_tangle::lookup::find_line_col;
+
+/// A definition site: 1-indexed `line` in `file`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DefSite {
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// A chunk or macro that nothing uses, with all its definitions.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct UnusedItem {
+    pub name: String,
+    pub locations: Vec<DefSite>,
+}
+
+/// Result of `find_unused`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct UnusedReport {
+    pub chunks: Vec<UnusedItem>,
+    pub macros: Vec<UnusedItem>,
+    /// False when the database records no macro calls at all.
+    pub macros_checked: bool,
+}
+
+impl UnusedReport {
+    /// One `file:line: message` line per unused definition.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let items = self
+            .chunks
+            .iter()
+            .map(|item| (item, "chunk", "referenced"))
+            .chain(self.macros.iter().map(|item| (item, "macro", "called")));
+        for (item, kind, verb) in items {
+            for site in &item.locations {
+                let line = site.line.map(|line| format!(":{line}")).unwrap_or_default();
+                out.push_str(&format!(
+                    "{}{line}: {kind} '{}' is defined but never {verb}\n",
+                    site.file, item.name
+                ));
+            }
+        }
+        if !self.macros_checked {
+            out.push_str("note: no macro calls are recorded yet; re-run weaveback to check macros\n");
+        }
+        out
+    }
+}
+
+/// Chunks and macros defined anywhere in the project and used nowhere.
+pub fn find_unused(db_path: &Path) -> Result<UnusedReport, ApiError> {
+    let db = open_db(db_path)?;
+
+    let referenced: HashSet<String> =
+        db.query_all_chunk_deps()?.into_iter().map(|(_, to, _)| to).collect();
+    let mut chunks: BTreeMap<String, Vec<DefSite>> = BTreeMap::new();
+    for def in db.list_chunk_defs(None)? {
+        if def.chunk_name.starts_with("@file ") || referenced.contains(&def.chunk_name) {
+            continue;
+        }
+        chunks
+            .entry(def.chunk_name)
+            .or_default()
+            .push(DefSite { file: def.src_file, line: Some(def.def_start) });
+    }
+
+    let called: HashSet<String> = db.called_macros()?.into_iter().collect();
+    let macros_checked = !called.is_empty();
+    let mut macros: BTreeMap<String, Vec<DefSite>> = BTreeMap::new();
+    if macros_checked {
+        let root = crate::db_admin::project_root_of(db_path).ok();
+        let mut texts: HashMap<String, Option<String>> = HashMap::new();
+        for (name, file, pos) in db.list_macro_defs()? {
+            if called.contains(&name) {
+                continue;
+            }
+            let text = texts
+                .entry(file.clone())
+                .or_insert_with(|| source_text(&db, root.as_deref(), &file));
+            let line = text.as_deref().map(|text| find_line_col(text, pos as usize).0);
+            macros.entry(name).or_default().push(DefSite { file, line });
+        }
+    }
+
+    let items = |map: BTreeMap<String, Vec<DefSite>>| {
+        map.into_iter()
+            .map(|(name, locations)| UnusedItem { name, locations })
+            .collect()
+    };
+    Ok(UnusedReport { chunks: items(chunks), macros: items(macros), macros_checked })
+}
+
+/// The text of `file`: its source snapshot, else the file on disk.
+fn source_text(db: &WeavebackDb, root: Option<&Path>, file: &str) -> Option<String> {
+    let bytes = match db.get_src_snapshot(file).ok().flatten() {
+        Some(bytes) => bytes,
+        None => {
+            let path = root.map_or_else(|| Path::new(file).to_path_buf(), |root| root.join(file));
+            std::fs::read(path).ok()?
+        }
+    };
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests;
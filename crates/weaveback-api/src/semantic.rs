@@ -158,6 +158,7 @@ fn embed_texts(cfg: &EmbeddingConfig, inputs: &[String]) -> Result<Vec<Vec<f32>>
             let base = cfg.endpoint.as_deref().unwrap_or("http://localhost:11434/v1");
             call_openai_embeddings(None, base, &cfg.model, inputs)
         }
+        "local" => weaveback_core::embedding::local_embedder(&cfg.model)?.embed(inputs),
         "anthropic" => Err(
             "Anthropic does not expose a compatible embedding API here; \
              use tags or an embedding backend".to_string()
@@ -250,6 +251,9 @@ pub fn run_auto_embed(
     }
     if embedded > 0 {
         eprintln!("auto-embed: embedded {embedded} block(s)");
+        if let Err(err) = db.update_embedding_index(&cfg.model) {
+            eprintln!("warning: embedding index update failed: {err}");
+        }
     }
 }
 #[cfg(test)]
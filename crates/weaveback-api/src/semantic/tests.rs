@@ -101,6 +101,23 @@ fn embed_texts_rejects_anthropic_backend() {
     assert!(err.contains("Anthropic"));
 }
 
+#[test]
+fn embed_texts_local_backend_is_deterministic() {
+    let cfg = EmbeddingConfig {
+        backend:    "local".to_string(),
+        model:      "hashed-ngram-64".to_string(),
+        endpoint:   None,
+        batch_size: 1,
+    };
+    let texts = ["tangle the sources".to_string(), "weave the docs".to_string()];
+    let first = embed_texts(&cfg, &texts).unwrap();
+    assert_eq!(first, embed_texts(&cfg, &texts).unwrap());
+    assert_eq!(first.iter().map(Vec::len).collect::<Vec<_>>(), vec![64, 64]);
+
+    let unknown = EmbeddingConfig { model: "text-embedding-3-small".to_string(), ..cfg };
+    assert!(embed_texts(&unknown, &texts).unwrap_err().contains("hashed-ngram"));
+}
+
 // ── default config values ────────────────────────────────────────────────
 
 #[test]
@@ -213,6 +230,28 @@ while True: time.sleep(1)
     assert!(needs.is_empty(), "all blocks should have been embedded");
 }
 
+#[test]
+fn test_run_auto_embed_local_backend_feeds_semantic_search() {
+    let mut db = WeavebackDb::open_temp().unwrap();
+    let src = "= Storage\n\nBaselines are stored in SQLite.\n\nMacros expand recursively.\n";
+    let blocks = weaveback_tangle::parse_source_blocks(src, "adoc");
+    db.set_source_blocks("doc.adoc", &blocks).unwrap();
+    db.set_src_snapshot("doc.adoc", src.as_bytes()).unwrap();
+
+    let cfg = EmbeddingConfig {
+        backend:    "local".to_string(),
+        model:      "hashed-ngram".to_string(),
+        endpoint:   None,
+        batch_size: 8,
+    };
+    run_auto_embed(&mut db, &cfg);
+    assert!(db.get_blocks_needing_embeddings("hashed-ngram").unwrap().is_empty());
+
+    let query = embed_texts(&cfg, &["where are baselines stored".to_string()]).unwrap();
+    let hits = db.search_prose_by_embedding(&query[0], 1).unwrap();
+    assert_eq!(hits[0].line_start, 3);
+}
+
 #[test]
 fn test_run_auto_embed_normalises_snapshot_paths() {
     use weaveback_tangle::SourceBlockEntry;
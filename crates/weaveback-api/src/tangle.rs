@@ -1,22 +1,40 @@
 // weaveback-api/src/tangle.rs
 // I'd Really Rather You Didn't edit this generated file.
 
+mod passes;
+mod resolve;
+mod watch;
+
+pub use passes::{PassFailure, pass_args, pass_prerequisites, pass_waves, run_passes, select_passes};
+pub use resolve::{parse_tangle_cfg, render_tangle_cfg};
+pub use watch::{affected_passes, pass_dependencies, run_watch};
+
 /// Per-pass configuration from `[[pass]]` in `weaveback.toml`.
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct TanglePassCfg {
+    /// Optional identity used by `depends_on`, `--pass`, errors and orphan
+    /// reports; stored with the pass's outputs but not part of its key.
+    pub name:            Option<String>,
+    /// Names of passes that must finish before this one starts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on:      Vec<String>,
     pub dir:             String,
     #[serde(rename = "gen")]
     pub output_dir:      Option<String>,
     pub ext:             Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub no_macros:       bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub macro_prelude:   Vec<String>,
     pub expanded_ext:    Option<String>,
     pub expanded_adoc_dir: Option<String>,
     pub expanded_md_dir: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub macro_only:      bool,
+    /// Expanded outputs evaluated from one parse of each source; replaces
+    /// `expanded_ext` and `macro_only` when non-empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projections:     Vec<ProjectionCfg>,
     pub open_delim:      Option<String>,
     pub close_delim:     Option<String>,
     pub chunk_end:       Option<String>,
@@ -24,8 +42,28 @@ pub struct TanglePassCfg {
     pub sigil:           Option<String>,
 }
 
+/// One entry of a pass's `projections`.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct ProjectionCfg {
+    /// Prelude evaluated after the pass's `macro_prelude` for this output.
+    pub prelude:      Option<String>,
+    pub expanded_ext: String,
+    /// Defaults to the pass's `expanded_md_dir` or `expanded_adoc_dir`.
+    pub expanded_dir: Option<String>,
+    /// Tangle this output into `gen`.  At most one projection may.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tangle:       bool,
+}
+
+impl TanglePassCfg {
+    /// How the pass is named in messages: its `name`, else its `dir`.
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.dir)
+    }
+}
+
 /// `[tags]` section in `weaveback.toml`.
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct TagsCfg {
     /// "anthropic" | "gemini" | "openai" | "ollama"
     #[serde(default = "default_tags_backend")]
@@ -45,7 +83,7 @@ pub fn default_tags_model()      -> String { "claude-haiku-4-5-20251001".to_stri
 pub fn default_tags_batch_size() -> usize  { 15 }
 
 /// `[semantic]` section in `weaveback.toml` (embeddings backend config).
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct EmbeddingsCfg {
     #[serde(default = "crate::semantic::default_embeddings_backend")]
     pub backend:    String,
@@ -56,8 +94,32 @@ pub struct EmbeddingsCfg {
     pub batch_size: usize,
 }
 
+/// Run-wide options that `run_tangle_all` forwards to every pass.
+#[derive(Debug, Clone, Default)]
+pub struct TangleRunOptions {
+    /// Overwrite generated files even if they differ from the stored baseline.
+    pub force_generated: bool,
+    /// Delete orphaned generated files that still match their baseline.
+    pub prune: bool,
+    /// Compare every pass's outputs with `gen/` without writing anything.
+    pub check: bool,
+    /// Three-way merge hand-edited generated files with the new output.
+    pub merge: bool,
+    /// `[profiles.NAME]` to build (`--profile`); `None` builds no profile.
+    pub profile: Option<String>,
+}
+
+/// Which passes of `weaveback.toml` a run covers.  The default is all.
+#[derive(Debug, Clone, Default)]
+pub struct PassSelection {
+    /// Run only the passes with these names (`--pass`); empty means all.
+    pub names: Vec<String>,
+    /// Run only passes whose inputs changed since the last run.
+    pub only_changed: bool,
+}
+
 /// Top-level `weaveback.toml` configuration.
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct TangleCfg {
     #[serde(rename = "gen")]
     pub default_gen: Option<String>,
@@ -65,98 +127,145 @@ pub struct TangleCfg {
     pub passes:      Vec<TanglePassCfg>,
     pub tags:        Option<TagsCfg>,
     pub embeddings:  Option<EmbeddingsCfg>,
+    /// Build profiles, by name (`[profiles.NAME]`).
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub profiles:    std::collections::BTreeMap<String, ProfileCfg>,
+    /// The profile `select_profile` applied, if any; not read from the file.
+    #[serde(skip)]
+    pub profile:     Option<String>,
 }
-/// Build a `std::process::Command` for one tangle pass.
-///
-/// `exe` is the path to the tangle executable to invoke.
-pub fn build_pass_cmd(
-    exe: &std::path::Path,
-    pass: &TanglePassCfg,
-    default_gen: &str,
-    force_generated: bool,
-) -> std::process::Command {
-    let mut cmd = std::process::Command::new(exe);
-    cmd.arg("--dir").arg(&pass.dir);
-    cmd.arg("--gen").arg(pass.output_dir.as_deref().unwrap_or(default_gen));
-    if force_generated {
-        cmd.arg("--force-generated");
-    }
-    if let Some(ext) = &pass.ext {
-        cmd.arg("--ext").arg(ext);
-    }
-    if pass.no_macros {
-        cmd.arg("--no-macros");
-    }
-    for prelude in &pass.macro_prelude {
-        cmd.arg("--macro-prelude").arg(prelude);
-    }
-    if let Some(ext) = &pass.expanded_ext {
-        cmd.arg("--expanded-ext").arg(ext);
-    }
-    if let Some(dir) = &pass.expanded_adoc_dir {
-        cmd.arg("--expanded-adoc-dir").arg(dir);
-    }
-    if let Some(dir) = &pass.expanded_md_dir {
-        cmd.arg("--expanded-md-dir").arg(dir);
-    }
-    if pass.macro_only {
-        cmd.arg("--macro-only");
-    }
-    if let Some(od) = &pass.open_delim {
-        cmd.arg("--open-delim").arg(od);
-    }
-    if let Some(cd) = &pass.close_delim {
-        cmd.arg("--close-delim").arg(cd);
+
+/// One `[profiles.NAME]` section in `weaveback.toml`.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct ProfileCfg {
+    /// Replaces the top-level `gen` for this profile; defaults to `<gen>/NAME`.
+    #[serde(rename = "gen")]
+    pub default_gen: Option<String>,
+}
+
+/// The database a run of `profile` records into: `weaveback.db`, or
+/// `weaveback.NAME.db` for a profile.
+pub fn profile_db_path(profile: Option<&str>) -> std::path::PathBuf {
+    match profile {
+        Some(name) => std::path::PathBuf::from(format!("weaveback.{name}.db")),
+        None => std::path::PathBuf::from("weaveback.db"),
     }
-    if let Some(ce) = &pass.chunk_end {
-        cmd.arg("--chunk-end").arg(ce);
+}
+
+impl TangleCfg {
+    /// Retarget every pass to the profile `name`: passes using the top-level
+    /// `gen` write to the profile's `gen` (default `<gen>/NAME`), passes with
+    /// their own `gen` to `<gen>/NAME`.
+    pub fn select_profile(&mut self, name: &str) -> Result<(), std::io::Error> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(std::io::Error::other(format!(
+                "profile name `{name}` may only contain letters, digits, `-` and `_`"
+            )));
+        }
+        let Some(profile) = self.profiles.get(name) else {
+            return Err(std::io::Error::other(format!("no [profiles.{name}] section")));
+        };
+        let in_profile = |dir: &str| std::path::Path::new(dir).join(name).to_string_lossy().into_owned();
+        let default_gen = profile.default_gen.clone().unwrap_or_else(|| {
+            in_profile(self.default_gen.as_deref().unwrap_or("."))
+        });
+        self.default_gen = Some(default_gen);
+        for pass in &mut self.passes {
+            if let Some(dir) = &mut pass.output_dir {
+                *dir = in_profile(dir);
+            }
+        }
+        self.profile = Some(name.to_string());
+        Ok(())
     }
-    if let Some(cm) = &pass.comment_markers {
-        cmd.arg("--comment-markers").arg(cm);
+
+    /// The database this configuration's runs record into.
+    pub fn db_path(&self) -> std::path::PathBuf {
+        profile_db_path(self.profile.as_deref())
     }
-    if let Some(s) = &pass.sigil {
-        cmd.arg("--sigil").arg(s);
+}
+/// Read, parse and resolve `weaveback.toml`; errors name the config file.
+/// Its paths are resolved against the directory holding the config.
+pub fn load_tangle_cfg(config_path: &std::path::Path) -> Result<TangleCfg, std::io::Error> {
+    let src = std::fs::read_to_string(config_path)
+        .map_err(|e| std::io::Error::new(e.kind(),
+            format!("{}: {e}", config_path.display())))?;
+    let root = config_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    parse_tangle_cfg(&src, root)
+        .map_err(|e| std::io::Error::new(e.kind(),
+            format!("{}: {e}", config_path.display())))
+}
+
+/// `load_tangle_cfg`, retargeted to `opts.profile` if one is selected.
+pub fn load_profiled_cfg(
+    config_path: &std::path::Path,
+    opts: &TangleRunOptions,
+) -> Result<TangleCfg, std::io::Error> {
+    let mut cfg = load_tangle_cfg(config_path)?;
+    if let Some(profile) = &opts.profile {
+        cfg.select_profile(profile)
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {e}", config_path.display())))?;
     }
-    cmd.arg("--no-fts");
-    cmd
+    Ok(cfg)
 }
+
 /// Run every pass in `weaveback.toml` and, if a db exists, rebuild FTS /
 /// run LLM tagging / run embedding.
 ///
 /// Returns `Err` if config cannot be read/parsed or if any pass fails.
 pub fn run_tangle_all(
     config_path: &std::path::Path,
-    force_generated: bool,
+    opts: &TangleRunOptions,
 ) -> Result<(), std::io::Error> {
-    let src = std::fs::read_to_string(config_path)
-        .map_err(|e| std::io::Error::new(e.kind(),
-            format!("{}: {e}", config_path.display())))?;
-    let cfg: TangleCfg = toml::from_str(&src)
-        .map_err(|e| std::io::Error::other(
-            format!("{}: {e}", config_path.display())))?;
+    run_tangle_selected(config_path, opts, &PassSelection::default())
+}
 
-    let exe = std::env::current_exe()?;
-    let default_gen = cfg.default_gen.as_deref().unwrap_or(".");
+/// Like `run_tangle_all`, but run only the passes picked by `selection`
+/// and the passes that depend on them.
+pub fn run_tangle_selected(
+    config_path: &std::path::Path,
+    opts: &TangleRunOptions,
+    selection: &PassSelection,
+) -> Result<(), std::io::Error> {
+    let cfg = load_profiled_cfg(config_path, opts)?;
 
-    for pass in &cfg.passes {
-        let mut cmd = build_pass_cmd(&exe, pass, default_gen, force_generated);
-        match cmd.status() {
-            Err(e) => {
-                return Err(std::io::Error::other(format!("{}: {e}", pass.dir)));
-            }
-            Ok(s) if !s.success() => {
-                return Err(std::io::Error::other(format!(
-                    "tangle pass failed for: {}",
-                    pass.dir
-                )));
-            }
-            Ok(_) => {}
+    // Bring an older database up to date so pass selection can read it.
+    let db_path = cfg.db_path();
+    if !opts.check && db_path.exists() {
+        weaveback_tangle::db::WeavebackDb::open(&db_path).map_err(std::io::Error::other)?;
+    }
+    let indices = select_passes(&cfg, selection)?;
+    if indices.is_empty() {
+        eprintln!("no pass selected; nothing to do");
+        return Ok(());
+    }
+    let failures = run_passes(&cfg, &indices, opts)?;
+    for failure in &failures {
+        eprintln!("error: {}", failure.describe());
+    }
+    let failed: Vec<&str> = failures.iter().map(|f| f.pass.as_str()).collect();
+
+    if opts.check {
+        if failed.is_empty() {
+            return Ok(());
         }
+        return Err(std::io::Error::other(format!(
+            "check failed for: {}",
+            failed.join(", ")
+        )));
+    }
+    if !failed.is_empty() {
+        return Err(std::io::Error::other(format!(
+            "tangle pass failed for: {}",
+            failed.join(", ")
+        )));
     }
 
-    let db_path = std::path::Path::new("weaveback.db");
     if db_path.exists() {
-        match weaveback_tangle::db::WeavebackDb::open(db_path) {
+        match weaveback_tangle::db::WeavebackDb::open(&db_path) {
             Ok(mut db) => {
                 if let Some(tags_cfg) = &cfg.tags {
                     crate::tag::run_auto_tag(&mut db, &crate::tag::TagConfig {
@@ -174,7 +283,7 @@ pub fn run_tangle_all(
                         batch_size: embed_cfg.batch_size,
                     });
                 }
-                if let Err(e) = db.rebuild_prose_fts(None) {
+                if let Err(e) = db.rebuild_fts(None) {
                     eprintln!("warning: FTS index rebuild failed: {e}");
                 }
             }
@@ -0,0 +1,335 @@
+// weaveback-api/src/tangle/passes.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use weaveback_tangle::db::WeavebackDb;
+
+use super::{
+    PassSelection, TangleCfg, TanglePassCfg, TangleRunOptions, pass_dependencies, profile_db_path,
+};
+use crate::process::{
+    PassContext, ProcessError, Projection, SinglePassArgs, is_markdown_ext, pass_key,
+    run_single_pass_with,
+};
+
+/// `expanded_adoc_dir` of a pass that sets none.
+pub(super) const DEFAULT_EXPANDED_ADOC_DIR: &str = "expanded-adoc";
+/// `expanded_md_dir` of a pass that sets none.
+pub(super) const DEFAULT_EXPANDED_MD_DIR: &str = "expanded-md";
+
+/// Extension a pass scans for (`md` when `ext` is unset, like the CLI).
+pub(super) fn pass_ext(pass: &TanglePassCfg) -> &str {
+    pass.ext.as_deref().unwrap_or("md")
+}
+
+/// Build the `run_single_pass` arguments for one `[[pass]]`.
+pub fn pass_args(
+    pass: &TanglePassCfg,
+    default_gen: &str,
+    opts: &TangleRunOptions,
+) -> SinglePassArgs {
+    let expanded_adoc_dir = pass.expanded_adoc_dir.as_deref().unwrap_or(DEFAULT_EXPANDED_ADOC_DIR);
+    let expanded_md_dir = pass.expanded_md_dir.as_deref().unwrap_or(DEFAULT_EXPANDED_MD_DIR);
+    let projections = pass
+        .projections
+        .iter()
+        .map(|p| {
+            let default_dir = if is_markdown_ext(Some(&p.expanded_ext)) {
+                expanded_md_dir
+            } else {
+                expanded_adoc_dir
+            };
+            Projection {
+                macro_prelude: pass.macro_prelude.iter().chain(&p.prelude).map(PathBuf::from).collect(),
+                expanded_ext: Some(p.expanded_ext.clone()),
+                expanded_dir: Some(PathBuf::from(p.expanded_dir.as_deref().unwrap_or(default_dir))),
+                tangle: p.tangle,
+            }
+        })
+        .collect();
+    SinglePassArgs {
+        inputs:          Vec::new(),
+        directory:       Some(PathBuf::from(&pass.dir)),
+        input_dir:       PathBuf::from("."),
+        gen_dir:         PathBuf::from(pass.output_dir.as_deref().unwrap_or(default_gen)),
+        open_delim:      pass.open_delim.clone().unwrap_or_else(|| "<[".to_string()),
+        close_delim:     pass.close_delim.clone().unwrap_or_else(|| "]>".to_string()),
+        chunk_end:       pass.chunk_end.clone().unwrap_or_else(|| "@".to_string()),
+        comment_markers: pass.comment_markers.clone().unwrap_or_else(|| "#,//".to_string()),
+        ext:             vec![pass_ext(pass).to_string()],
+        no_macros:       pass.no_macros,
+        macro_prelude:   pass.macro_prelude.iter().map(PathBuf::from).collect(),
+        expanded_ext:    pass.expanded_ext.clone(),
+        expanded_adoc_dir: PathBuf::from(expanded_adoc_dir),
+        expanded_md_dir: PathBuf::from(expanded_md_dir),
+        macro_only:      pass.macro_only,
+        projections,
+        dry_run:         false,
+        db:              profile_db_path(opts.profile.as_deref()),
+        depfile:         None,
+        stamp:           None,
+        strict:          false,
+        profile:         opts.profile.clone(),
+        warn_unused:     false,
+        allow_env:       false,
+        allow_home:      false,
+        force_generated: opts.force_generated,
+        prune:           opts.prune,
+        check:           opts.check,
+        merge:           opts.merge,
+        sigil:           pass.sigil.as_deref().and_then(|s| s.chars().next()).unwrap_or('%'),
+        include:         ".".to_string(),
+        formatter:       Vec::new(),
+        no_fts:          true,
+        dump_expanded:   false,
+        project_root:    None,
+        pass_name:       pass.name.clone(),
+    }
+}
+fn lexical(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .collect()
+}
+
+fn reads_file(reader: &TanglePassCfg, file: &Path) -> bool {
+    file.extension().and_then(|e| e.to_str()) == Some(pass_ext(reader))
+        && lexical(file).starts_with(lexical(Path::new(&reader.dir)))
+}
+
+/// Generated files the last run of `writer` recorded, under its `gen` dir.
+fn recorded_outputs(
+    writer: &TanglePassCfg,
+    default_gen: &str,
+    prior: Option<&WeavebackDb>,
+) -> Vec<PathBuf> {
+    let args = pass_args(writer, default_gen, &TangleRunOptions::default());
+    let (Some(prior), true) = (prior, args.tangles()) else {
+        return Vec::new();
+    };
+    prior
+        .get_pass_outputs(&pass_key(&args))
+        .unwrap_or_default()
+        .iter()
+        .map(|out| args.gen_dir.join(out))
+        .collect()
+}
+
+/// Whether `writer`, which last produced `outputs`, writes files `reader` reads.
+fn writes_into(writer: &TanglePassCfg, outputs: &[PathBuf], reader: &TanglePassCfg) -> bool {
+    let args = pass_args(writer, "", &TangleRunOptions::default());
+    for projection in args.effective_projections() {
+        let Some(dir) = &projection.expanded_dir else {
+            continue;
+        };
+        let ext = projection.expanded_ext.as_deref().unwrap_or_else(|| pass_ext(writer));
+        let (dir, reader_dir) = (lexical(dir), lexical(Path::new(&reader.dir)));
+        if ext.trim_start_matches('.') == pass_ext(reader)
+            && (dir.starts_with(&reader_dir) || reader_dir.starts_with(&dir))
+        {
+            return true;
+        }
+    }
+    outputs.iter().any(|out| reads_file(reader, out))
+}
+
+/// For every pass, the indices of the passes it waits for.
+///
+/// `prior` is the persistent database of the previous run, if any; it tells
+/// which generated files each pass produces.
+pub fn pass_prerequisites(
+    cfg: &TangleCfg,
+    prior: Option<&WeavebackDb>,
+) -> Result<Vec<Vec<usize>>, std::io::Error> {
+    let default_gen = cfg.default_gen.as_deref().unwrap_or(".");
+    let mut by_name: HashMap<&str, usize> = HashMap::new();
+    for (i, pass) in cfg.passes.iter().enumerate() {
+        if let Some(name) = &pass.name
+            && by_name.insert(name, i).is_some()
+        {
+            return Err(std::io::Error::other(format!("duplicate pass name `{name}`")));
+        }
+    }
+    let outputs: Vec<Vec<PathBuf>> = cfg
+        .passes
+        .iter()
+        .map(|pass| recorded_outputs(pass, default_gen, prior))
+        .collect();
+
+    let mut prerequisites = Vec::with_capacity(cfg.passes.len());
+    for (j, reader) in cfg.passes.iter().enumerate() {
+        let mut waits_for: BTreeSet<usize> = (0..j)
+            .filter(|&i| writes_into(&cfg.passes[i], &outputs[i], reader))
+            .collect();
+        for dep in &reader.depends_on {
+            let Some(&i) = by_name.get(dep.as_str()) else {
+                return Err(std::io::Error::other(format!(
+                    "pass `{}` depends on unknown pass `{dep}`",
+                    reader.label()
+                )));
+            };
+            waits_for.insert(i);
+        }
+        prerequisites.push(waits_for.into_iter().collect());
+    }
+    Ok(prerequisites)
+}
+
+/// Group the passes at `indices` into waves that can run concurrently.
+///
+/// Prerequisites outside `indices` are taken as already satisfied.
+pub fn pass_waves(
+    cfg: &TangleCfg,
+    prerequisites: &[Vec<usize>],
+    indices: &[usize],
+) -> Result<Vec<Vec<usize>>, std::io::Error> {
+    let selected: HashSet<usize> = indices.iter().copied().collect();
+    let mut wave_of: HashMap<usize, usize> = HashMap::new();
+    let mut pending: Vec<usize> = indices.to_vec();
+    pending.sort_unstable();
+    while !pending.is_empty() {
+        let before = pending.len();
+        pending.retain(|&j| {
+            let waits: Option<Vec<usize>> = prerequisites[j]
+                .iter()
+                .filter(|i| selected.contains(i))
+                .map(|i| wave_of.get(i).copied())
+                .collect();
+            match waits {
+                Some(waits) => {
+                    wave_of.insert(j, waits.into_iter().map(|w| w + 1).max().unwrap_or(0));
+                    false
+                }
+                None => true,
+            }
+        });
+        if pending.len() == before {
+            let names: Vec<&str> = pending.iter().map(|&j| cfg.passes[j].label()).collect();
+            return Err(std::io::Error::other(format!(
+                "dependency cycle between passes: {}",
+                names.join(", ")
+            )));
+        }
+    }
+
+    let mut waves: Vec<Vec<usize>> = vec![Vec::new(); wave_of.values().max().map_or(0, |w| w + 1)];
+    let mut ordered: Vec<(usize, usize)> = wave_of.into_iter().collect();
+    ordered.sort_unstable();
+    for (j, w) in ordered {
+        waves[w].push(j);
+    }
+    Ok(waves)
+}
+/// Whether any input of `pass` differs from its snapshot in `prior`.
+fn pass_changed(pass: &TanglePassCfg, prior: &WeavebackDb, root: &Path) -> bool {
+    pass_dependencies(pass).iter().any(|path| {
+        let key = path.strip_prefix(root).unwrap_or(path).to_string_lossy().into_owned();
+        let Ok(Some(snapshot)) = prior.get_src_snapshot(&key) else {
+            return true;
+        };
+        std::fs::read(path).map_or(true, |content| content != snapshot)
+    })
+}
+
+/// Indices (in config order) of the passes `selection` asks for, plus all
+/// passes that depend on them.
+pub fn select_passes(
+    cfg: &TangleCfg,
+    selection: &PassSelection,
+) -> Result<Vec<usize>, std::io::Error> {
+    let db_path = cfg.db_path();
+    let prior = if db_path.exists() { WeavebackDb::open_read_only(&db_path).ok() } else { None };
+    let prerequisites = pass_prerequisites(cfg, prior.as_ref())?;
+
+    let mut selected: BTreeSet<usize> = if selection.names.is_empty() {
+        (0..cfg.passes.len()).collect()
+    } else {
+        let mut named = BTreeSet::new();
+        for name in &selection.names {
+            let Some(i) = cfg.passes.iter().position(|p| p.name.as_deref() == Some(name.as_str())) else {
+                return Err(std::io::Error::other(format!("no pass named `{name}`")));
+            };
+            named.insert(i);
+        }
+        named
+    };
+    if selection.only_changed && let Some(prior) = &prior {
+        let root = std::env::current_dir()?.canonicalize()?;
+        selected.retain(|&i| pass_changed(&cfg.passes[i], prior, &root));
+    }
+
+    loop {
+        let dependents: Vec<usize> = (0..cfg.passes.len())
+            .filter(|j| !selected.contains(j))
+            .filter(|&j| prerequisites[j].iter().any(|i| selected.contains(i)))
+            .collect();
+        if dependents.is_empty() {
+            break;
+        }
+        selected.extend(dependents);
+    }
+    Ok(selected.into_iter().collect())
+}
+/// A pass that failed, identified by its name, or its `dir` if unnamed.
+#[derive(Debug)]
+pub struct PassFailure {
+    pub pass: String,
+    pub error: ProcessError,
+}
+
+impl PassFailure {
+    /// The error with its full chain of causes, on one line.  Causes whose
+    /// message already appears (wrapper errors often embed it) are skipped.
+    pub fn describe(&self) -> String {
+        let mut text = self.error.to_string();
+        let mut source = std::error::Error::source(&self.error);
+        while let Some(cause) = source {
+            let message = cause.to_string();
+            if !text.contains(&message) {
+                text.push_str(": ");
+                text.push_str(&message);
+            }
+            source = cause.source();
+        }
+        format!("{}: {text}", self.pass)
+    }
+}
+
+/// Run the passes at `indices` in-process, independent passes concurrently.
+///
+/// Returns the failed passes in config order.
+pub fn run_passes(
+    cfg: &TangleCfg,
+    indices: &[usize],
+    opts: &TangleRunOptions,
+) -> Result<Vec<PassFailure>, std::io::Error> {
+    let default_gen = cfg.default_gen.as_deref().unwrap_or(".");
+    let db_path = cfg.db_path();
+    let prior = if db_path.exists() { WeavebackDb::open_read_only(&db_path).ok() } else { None };
+    let prerequisites = pass_prerequisites(cfg, prior.as_ref())?;
+    drop(prior);
+    let waves = pass_waves(cfg, &prerequisites, indices)?;
+
+    let ctx = PassContext::new();
+    let mut failures = Vec::new();
+    for wave in waves {
+        let mut failed: Vec<PassFailure> = wave
+            .par_iter()
+            .filter_map(|&i| {
+                let pass = &cfg.passes[i];
+                run_single_pass_with(pass_args(pass, default_gen, opts), &ctx)
+                    .err()
+                    .map(|error| PassFailure { pass: pass.label().to_string(), error })
+            })
+            .collect();
+        let stop = !failed.is_empty() && !opts.check;
+        failures.append(&mut failed);
+        if stop {
+            break;
+        }
+    }
+    Ok(failures)
+}
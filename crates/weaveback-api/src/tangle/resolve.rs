@@ -0,0 +1,232 @@
+// weaveback-api/src/tangle/resolve.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use toml::{Table, Value};
+use weaveback_core::glob::glob_match;
+
+use super::TangleCfg;
+use super::passes::{DEFAULT_EXPANDED_ADOC_DIR, DEFAULT_EXPANDED_MD_DIR};
+
+/// Placeholder replaced by the directory a globbed `dir` matched.
+const CRATE_PLACEHOLDER: &str = "{crate}";
+
+/// Pass keys holding a path or a list of paths.
+const PASS_PATH_KEYS: &[&str] =
+    &["dir", "gen", "macro_prelude", "expanded_adoc_dir", "expanded_md_dir"];
+
+/// Path keys of one `projections` entry.
+const PROJECTION_PATH_KEYS: &[&str] = &["expanded_dir", "prelude"];
+
+/// Parse `weaveback.toml` text, applying `[defaults]`, `[template.*]` and
+/// globbed `dir`s.  Paths in the config are relative to `root`, where globs
+/// are expanded too; every path of the result is rebased onto `root` so it
+/// can be used from the working directory.
+pub fn parse_tangle_cfg(src: &str, root: &Path) -> io::Result<TangleCfg> {
+    let mut doc: Table = toml::from_str(src).map_err(io::Error::other)?;
+    let defaults = take_table(&mut doc, "defaults")?;
+    let templates = take_table(&mut doc, "template")?;
+    let passes = match doc.remove("pass") {
+        None => Vec::new(),
+        Some(Value::Array(passes)) => passes,
+        Some(_) => return Err(io::Error::other("`pass` must be an array of tables ([[pass]])")),
+    };
+
+    let mut resolved = Vec::with_capacity(passes.len());
+    for (i, pass) in passes.into_iter().enumerate() {
+        let Value::Table(pass) = pass else {
+            return Err(io::Error::other(format!("pass #{}: expected a table", i + 1)));
+        };
+        let label = pass
+            .get("name")
+            .and_then(Value::as_str)
+            .map_or_else(|| format!("#{}", i + 1), |name| format!("`{name}`"));
+        let mut merged = defaults.clone();
+        if let Some(template) = pass.get("use") {
+            let template = template.as_str().ok_or_else(|| {
+                io::Error::other(format!("pass {label}: `use` must be a string"))
+            })?;
+            match templates.get(template) {
+                Some(Value::Table(keys)) => merged.extend(keys.clone()),
+                _ => {
+                    return Err(io::Error::other(format!(
+                        "pass {label}: unknown template `{template}`"
+                    )));
+                }
+            }
+        }
+        merged.extend(pass);
+        merged.remove("use");
+
+        let Some(dir) = merged.get("dir").and_then(Value::as_str).map(str::to_string) else {
+            return Err(io::Error::other(format!("pass {label}: missing `dir`")));
+        };
+        if !dir.contains('*') {
+            if mentions_placeholder(&Value::Table(merged.clone())) {
+                return Err(io::Error::other(format!(
+                    "pass {label}: `{CRATE_PLACEHOLDER}` needs a globbed `dir`"
+                )));
+            }
+            resolved.push(Value::Table(merged));
+            continue;
+        }
+        let matches = expand_dir_glob(root, &dir);
+        if matches.is_empty() {
+            return Err(io::Error::other(format!(
+                "pass {label}: `{dir}` matches no directory"
+            )));
+        }
+        for (path, krate) in matches {
+            let mut pass = merged.clone();
+            pass.insert("dir".to_string(), Value::String(path));
+            let mut pass = Value::Table(pass);
+            substitute_placeholder(&mut pass, &krate);
+            resolved.push(pass);
+        }
+    }
+    if root != Path::new(".") {
+        for pass in &mut resolved {
+            rebase_pass(pass, root);
+        }
+        doc.entry("gen").or_insert_with(|| Value::String(".".to_string()));
+        rebase_keys(&mut doc, &["gen"], root);
+        if let Some(Value::Table(profiles)) = doc.get_mut("profiles") {
+            for (_, profile) in profiles.iter_mut() {
+                if let Value::Table(profile) = profile {
+                    rebase_keys(profile, &["gen"], root);
+                }
+            }
+        }
+    }
+    doc.insert("pass".to_string(), Value::Array(resolved));
+    Value::Table(doc).try_into().map_err(io::Error::other)
+}
+
+/// The resolved config as TOML, as printed by `wb-tangle --print-config`.
+pub fn render_tangle_cfg(cfg: &TangleCfg) -> io::Result<String> {
+    toml::to_string(cfg).map_err(io::Error::other)
+}
+
+fn take_table(doc: &mut Table, key: &str) -> io::Result<Table> {
+    match doc.remove(key) {
+        None => Ok(Table::new()),
+        Some(Value::Table(table)) => Ok(table),
+        Some(_) => Err(io::Error::other(format!("`{key}` must be a table"))),
+    }
+}
+
+/// Directories matching `pattern` below `root`, sorted, each with the name
+/// matched by the first wildcard component.  The paths are relative to
+/// `root`, like `pattern`, and keep its trailing `/`.
+fn expand_dir_glob(root: &Path, pattern: &str) -> Vec<(String, String)> {
+    let trailing = if pattern.ends_with('/') { "/" } else { "" };
+    let mut found: Vec<(Vec<String>, Option<String>)> = vec![(Vec::new(), None)];
+    for component in pattern.split('/').filter(|c| !c.is_empty()) {
+        if !component.contains('*') {
+            for (parts, _) in &mut found {
+                parts.push(component.to_string());
+            }
+            continue;
+        }
+        let mut next = Vec::new();
+        for (parts, krate) in found {
+            let dir = parts.iter().fold(root.to_path_buf(), |dir, part| dir.join(part));
+            let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+            let mut names: Vec<String> = entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| !name.starts_with('.') && glob_match(component, name))
+                .collect();
+            names.sort();
+            for name in names {
+                let mut parts = parts.clone();
+                parts.push(name.clone());
+                next.push((parts, Some(krate.clone().unwrap_or(name))));
+            }
+        }
+        found = next;
+    }
+    found
+        .into_iter()
+        .filter_map(|(parts, krate)| {
+            let dir = parts.iter().fold(root.to_path_buf(), |d, p| d.join(p));
+            if !dir.is_dir() {
+                return None;
+            }
+            Some((format!("{}{trailing}", parts.join("/")), krate.unwrap_or_default()))
+        })
+        .collect()
+}
+
+/// Rebase the paths of a resolved pass onto `root`.  The expanded
+/// directories are set first when missing, as their defaults are relative
+/// to the config as well.
+fn rebase_pass(pass: &mut Value, root: &Path) {
+    let Value::Table(pass) = pass else { return };
+    for (key, default) in [
+        ("expanded_adoc_dir", DEFAULT_EXPANDED_ADOC_DIR),
+        ("expanded_md_dir", DEFAULT_EXPANDED_MD_DIR),
+    ] {
+        pass.entry(key).or_insert_with(|| Value::String(default.to_string()));
+    }
+    rebase_keys(pass, PASS_PATH_KEYS, root);
+    if let Some(Value::Array(projections)) = pass.get_mut("projections") {
+        for projection in projections {
+            if let Value::Table(projection) = projection {
+                rebase_keys(projection, PROJECTION_PATH_KEYS, root);
+            }
+        }
+    }
+}
+
+fn rebase_keys(table: &mut Table, keys: &[&str], root: &Path) {
+    for key in keys {
+        match table.get_mut(*key) {
+            Some(Value::String(path)) => *path = rebase(root, path),
+            Some(Value::Array(paths)) => {
+                for path in paths {
+                    if let Value::String(path) = path {
+                        *path = rebase(root, path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `path`, relative to `root`, as seen from the working directory.  Absolute
+/// paths are kept, and so is a trailing `/`.
+fn rebase(root: &Path, path: &str) -> String {
+    let joined: PathBuf = root
+        .join(path)
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect();
+    if joined.as_os_str().is_empty() {
+        return ".".to_string();
+    }
+    let trailing = if path.ends_with('/') { "/" } else { "" };
+    format!("{}{trailing}", joined.to_string_lossy())
+}
+
+fn mentions_placeholder(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.contains(CRATE_PLACEHOLDER),
+        Value::Array(items) => items.iter().any(mentions_placeholder),
+        Value::Table(table) => table.values().any(mentions_placeholder),
+        _ => false,
+    }
+}
+
+fn substitute_placeholder(value: &mut Value, krate: &str) {
+    match value {
+        Value::String(s) => *s = s.replace(CRATE_PLACEHOLDER, krate),
+        Value::Array(items) => items.iter_mut().for_each(|v| substitute_placeholder(v, krate)),
+        Value::Table(table) => table.iter_mut().for_each(|(_, v)| substitute_placeholder(v, krate)),
+        _ => {}
+    }
+}
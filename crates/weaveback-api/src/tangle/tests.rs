@@ -1,9 +1,10 @@
 // weaveback-api/src/tangle/tests.rs
 // I'd Really Rather You Didn't edit this generated file.
 
-mod command;
 mod config;
+mod passes;
 mod run;
+mod watch;
 
 use super::*;
 use tempfile::TempDir;
@@ -23,6 +23,40 @@ close_delim = ">>"
     assert_eq!(cfg.passes[0].open_delim.as_deref(), Some("<<"));
 }
 
+#[test]
+fn select_profile_moves_passes_into_profile_gen_dirs() {
+    let toml_src = r#"
+gen = "gen"
+
+[profiles.embedded]
+[profiles.hosted]
+gen = "build/hosted"
+
+[[pass]]
+dir = "src/"
+
+[[pass]]
+dir = "docs/"
+gen = "site"
+"#;
+    let mut embedded: TangleCfg = toml::from_str(toml_src).unwrap();
+    embedded.select_profile("embedded").unwrap();
+    assert_eq!(embedded.default_gen.as_deref(), Some("gen/embedded"));
+    assert_eq!(embedded.passes[1].output_dir.as_deref(), Some("site/embedded"));
+    assert_eq!(embedded.db_path(), std::path::PathBuf::from("weaveback.embedded.db"));
+
+    let mut hosted: TangleCfg = toml::from_str(toml_src).unwrap();
+    assert_eq!(hosted.db_path(), std::path::PathBuf::from("weaveback.db"));
+    hosted.select_profile("hosted").unwrap();
+    assert_eq!(hosted.default_gen.as_deref(), Some("build/hosted"));
+    assert_eq!(hosted.passes[1].output_dir.as_deref(), Some("site/hosted"));
+
+    let err = hosted.select_profile("desktop").unwrap_err();
+    assert!(err.to_string().contains("[profiles.desktop]"), "{err}");
+    let err = hosted.select_profile("../x").unwrap_err();
+    assert!(err.to_string().contains("may only contain"), "{err}");
+}
+
 #[test]
 fn default_tags_values_are_sensible() {
     assert!(!default_tags_backend().is_empty());
@@ -73,3 +107,197 @@ model = "text-embedding-3-small"
     assert_eq!(eb.model, "text-embedding-3-small");
     assert_eq!(eb.batch_size, crate::semantic::default_embeddings_batch_size());
 }
+
+#[test]
+fn defaults_and_templates_layer_under_pass_keys() {
+    let toml_src = r#"
+[defaults]
+ext = "wvb"
+sigil = "¤"
+macro_prelude = ["prelude/base.wvb"]
+
+[template.docs]
+expanded_ext = "adoc"
+macro_prelude = ["prelude/asciidoc.wvb"]
+
+[[pass]]
+dir = "a/"
+use = "docs"
+sigil = "%"
+
+[[pass]]
+dir = "b/"
+"#;
+    let cfg = parse_tangle_cfg(toml_src, std::path::Path::new(".")).unwrap();
+    let (a, b) = (&cfg.passes[0], &cfg.passes[1]);
+    assert_eq!(a.ext.as_deref(), Some("wvb"));
+    assert_eq!(a.sigil.as_deref(), Some("%"));
+    assert_eq!(a.expanded_ext.as_deref(), Some("adoc"));
+    assert_eq!(a.macro_prelude, ["prelude/asciidoc.wvb"]);
+    assert_eq!(b.sigil.as_deref(), Some("¤"));
+    assert_eq!(b.expanded_ext, None);
+    assert_eq!(b.macro_prelude, ["prelude/base.wvb"]);
+}
+
+#[test]
+fn unknown_template_is_an_error() {
+    let toml_src = "[[pass]]\nname = \"docs\"\ndir = \"a/\"\nuse = \"missing\"\n";
+    let err = parse_tangle_cfg(toml_src, std::path::Path::new(".")).err().unwrap();
+    assert_eq!(err.to_string(), "pass `docs`: unknown template `missing`");
+}
+
+#[test]
+fn globbed_dir_expands_per_directory_with_crate_placeholder() {
+    let tmp = TempDir::new().unwrap();
+    for krate in ["beta", "alpha", "no-sources", ".hidden"] {
+        std::fs::create_dir_all(tmp.path().join("crates").join(krate)).unwrap();
+    }
+    std::fs::create_dir_all(tmp.path().join("crates/alpha/src-wvb")).unwrap();
+    std::fs::create_dir_all(tmp.path().join("crates/beta/src-wvb")).unwrap();
+    std::fs::create_dir_all(tmp.path().join("crates/.hidden/src-wvb")).unwrap();
+    let toml_src = r#"
+[[pass]]
+dir = "first/"
+
+[[pass]]
+name = "{crate}-docs"
+dir = "crates/*/src-wvb/"
+expanded_adoc_dir = "expanded-adoc/crates/{crate}/src"
+
+[[pass]]
+dir = "last/"
+"#;
+    let cfg = parse_tangle_cfg(toml_src, tmp.path()).unwrap();
+    let dirs: Vec<&str> = cfg.passes.iter().map(|p| p.dir.as_str()).collect();
+    let under_root = |dir: &str| tmp.path().join(dir).to_string_lossy().into_owned();
+    assert_eq!(dirs, [
+        under_root("first/"),
+        under_root("crates/alpha/src-wvb/"),
+        under_root("crates/beta/src-wvb/"),
+        under_root("last/"),
+    ]);
+    assert_eq!(cfg.passes[1].name.as_deref(), Some("alpha-docs"));
+    assert_eq!(
+        cfg.passes[2].expanded_adoc_dir,
+        Some(under_root("expanded-adoc/crates/beta/src"))
+    );
+}
+
+#[test]
+fn loaded_config_expands_globs_next_to_the_config_file() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join("sub/crates/alpha")).unwrap();
+    let config = tmp.path().join("sub/weaveback.toml");
+    std::fs::write(&config, "[[pass]]\ndir = \"crates/*/\"\n").unwrap();
+    let cfg = load_tangle_cfg(&config).unwrap();
+    let dirs: Vec<&str> = cfg.passes.iter().map(|p| p.dir.as_str()).collect();
+    assert_eq!(dirs, [tmp.path().join("sub/crates/alpha/").to_string_lossy()]);
+}
+
+#[test]
+fn every_config_path_is_relative_to_the_config_directory() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join("sub/crates/alpha")).unwrap();
+    let config = tmp.path().join("sub/weaveback.toml");
+    let toml_src = r#"
+gen = "out"
+
+[profiles.release]
+gen = "release"
+
+[[pass]]
+dir = "docs/"
+macro_prelude = ["prelude/common.wvb"]
+
+[[pass]]
+dir = "crates/*/"
+gen = "gen/{crate}"
+expanded_md_dir = "expanded-md/{crate}"
+
+[[pass.projections]]
+expanded_ext = "md"
+prelude = "prelude/md.wvb"
+expanded_dir = "docs/{crate}"
+"#;
+    std::fs::write(&config, toml_src).unwrap();
+
+    let cfg = load_tangle_cfg(&config).unwrap();
+
+    let sub = |path: &str| tmp.path().join("sub").join(path).to_string_lossy().into_owned();
+    assert_eq!(cfg.default_gen, Some(sub("out")));
+    assert_eq!(cfg.profiles["release"].default_gen, Some(sub("release")));
+    let docs = &cfg.passes[0];
+    assert_eq!(docs.dir, sub("docs/"));
+    assert_eq!(docs.macro_prelude, [sub("prelude/common.wvb")]);
+    assert_eq!(docs.expanded_adoc_dir, Some(sub("expanded-adoc")));
+    let alpha = &cfg.passes[1];
+    assert_eq!(alpha.dir, sub("crates/alpha/"));
+    assert_eq!(alpha.output_dir, Some(sub("gen/alpha")));
+    assert_eq!(alpha.expanded_md_dir, Some(sub("expanded-md/alpha")));
+    assert_eq!(alpha.projections[0].prelude, Some(sub("prelude/md.wvb")));
+    assert_eq!(alpha.projections[0].expanded_dir, Some(sub("docs/alpha")));
+}
+
+#[test]
+fn globbed_pass_runs_from_outside_the_config_directory() {
+    let tmp = TempDir::new().unwrap();
+    let alpha = tmp.path().join("sub/crates/alpha");
+    std::fs::create_dir_all(&alpha).unwrap();
+    std::fs::write(alpha.join("notes.adoc"), "<<@file out.txt>>=\nhello\n@\n").unwrap();
+    let config = tmp.path().join("sub/weaveback.toml");
+    let toml_src = format!(
+        "gen = {:?}\n[[pass]]\ndir = \"crates/*/\"\next = \"adoc\"\nopen_delim = \"<<\"\nclose_delim = \">>\"\n",
+        tmp.path().join("gen").to_str().unwrap(),
+    );
+    std::fs::write(&config, toml_src).unwrap();
+    assert_ne!(std::env::current_dir().unwrap(), tmp.path().join("sub"));
+    let cfg = load_tangle_cfg(&config).unwrap();
+    // Check mode writes neither files nor a database.
+    let opts = TangleRunOptions { check: true, ..TangleRunOptions::default() };
+
+    let failures = run_passes(&cfg, &[0], &opts).unwrap();
+
+    assert_eq!(failures.len(), 1);
+    assert!(
+        matches!(failures[0].error, crate::process::ProcessError::OutOfDate { count: 1 }),
+        "the pass should find notes.adoc, got: {}",
+        failures[0].describe()
+    );
+}
+
+#[test]
+fn crate_placeholder_needs_a_glob_and_a_glob_needs_a_match() {
+    let tmp = TempDir::new().unwrap();
+    let placeholder = "[[pass]]\ndir = \"a/\"\ngen = \"out/{crate}\"\n";
+    let err = parse_tangle_cfg(placeholder, tmp.path()).err().unwrap();
+    assert_eq!(err.to_string(), "pass #1: `{crate}` needs a globbed `dir`");
+
+    let unmatched = "[[pass]]\ndir = \"crates/*/\"\n";
+    let err = parse_tangle_cfg(unmatched, tmp.path()).err().unwrap();
+    assert_eq!(err.to_string(), "pass #1: `crates/*/` matches no directory");
+}
+
+#[test]
+fn rendered_config_parses_back_to_the_same_passes() {
+    let toml_src = r#"
+gen = "crates/"
+
+[defaults]
+ext = "wvb"
+
+[[pass]]
+name = "docs"
+dir = "docs/"
+macro_only = true
+
+[tags]
+backend = "ollama"
+"#;
+    let cfg = parse_tangle_cfg(toml_src, std::path::Path::new(".")).unwrap();
+    let rendered = render_tangle_cfg(&cfg).unwrap();
+    assert!(!rendered.contains("no_macros"), "{rendered}");
+    let again = parse_tangle_cfg(&rendered, std::path::Path::new(".")).unwrap();
+    assert_eq!(render_tangle_cfg(&again).unwrap(), rendered);
+    assert_eq!(again.passes[0].ext.as_deref(), Some("wvb"));
+    assert!(again.passes[0].macro_only);
+}
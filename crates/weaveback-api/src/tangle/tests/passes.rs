@@ -0,0 +1,296 @@
+// weaveback-api/src/tangle/tests/passes.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+use weaveback_tangle::db::WeavebackDb;
+
+fn parse_cfg(toml_src: &str) -> TangleCfg {
+    toml::from_str(toml_src).unwrap()
+}
+
+fn waves(cfg: &TangleCfg, indices: &[usize], prior: Option<&WeavebackDb>) -> Vec<Vec<usize>> {
+    pass_waves(cfg, &pass_prerequisites(cfg, prior).unwrap(), indices).unwrap()
+}
+
+#[test]
+fn pass_args_apply_cli_defaults_and_run_options() {
+    let cfg = parse_cfg("gen = \"crates/\"\n[[pass]]\ndir = \"src/\"\nsigil = \"¤\"\n");
+    let opts = TangleRunOptions { check: true, ..TangleRunOptions::default() };
+
+    let args = pass_args(&cfg.passes[0], "crates/", &opts);
+
+    assert_eq!(args.directory.as_deref(), Some(std::path::Path::new("src/")));
+    assert_eq!(args.gen_dir, std::path::PathBuf::from("crates/"));
+    assert_eq!(args.ext, vec!["md".to_string()]);
+    assert_eq!(args.open_delim, "<[");
+    assert_eq!(args.sigil, '¤');
+    assert!(args.check && args.no_fts);
+    assert_eq!(
+        crate::process::pass_key(&args),
+        "src/|md|crates/",
+        "same key as the subprocess invocation, so pass_outputs carry over"
+    );
+}
+
+#[test]
+fn pass_args_map_projections_onto_pass_preludes_and_dirs() {
+    let cfg = parse_cfg(r#"
+[[pass]]
+dir = "src/"
+ext = "wvb"
+macro_prelude = ["prelude/common.wvb"]
+expanded_md_dir = "docs/md"
+projections = [
+  { prelude = "prelude/asciidoc.wvb", expanded_ext = "adoc", expanded_dir = "out/adoc", tangle = true },
+  { prelude = "prelude/markdown.wvb", expanded_ext = "md" },
+]
+"#);
+    let args = pass_args(&cfg.passes[0], "gen/", &TangleRunOptions::default());
+
+    let preludes: Vec<Vec<&str>> = args
+        .projections
+        .iter()
+        .map(|p| p.macro_prelude.iter().map(|p| p.to_str().unwrap()).collect())
+        .collect();
+    assert_eq!(preludes, [
+        ["prelude/common.wvb", "prelude/asciidoc.wvb"],
+        ["prelude/common.wvb", "prelude/markdown.wvb"],
+    ]);
+    assert_eq!(args.projections[0].expanded_dir.as_deref(), Some(std::path::Path::new("out/adoc")));
+    assert_eq!(args.projections[1].expanded_dir.as_deref(), Some(std::path::Path::new("docs/md")));
+    assert!(args.tangles());
+    assert_eq!(args.effective_projections(), args.projections);
+}
+
+#[test]
+fn pass_reading_a_projection_waits_for_its_writer() {
+    let cfg = parse_cfg(r#"
+[[pass]]
+dir = "src/"
+ext = "wvb"
+projections = [
+  { expanded_ext = "adoc", tangle = true },
+  { expanded_ext = "md", expanded_dir = "docs/gen" },
+]
+[[pass]]
+dir = "docs/"
+"#);
+    assert_eq!(waves(&cfg, &[0, 1], None), vec![vec![0], vec![1]]);
+}
+
+#[test]
+fn independent_passes_share_one_wave() {
+    let cfg = parse_cfg(r#"
+gen = "crates/"
+[[pass]]
+dir = "a/"
+[[pass]]
+dir = "b/"
+ext = "adoc"
+expanded_ext = "adoc"
+expanded_adoc_dir = "expanded-adoc/b"
+"#);
+    assert_eq!(waves(&cfg, &[0, 1], None), vec![vec![0, 1]]);
+}
+
+#[test]
+fn pass_reading_expanded_documents_waits_for_writer() {
+    let cfg = parse_cfg(r#"
+[[pass]]
+dir = "docs/"
+[[pass]]
+dir = "./src/"
+ext = "wvb"
+expanded_ext = "md"
+macro_only = true
+expanded_md_dir = "docs/gen"
+[[pass]]
+dir = "other/"
+"#);
+    assert_eq!(waves(&cfg, &[0, 1, 2], None), vec![vec![0, 1, 2]], "readers never wait for later passes");
+
+    let cfg = parse_cfg(r#"
+[[pass]]
+dir = "./src/"
+ext = "wvb"
+expanded_ext = "md"
+macro_only = true
+expanded_md_dir = "docs/gen"
+[[pass]]
+dir = "docs/"
+[[pass]]
+dir = "other/"
+"#);
+    assert_eq!(waves(&cfg, &[0, 1, 2], None), vec![vec![0, 2], vec![1]]);
+}
+
+#[test]
+fn recorded_outputs_order_dependent_passes() {
+    let cfg = parse_cfg(r#"
+gen = "out/"
+[[pass]]
+dir = "src/"
+ext = "adoc"
+[[pass]]
+dir = "out/docs/"
+"#);
+    let mut prior = WeavebackDb::open_temp().unwrap();
+    let key = crate::process::pass_key(&pass_args(&cfg.passes[0], "out/", &TangleRunOptions::default()));
+
+    prior.set_pass_outputs(&key, None, &["main.rs".to_string()]).unwrap();
+    assert_eq!(waves(&cfg, &[0, 1], Some(&prior)), vec![vec![0, 1]]);
+
+    prior.set_pass_outputs(&key, None, &["docs/guide.md".to_string()]).unwrap();
+    assert_eq!(waves(&cfg, &[0, 1], Some(&prior)), vec![vec![0], vec![1]]);
+}
+
+#[test]
+fn run_passes_names_the_failing_pass() {
+    let dir = TempDir::new().unwrap();
+    let good = dir.path().join("good");
+    std::fs::create_dir_all(&good).unwrap();
+    std::fs::write(good.join("notes.md"), "no chunks here\n").unwrap();
+    let missing = dir.path().join("missing");
+    let toml_src = format!(
+        "gen = {:?}\n[[pass]]\ndir = {:?}\n[[pass]]\ndir = {:?}\n",
+        dir.path().join("gen").to_str().unwrap(),
+        good.to_str().unwrap(),
+        missing.to_str().unwrap(),
+    );
+    let cfg = parse_cfg(&toml_src);
+    // Check mode writes neither files nor a database.
+    let opts = TangleRunOptions { check: true, ..TangleRunOptions::default() };
+
+    let failures = run_passes(&cfg, &[0, 1], &opts).unwrap();
+
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].pass, missing.to_str().unwrap());
+    assert!(failures[0].describe().starts_with(missing.to_str().unwrap()));
+    assert!(matches!(failures[0].error, crate::process::ProcessError::Io { .. }));
+}
+
+#[test]
+fn pass_args_map_every_pass_field() {
+    let cfg = parse_cfg(r#"
+[[pass]]
+dir = "src/"
+gen = "out/"
+ext = "wvb"
+no_macros = true
+macro_prelude = ["prelude/asciidoc.wvb"]
+expanded_ext = "adoc"
+expanded_adoc_dir = "docs/adoc"
+expanded_md_dir = "docs/md"
+macro_only = true
+open_delim = "<<"
+close_delim = ">>"
+chunk_end = "@@"
+comment_markers = ";"
+"#);
+    let opts = TangleRunOptions { prune: true, merge: true, force_generated: true, ..TangleRunOptions::default() };
+
+    let args = pass_args(&cfg.passes[0], "default/", &opts);
+
+    assert_eq!(args.gen_dir, std::path::PathBuf::from("out/"), "the pass's gen overrides the default");
+    assert!(args.no_macros && args.macro_only);
+    assert_eq!(args.macro_prelude, [std::path::PathBuf::from("prelude/asciidoc.wvb")]);
+    assert_eq!(args.expanded_ext.as_deref(), Some("adoc"));
+    assert_eq!(args.expanded_adoc_dir, std::path::PathBuf::from("docs/adoc"));
+    assert_eq!(args.expanded_md_dir, std::path::PathBuf::from("docs/md"));
+    assert_eq!((args.open_delim.as_str(), args.close_delim.as_str()), ("<<", ">>"));
+    assert_eq!((args.chunk_end.as_str(), args.comment_markers.as_str()), ("@@", ";"));
+    assert!(args.prune && args.merge && args.force_generated && !args.check);
+}
+
+#[test]
+fn pass_args_carry_the_profile_and_its_database() {
+    let cfg = parse_cfg("[[pass]]\ndir = \"src/\"\n");
+    let opts = TangleRunOptions { profile: Some("embedded".to_string()), ..TangleRunOptions::default() };
+    let args = pass_args(&cfg.passes[0], ".", &opts);
+    assert_eq!(args.profile.as_deref(), Some("embedded"));
+    assert_eq!(args.db, std::path::PathBuf::from("weaveback.embedded.db"));
+}
+
+#[test]
+fn naming_a_pass_keeps_its_key_and_reports_the_name() {
+    let named = parse_cfg("[[pass]]\nname = \"core\"\ndir = \"src/\"\n");
+    let unnamed = parse_cfg("[[pass]]\ndir = \"src/\"\n");
+    let key = |cfg: &TangleCfg| {
+        crate::process::pass_key(&pass_args(&cfg.passes[0], ".", &TangleRunOptions::default()))
+    };
+    assert_eq!(key(&named), key(&unnamed), "outputs recorded before the name stay owned");
+    assert_eq!(named.passes[0].label(), "core");
+    let args = pass_args(&named.passes[0], ".", &TangleRunOptions::default());
+    assert_eq!(args.pass_name.as_deref(), Some("core"));
+}
+
+#[test]
+fn depends_on_orders_passes_regardless_of_config_order() {
+    let cfg = parse_cfg(r#"
+[[pass]]
+name = "docs"
+dir = "docs/"
+depends_on = ["spec"]
+[[pass]]
+name = "spec"
+dir = "cli-spec/"
+[[pass]]
+dir = "other/"
+"#);
+    assert_eq!(waves(&cfg, &[0, 1, 2], None), vec![vec![1, 2], vec![0]]);
+    assert_eq!(waves(&cfg, &[0], None), vec![vec![0]], "unselected prerequisites are not waited for");
+}
+
+#[test]
+fn invalid_pass_graphs_are_rejected() {
+    let unknown = parse_cfg("[[pass]]\nname = \"a\"\ndir = \"a/\"\ndepends_on = [\"nope\"]\n");
+    let err = pass_prerequisites(&unknown, None).unwrap_err();
+    assert!(err.to_string().contains("unknown pass `nope`"));
+
+    let duplicate = parse_cfg("[[pass]]\nname = \"a\"\ndir = \"a/\"\n[[pass]]\nname = \"a\"\ndir = \"b/\"\n");
+    assert!(pass_prerequisites(&duplicate, None).is_err());
+
+    let cycle = parse_cfg(r#"
+[[pass]]
+name = "a"
+dir = "a/"
+depends_on = ["b"]
+[[pass]]
+name = "b"
+dir = "b/"
+depends_on = ["a"]
+"#);
+    let prerequisites = pass_prerequisites(&cycle, None).unwrap();
+    let err = pass_waves(&cycle, &prerequisites, &[0, 1]).unwrap_err();
+    assert!(err.to_string().contains("dependency cycle between passes: a, b"));
+}
+
+#[test]
+fn select_passes_adds_dependents_of_named_passes() {
+    let cfg = parse_cfg(r#"
+[[pass]]
+name = "spec"
+dir = "cli-spec/"
+[[pass]]
+name = "cli"
+dir = "crates/cli/"
+depends_on = ["spec"]
+[[pass]]
+name = "docs"
+dir = "docs/"
+depends_on = ["cli"]
+[[pass]]
+name = "other"
+dir = "other/"
+"#);
+    let select = |names: &[&str]| {
+        let selection = PassSelection {
+            names: names.iter().map(|n| n.to_string()).collect(),
+            only_changed: false,
+        };
+        select_passes(&cfg, &selection)
+    };
+    assert_eq!(select(&["spec"]).unwrap(), vec![0, 1, 2]);
+    assert_eq!(select(&["cli", "other"]).unwrap(), vec![1, 2, 3]);
+    assert!(select(&["missing"]).unwrap_err().to_string().contains("no pass named `missing`"));
+}
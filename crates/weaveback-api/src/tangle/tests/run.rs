@@ -7,7 +7,7 @@ use super::*;
 fn run_tangle_all_errors_on_missing_config() {
     let dir = TempDir::new().unwrap();
     let cfg_path = dir.path().join("nonexistent.toml");
-    let result = run_tangle_all(&cfg_path, false);
+    let result = run_tangle_all(&cfg_path, &TangleRunOptions::default());
     assert!(result.is_err());
 }
 
@@ -16,7 +16,7 @@ fn run_tangle_all_errors_on_bad_toml() {
     let dir = TempDir::new().unwrap();
     let cfg_path = dir.path().join("weaveback.toml");
     std::fs::write(&cfg_path, "[[pass\nbad toml{{{{").unwrap();
-    let result = run_tangle_all(&cfg_path, false);
+    let result = run_tangle_all(&cfg_path, &TangleRunOptions::default());
     assert!(result.is_err());
 }
 
@@ -63,7 +63,7 @@ model = "gpt-4o"
 "#;
     std::fs::write(&cfg_path, toml_empty_passes).unwrap();
 
-    let _ = run_tangle_all(&cfg_path, false);
+    let _ = run_tangle_all(&cfg_path, &TangleRunOptions::default());
 
     std::env::set_current_dir(old_cwd).unwrap();
 }
@@ -85,7 +85,7 @@ fn test_run_tangle_all_db_open_error_path() {
     // Actually, it returns early if passes fail.
     // So we use an empty pass list.
     std::fs::write(&cfg_path, "[pass]\n").unwrap();
-    let _ = run_tangle_all(&cfg_path, false);
+    let _ = run_tangle_all(&cfg_path, &TangleRunOptions::default());
 
     std::env::set_current_dir(old_cwd).unwrap();
 }
@@ -98,7 +98,7 @@ fn test_run_tangle_all_fails_if_pass_fails() {
     let toml_src = "[[pass]]\ndir = \"/tmp/nonexistent_path_weaveback_test\"\n";
     std::fs::write(&cfg_path, toml_src).unwrap();
 
-    let res = run_tangle_all(&cfg_path, false);
+    let res = run_tangle_all(&cfg_path, &TangleRunOptions::default());
     // This fails because the current_exe (test runner) is spawned
     // and its exit status is checked. Since it's called with unknown args,
     // it exits with error 101 or similar.
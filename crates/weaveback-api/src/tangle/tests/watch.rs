@@ -0,0 +1,53 @@
+// weaveback-api/src/tangle/tests/watch.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+fn pass_cfg(dir: &Path, extra: &str) -> TangleCfg {
+    let toml_src = format!("[[pass]]\ndir = {:?}\next = \"adoc\"\n{extra}", dir.to_str().unwrap());
+    toml::from_str(&toml_src).unwrap()
+}
+
+#[test]
+fn pass_dependencies_include_sources_and_included_files() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path().canonicalize().unwrap();
+    let src = root.join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    let shared = root.join("shared.txt");
+    std::fs::write(&shared, "shared\n").unwrap();
+    std::fs::write(src.join("a.adoc"), format!("%include({})\n", shared.display())).unwrap();
+    std::fs::write(src.join("notes.md"), "not a source\n").unwrap();
+
+    let cfg = pass_cfg(&src, "");
+    let deps = pass_dependencies(&cfg.passes[0]);
+
+    assert!(deps.contains(&src.join("a.adoc")));
+    assert!(deps.contains(&shared));
+    assert!(!deps.contains(&src.join("notes.md")));
+}
+
+#[test]
+fn affected_passes_match_dir_ext_and_dependencies() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path().canonicalize().unwrap();
+    let a = root.join("a");
+    let b = root.join("b");
+    std::fs::create_dir_all(&a).unwrap();
+    std::fs::create_dir_all(&b).unwrap();
+    let toml_src = format!(
+        "[[pass]]\ndir = {:?}\next = \"adoc\"\n[[pass]]\ndir = {:?}\next = \"adoc\"\n",
+        a.to_str().unwrap(),
+        b.to_str().unwrap()
+    );
+    let cfg: TangleCfg = toml::from_str(&toml_src).unwrap();
+    let prelude = root.join("prelude.txt");
+    let deps = vec![HashSet::new(), HashSet::from([prelude.clone()])];
+
+    let changed = |paths: &[PathBuf]| paths.iter().cloned().collect::<HashSet<_>>();
+    assert_eq!(affected_passes(&cfg, &deps, &changed(&[a.join("new.adoc")])), vec![0]);
+    assert_eq!(affected_passes(&cfg, &deps, &changed(&[prelude])), vec![1]);
+    assert!(affected_passes(&cfg, &deps, &changed(&[a.join("out.rs"), root.join("x.adoc")])).is_empty());
+}
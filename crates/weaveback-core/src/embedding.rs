@@ -0,0 +1,102 @@
+// weaveback-core/src/embedding.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+/// Turns texts into vectors of a fixed dimension.
+pub trait Embedder {
+    /// Length of every vector `embed` returns.
+    fn dims(&self) -> usize;
+
+    /// One vector per text, in order.
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String>;
+}
+
+/// The in-process embedder for a `backend = "local"` model name.
+pub fn local_embedder(model: &str) -> Result<Box<dyn Embedder>, String> {
+    let dims = match model.strip_prefix("hashed-ngram") {
+        Some("") => Some(HashedNgramEmbedder::DEFAULT_DIMS),
+        Some(suffix) => suffix
+            .strip_prefix('-')
+            .and_then(|dims| dims.parse().ok())
+            .filter(|dims| *dims > 0),
+        None => None,
+    };
+    match dims {
+        Some(dims) => Ok(Box::new(HashedNgramEmbedder { dims })),
+        None => Err(format!(
+            "unknown local embedding model `{model}`; use `hashed-ngram` or `hashed-ngram-<dims>`"
+        )),
+    }
+}
+
+/// Deterministic embedder over hashed word and character n-grams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashedNgramEmbedder {
+    pub dims: usize,
+}
+
+impl HashedNgramEmbedder {
+    pub const DEFAULT_DIMS: usize = 256;
+
+    const WORD_WEIGHT: f32 = 1.0;
+    const BIGRAM_WEIGHT: f32 = 0.7;
+    const TRIGRAM_WEIGHT: f32 = 0.3;
+
+    /// The vector of one text.
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let mut counts: std::collections::HashMap<(u8, String), u32> = Default::default();
+        for (i, word) in words.iter().enumerate() {
+            *counts.entry((0, word.clone())).or_default() += 1;
+            if let Some(next) = words.get(i + 1) {
+                *counts.entry((1, format!("{word} {next}"))).or_default() += 1;
+            }
+            let padded: Vec<char> = format!("#{word}#").chars().collect();
+            for trigram in padded.windows(3) {
+                *counts.entry((2, trigram.iter().collect())).or_default() += 1;
+            }
+        }
+
+        let mut vector = vec![0.0f32; self.dims.max(1)];
+        for ((kind, feature), count) in counts {
+            let weight = match kind {
+                0 => Self::WORD_WEIGHT,
+                1 => Self::BIGRAM_WEIGHT,
+                _ => Self::TRIGRAM_WEIGHT,
+            };
+            let hash = fnv1a(kind, &feature);
+            let bucket = (hash % vector.len() as u64) as usize;
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign * weight * (1.0 + (count as f32).ln());
+        }
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        vector
+    }
+}
+
+impl Embedder for HashedNgramEmbedder {
+    fn dims(&self) -> usize {
+        self.dims
+    }
+
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        Ok(texts.iter().map(|text| self.embed_text(text)).collect())
+    }
+}
+
+/// 64-bit FNV-1a of a feature, seeded with its kind so that a word and a
+/// trigram with the same characters land in different buckets.
+fn fnv1a(kind: u8, feature: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in std::iter::once(kind).chain(feature.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
@@ -0,0 +1,32 @@
+// weaveback-core/src/glob/tests.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+
+#[test]
+fn stars_match_any_run_including_slashes() {
+    assert!(glob_match("db-*", "db-open"));
+    assert!(glob_match("*open", "db-open"));
+    assert!(glob_match("tests/*", "tests/unit/parse"));
+    assert!(glob_match("a*b*c", "axxbyyc"));
+    assert!(glob_match("*", ""));
+    assert!(glob_match("**", "x"));
+    assert!(!glob_match("*close", "db-open"));
+    assert!(!glob_match("a*b*c", "axxbyy"));
+}
+
+#[test]
+fn question_marks_match_one_character() {
+    assert!(glob_match("db-?lose", "db-close"));
+    assert!(glob_match("?é", "aé"));
+    assert!(!glob_match("db-?", "db-open"));
+    assert!(!glob_match("?", ""));
+}
+
+#[test]
+fn other_characters_match_literally() {
+    assert!(glob_match("crates", "crates"));
+    assert!(!glob_match("crates", "crate"));
+    assert!(!glob_match("", "x"));
+    assert!(glob_match("", ""));
+}
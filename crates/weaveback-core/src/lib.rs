@@ -1,10 +1,16 @@
 // weaveback-core/src/lib.rs
 // I'd Really Rather You Didn't edit this generated file.
 
+pub mod embedding;
+pub mod glob;
+
 /// Maximum recursion depth for macro expansion and noweb chunk expansion.
 pub const MAX_RECURSION_DEPTH: usize = 100;
 use std::path::{Path, PathBuf};
 
+/// Prefix of a portable path that was under the project root.
+pub const PORTABLE_ROOT: &str = "$ROOT/";
+
 pub struct PathResolver {
     project_root: PathBuf,
     gen_dir: PathBuf,
@@ -60,4 +66,23 @@ impl PathResolver {
     pub fn resolve_gen(&self, db_path: &str) -> PathBuf {
         self.project_root.join(&self.gen_dir).join(db_path)
     }
+
+    /// Rewrite an absolute path under `project_root` as `$ROOT/…`.
+    pub fn to_portable(&self, path: &str) -> String {
+        match Path::new(path).strip_prefix(&self.project_root) {
+            Ok(rel) if Path::new(path).is_absolute() => {
+                let parts: Vec<_> = rel.iter().map(|part| part.to_string_lossy()).collect();
+                format!("{PORTABLE_ROOT}{}", parts.join("/"))
+            }
+            _ => path.to_string(),
+        }
+    }
+
+    /// Rebase a `$ROOT/…` path from `to_portable` onto `project_root`.
+    pub fn from_portable(&self, path: &str) -> String {
+        match path.strip_prefix(PORTABLE_ROOT) {
+            Some(rel) => self.project_root.join(rel).to_string_lossy().into_owned(),
+            None => path.to_string(),
+        }
+    }
 }
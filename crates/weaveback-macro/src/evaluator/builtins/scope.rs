@@ -59,6 +59,12 @@ pub(in crate::evaluator::builtins) fn builtin_alias(eval: &mut Evaluator, node:
         .ok_or_else(|| EvalError::InvalidUsage(
             format!("alias: macro '{source_name}' is not defined"),
         ))?;
+    eval.record_macro_call(
+        source_name.clone(),
+        parts[1].token.src,
+        parts[1].token.pos as u32,
+        (parts[1].end_pos.saturating_sub(parts[1].token.pos)) as u32,
+    );
     mac.name = new_name.clone();
     mac.binding_kind = MacroBindingKind::Rebindable;
     for part in &parts[2..] {
@@ -27,6 +27,7 @@ mod source;
 mod state_delegates;
 mod tracing;
 
+#[derive(Clone)]
 pub struct Evaluator {
     state: EvaluatorState,
     builtins: HashMap<String, BuiltinFn>,
@@ -35,6 +35,12 @@ impl Evaluator {
             Some(m) => m,
             None => return Err(EvalError::UndefinedMacro(name.into())),
         };
+        self.record_macro_call(
+            name.to_string(),
+            node.token.src,
+            node.token.pos as u32,
+            (node.end_pos.saturating_sub(node.token.pos)) as u32,
+        );
 
         let param_nodes = Self::macro_param_nodes(node);
 
@@ -30,6 +30,12 @@ impl Evaluator {
         self.state.get_sigil()
     }
 
+    /// Use `sigil` for everything parsed from now on (sources, includes,
+    /// `eval` bodies) and return the sigil it replaces.
+    pub fn set_sigil(&mut self, sigil: char) -> char {
+        std::mem::replace(&mut self.state.config.sigil, sigil)
+    }
+
     pub fn set_early_exit(&mut self) {
         self.state.early_exit = true;
     }
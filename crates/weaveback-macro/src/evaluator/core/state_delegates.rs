@@ -77,6 +77,10 @@ impl Evaluator {
         self.state.macro_defs.push(crate::evaluator::state::MacroDefRaw { macro_name, src, pos, length });
     }
 
+    pub fn record_macro_call(&mut self, macro_name: String, src: u32, pos: u32, length: u32) {
+        self.state.macro_calls.push(crate::evaluator::state::MacroCallRaw { macro_name, src, pos, length });
+    }
+
     pub fn drain_var_defs(&mut self) -> Vec<crate::evaluator::state::VarDefRaw> {
         self.state.drain_var_defs()
     }
@@ -85,6 +89,10 @@ impl Evaluator {
         self.state.drain_macro_defs()
     }
 
+    pub fn drain_macro_calls(&mut self) -> Vec<crate::evaluator::state::MacroCallRaw> {
+        self.state.drain_macro_calls()
+    }
+
     pub fn push_warning(&mut self, msg: String) {
         self.state.push_warning(msg);
     }
@@ -5,6 +5,7 @@
 
 use monty::{MontyObject, MontyRun};
 use std::collections::{HashMap, HashSet};
+#[derive(Clone)]
 pub struct MontyEvaluator;
 
 impl Default for MontyEvaluator {
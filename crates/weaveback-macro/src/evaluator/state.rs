@@ -67,6 +67,7 @@ pub struct ScopeFrame {
     pub variables: HashMap<String, TrackedValue>,
     pub macros: HashMap<String, MacroDefinition>,
 }
+#[derive(Clone)]
 pub struct SourceManager {
     source_files: Vec<Vec<u8>>,
     file_names: Vec<PathBuf>,
@@ -135,6 +136,18 @@ pub struct MacroDefRaw {
     /// Byte length of the whole def(...) call.
     pub length: u32,
 }
+/// Raw record of a call of a user-defined macro.
+#[derive(Debug, Clone)]
+pub struct MacroCallRaw {
+    pub macro_name: String,
+    /// Source file index (same as Token.src).
+    pub src: u32,
+    /// Byte offset of the call in the source.
+    pub pos: u32,
+    /// Byte length of the whole call.
+    pub length: u32,
+}
+#[derive(Clone)]
 pub struct EvaluatorState {
     pub config: EvalConfig,
     pub(crate) dependency_discovery_active: bool,
@@ -151,6 +164,8 @@ pub struct EvaluatorState {
     pub var_defs: Vec<VarDefRaw>,
     /// Accumulated `%def/%pydef` call sites for the macro_defs_map.
     pub macro_defs: Vec<MacroDefRaw>,
+    /// Accumulated calls of user-defined macros.
+    pub macro_calls: Vec<MacroCallRaw>,
     /// Diagnostic warnings collected during evaluation (non-fatal).
     pub warnings: Vec<String>,
 }
@@ -169,6 +184,7 @@ impl EvaluatorState {
             discovered_dependency_paths: Vec::new(),
             var_defs: Vec::new(),
             macro_defs: Vec::new(),
+            macro_calls: Vec::new(),
             warnings: Vec::new(),
         }
     }
@@ -189,6 +205,10 @@ impl EvaluatorState {
         std::mem::take(&mut self.macro_defs)
     }
 
+    pub fn drain_macro_calls(&mut self) -> Vec<MacroCallRaw> {
+        std::mem::take(&mut self.macro_calls)
+    }
+
     pub fn push_scope(&mut self) {
         self.scope_stack.push(ScopeFrame::default());
     }
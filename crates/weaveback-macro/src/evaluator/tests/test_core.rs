@@ -53,15 +53,29 @@ fn test_core_record_and_drain_definition_helpers() {
     let mut eval = Evaluator::new(EvalConfig::default());
     eval.record_var_def("answer".into(), 1, 2, 3);
     eval.record_macro_def("greet".into(), 4, 5, 6);
+    eval.record_macro_call("greet".into(), 7, 8, 9);
 
     let var_defs = eval.drain_var_defs();
     let macro_defs = eval.drain_macro_defs();
+    let macro_calls = eval.drain_macro_calls();
     assert_eq!(var_defs.len(), 1);
     assert_eq!(var_defs[0].var_name, "answer");
     assert_eq!(macro_defs.len(), 1);
     assert_eq!(macro_defs[0].macro_name, "greet");
+    assert_eq!(macro_calls.len(), 1);
+    assert_eq!(macro_calls[0].pos, 8);
     assert!(eval.drain_var_defs().is_empty());
     assert!(eval.drain_macro_defs().is_empty());
+    assert!(eval.drain_macro_calls().is_empty());
+}
+
+#[test]
+fn test_core_records_user_macro_calls_and_alias_sources() {
+    let mut eval = Evaluator::new(EvalConfig::default());
+    let text = "%def(greet, who, %{hi %(who)%})%def(wave, %{~%})%alias(hello, wave)%greet(x)%set(y, 1)";
+    crate::macro_api::process_string(text, None, &mut eval).unwrap();
+    let calls: Vec<String> = eval.drain_macro_calls().into_iter().map(|c| c.macro_name).collect();
+    assert_eq!(calls, ["wave", "greet"]);
 }
 
 #[test]
@@ -103,6 +117,29 @@ fn test_discover_includes_in_string_records_paths() {
     assert!(eval.take_discovered_dependency_paths().is_empty());
 }
 
+#[test]
+fn test_parsed_source_evaluates_like_process_string_in_each_evaluator() {
+    let temp = TempDir::new().unwrap();
+    let doc = temp.path().join("doc.txt");
+    let text = "%set(x, world)[%greet(%(x))]";
+    fs::write(&doc, text).unwrap();
+    let parsed = crate::macro_api::parse_source(text, &doc, '%').unwrap();
+
+    for prelude in ["%def(greet, who, %{hello %(who)%})", "%def(g, %{%})%def(greet, who, %{bye %(who)%})"] {
+        let mut with_prelude = Evaluator::new(EvalConfig::default());
+        crate::macro_api::process_string(prelude, None, &mut with_prelude).unwrap();
+        let mut reparsed = with_prelude.clone();
+        let once = crate::macro_api::process_parsed(&parsed, &mut with_prelude).unwrap();
+        let again = crate::macro_api::process_string(text, Some(&doc), &mut reparsed).unwrap();
+        assert_eq!(once, again);
+        assert_eq!(with_prelude.source_files(), reparsed.source_files());
+    }
+
+    let mut other_sigil = Evaluator::new(EvalConfig { sigil: '§', ..EvalConfig::default() });
+    let out = crate::macro_api::process_parsed(&parsed, &mut other_sigil).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), text);
+}
+
 #[test]
 fn test_core_do_include_accepts_absolute_existing_path() {
     let temp = TempDir::new().unwrap();
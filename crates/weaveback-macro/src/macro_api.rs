@@ -5,6 +5,7 @@
 
 use crate::evaluator::{EvalConfig, EvalError, Evaluator};
 use crate::evaluator::output::{EvalOutput, MacroMapEntry};
+use crate::types::ASTNode;
 
 pub type TracingResult = (Vec<u8>, Vec<(u32, MacroMapEntry)>);
 use std::fs;
@@ -143,6 +144,75 @@ pub fn discover_includes_in_file(
     discover_includes_in_string(&content, Some(input_file), evaluator)
         .map_err(|e| with_input_context(input_file, e))
 }
+/// A document lexed and parsed once, for evaluation by several evaluators.
+#[derive(Debug, Clone)]
+pub struct ParsedSource {
+    path: PathBuf,
+    text: String,
+    sigil: char,
+    ast: ASTNode,
+}
+
+impl ParsedSource {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Lex and parse `source`, read from `real_path`, with `sigil`.
+pub fn parse_source(source: &str, real_path: &Path, sigil: char) -> Result<ParsedSource, EvalError> {
+    let ast = crate::evaluator::lex_parse_content(source, sigil, 0).map_err(EvalError::ParseError)?;
+    Ok(ParsedSource {
+        path: real_path.to_path_buf(),
+        text: source.to_string(),
+        sigil,
+        ast,
+    })
+}
+
+fn relabel_source(node: &mut ASTNode, src: u32) {
+    node.src = src;
+    node.token.src = src;
+    if let Some(name) = &mut node.name {
+        name.src = src;
+    }
+    for part in &mut node.parts {
+        relabel_source(part, src);
+    }
+}
+
+/// `process_string` for an already parsed document.
+pub fn process_parsed(parsed: &ParsedSource, evaluator: &mut Evaluator) -> Result<Vec<u8>, EvalError> {
+    if evaluator.get_sigil() != parsed.sigil.to_string().as_bytes() {
+        return process_string(&parsed.text, Some(&parsed.path), evaluator);
+    }
+    let src = match fs::metadata(&parsed.path) {
+        Ok(md) if md.is_file() => evaluator.add_source_if_not_present(parsed.path.clone())?,
+        _ => evaluator.add_source_bytes(parsed.text.as_bytes().to_vec(), parsed.path.clone()),
+    };
+    let mut ast = parsed.ast.clone();
+    relabel_source(&mut ast, src);
+    evaluator.validate_ast_semantics(&ast)?;
+    evaluator.set_current_file(parsed.path.clone());
+    let output_string = evaluator.evaluate(&ast)?;
+    Ok(output_string.into_bytes())
+}
+
+/// `discover_includes_in_string` for an already parsed document.
+pub fn discover_includes_in_parsed(
+    parsed: &ParsedSource,
+    evaluator: &mut Evaluator,
+) -> Result<Vec<PathBuf>, EvalError> {
+    evaluator.set_dependency_discovery_active(true);
+    let result = process_parsed(parsed, evaluator);
+    let includes = evaluator.take_discovered_dependency_paths();
+    evaluator.set_dependency_discovery_active(false);
+    result.map(|_| includes)
+}
 /// Evaluate `source` with precise per-byte token attribution.
 ///
 /// Returns the expanded string and a sorted list of `SpanRange` entries —
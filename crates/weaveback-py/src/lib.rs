@@ -4,25 +4,29 @@
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
 use pythonize::{depythonize, pythonize};
+use std::path::{Path, PathBuf};
 use weaveback_agent_core::{ChangePlan, Workspace, WorkspaceConfig};
 
 #[pyclass]
 struct PyWorkspace {
     inner: Workspace,
+    db_path: PathBuf,
 }
 
 #[pymethods]
 impl PyWorkspace {
     #[new]
     fn new(project_root: String, db_path: String, gen_dir: String) -> Self {
+        let db_path = PathBuf::from(db_path);
         let config = WorkspaceConfig {
             project_root: project_root.into(),
-            db_path: db_path.into(),
+            db_path: db_path.clone(),
             gen_dir: gen_dir.into(),
         };
 
         Self {
             inner: Workspace::open(config),
+            db_path,
         }
     }
 
@@ -34,6 +38,15 @@ impl PyWorkspace {
             .map_err(Into::into)
     }
 
+    #[pyo3(signature = (query, lcov=None))]
+    fn query(&self, py: Python<'_>, query: &str, lcov: Option<String>) -> PyResult<Py<PyAny>> {
+        let value = weaveback_api::query::evaluate_query(query, &self.db_path, lcov.as_deref().map(Path::new))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        pythonize(py, &value)
+            .map(|value| value.unbind())
+            .map_err(Into::into)
+    }
+
     fn trace(&self, py: Python<'_>, out_file: &str, out_line: u32, out_col: u32) -> PyResult<Py<PyAny>> {
         let value = self.inner.session().trace(out_file, out_line, out_col)
             .map_err(PyRuntimeError::new_err)?;
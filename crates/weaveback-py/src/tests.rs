@@ -26,6 +26,11 @@ fn test_py_workspace_basic() {
         let res = ws.search(py, "test", 10).unwrap();
         assert!(res.bind(py).is_instance_of::<pyo3::types::PyList>());
 
+        // Query
+        let res = ws.query(py, "chunks order by name", None).unwrap();
+        assert!(res.bind(py).is_instance_of::<pyo3::types::PyDict>());
+        assert!(ws.query(py, "chunks where size > 1", None).is_err());
+
         // Trace
         let res = ws.trace(py, "nonexistent.rs", 1, 1).unwrap();
         assert!(res.bind(py).is_none() || res.bind(py).is_instance_of::<pyo3::types::PyDict>());
@@ -0,0 +1,151 @@
+// weaveback-tangle/benches/source_maps.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use rusqlite::{Connection, params};
+use weaveback_tangle::db::{Confidence, NowebMapEntry, WeavebackDb};
+
+const FILES: u32 = 200;
+const LINES_PER_FILE: u32 = 600;
+/// A nested chunk reference every this many lines of the outer chunk.
+const REFERENCE_EVERY: u32 = 30;
+const NESTED_LINES: u32 = 12;
+
+/// Output files with their source maps.
+type Maps = [(String, Vec<(u32, NowebMapEntry)>)];
+/// Writes `Maps` into the database at a path; returns the time and rows.
+type Writer = fn(&Path, &Maps) -> (Duration, i64);
+
+fn entry(src_file: &str, chunk_name: &str, src_line: u32, indent: &str) -> NowebMapEntry {
+    NowebMapEntry {
+        src_file: src_file.to_string(),
+        chunk_name: chunk_name.to_string(),
+        src_line,
+        indent: indent.to_string(),
+        confidence: Confidence::Exact,
+    }
+}
+
+/// The source map of one synthetic output file.
+fn output_map(file: u32) -> Vec<(u32, NowebMapEntry)> {
+    let src_file = format!("src/module{}.wvb", file % 40);
+    let mut lines = Vec::with_capacity(LINES_PER_FILE as usize);
+    let mut outer_line = 0;
+    let mut nested = 0;
+    while (lines.len() as u32) < LINES_PER_FILE {
+        let out_line = lines.len() as u32;
+        if outer_line > 0 && outer_line % REFERENCE_EVERY == 0 {
+            let chunk = format!("helper-{file}-{nested}");
+            for line in 0..NESTED_LINES {
+                let src_line = 1000 + nested * 20 + line;
+                lines.push((out_line + line, entry(&src_file, &chunk, src_line, "    ")));
+            }
+            nested += 1;
+        } else {
+            lines.push((out_line, entry(&src_file, "@file out", outer_line, "")));
+        }
+        outer_line += 1;
+    }
+    lines
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Write every map through `set_noweb_entries`.
+fn write_ranges(path: &Path, maps: &Maps) -> (Duration, i64) {
+    let mut db = WeavebackDb::open(path).unwrap();
+    let started = Instant::now();
+    for (out_file, entries) in maps {
+        db.set_noweb_entries(out_file, entries).unwrap();
+    }
+    let elapsed = started.elapsed();
+    let rows = db
+        .table_row_counts()
+        .unwrap()
+        .into_iter()
+        .find(|(table, _)| table == "noweb_map")
+        .map_or(0, |(_, rows)| rows);
+    (elapsed, rows)
+}
+
+/// Write every map one row per line into a schema-2 `noweb_map`.
+fn write_lines(path: &Path, maps: &Maps) -> (Duration, i64) {
+    drop(WeavebackDb::open(path).unwrap());
+    let mut conn = Connection::open(path).unwrap();
+    conn.execute_batch("
+        DROP TABLE noweb_map;
+        CREATE TABLE noweb_map (
+            out_file   INTEGER NOT NULL REFERENCES files(id),
+            out_line   INTEGER NOT NULL,
+            src_file   INTEGER NOT NULL REFERENCES files(id),
+            chunk_name TEXT    NOT NULL,
+            src_line   INTEGER NOT NULL,
+            indent     TEXT    NOT NULL,
+            confidence TEXT    NOT NULL DEFAULT 'exact',
+            PRIMARY KEY (out_file, out_line)
+        ) STRICT, WITHOUT ROWID;
+        CREATE INDEX idx_noweb_map_src ON noweb_map(src_file, src_line);
+    ").unwrap();
+    let intern = |conn: &Connection, path: &str| -> i64 {
+        conn.execute("INSERT OR IGNORE INTO files (path) VALUES (?1)", params![path]).unwrap();
+        conn.query_row("SELECT id FROM files WHERE path = ?1", params![path], |row| row.get(0))
+            .unwrap()
+    };
+    let started = Instant::now();
+    for (out_file, entries) in maps {
+        let out_id = intern(&conn, out_file);
+        let src_id = intern(&conn, &entries[0].1.src_file);
+        let tx = conn.transaction().unwrap();
+        {
+            let mut stmt = tx
+                .prepare_cached(
+                    "INSERT OR REPLACE INTO noweb_map
+                     (out_file, out_line, src_file, chunk_name, src_line, indent, confidence)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .unwrap();
+            for (line, e) in entries {
+                stmt.execute(params![
+                    out_id,
+                    line,
+                    src_id,
+                    e.chunk_name,
+                    e.src_line,
+                    e.indent,
+                    e.confidence.as_str()
+                ])
+                .unwrap();
+            }
+        }
+        tx.commit().unwrap();
+    }
+    let elapsed = started.elapsed();
+    let rows = conn.query_row("SELECT COUNT(*) FROM noweb_map", [], |row| row.get(0)).unwrap();
+    (elapsed, rows)
+}
+
+fn main() {
+    let maps: Vec<(String, Vec<(u32, NowebMapEntry)>)> = (0..FILES)
+        .map(|file| (format!("gen/out{file}.rs"), output_map(file)))
+        .collect();
+    let lines: usize = maps.iter().map(|(_, entries)| entries.len()).sum();
+    let dir = tempfile::tempdir().unwrap();
+
+    println!("noweb_map: {FILES} files, {lines} generated lines");
+    println!("{:<10} {:>10} {:>12} {:>12}", "layout", "rows", "write", "db size");
+    let layouts: [(&str, Writer); 2] = [("per-line", write_lines), ("ranges", write_ranges)];
+    for (name, write) in layouts {
+        let path = dir.path().join(format!("{name}.db"));
+        // `write` closes its connection, which checkpoints the WAL.
+        let (elapsed, rows) = write(&path, &maps);
+        println!(
+            "{name:<10} {rows:>10} {:>10.1}ms {:>10}KiB",
+            elapsed.as_secs_f64() * 1000.0,
+            file_size(&path) / 1024
+        );
+    }
+}
@@ -8,14 +8,23 @@
 /// are stored in the database so that unchanged blocks can be skipped on the
 /// next run.
 mod adoc;
+mod lines;
 mod markdown;
+mod noweb;
+mod org;
 mod utils;
 
 use adoc::parse_adoc_raw;
 use markdown::parse_markdown_raw;
+use noweb::parse_noweb_raw;
+use org::parse_org_raw;
 
 #[cfg(test)]
 use adoc::{has_unclosed_adoc_fence, is_adoc_fence, is_adoc_section_header};
+#[cfg(test)]
+use noweb::is_noweb_definition;
+#[cfg(test)]
+use org::is_org_headline;
 
 /// A parsed logical block with its line range and content hash.
 #[derive(Debug, Clone)]
@@ -29,11 +38,14 @@ pub struct SourceBlockEntry {
 /// Parse `source` into logical blocks based on its file `extension`.
 ///
 /// Recognised extensions: `adoc`, `asciidoc` (AsciiDoc line scanner);
-/// `md`, `markdown` (pulldown-cmark); everything else gets a single block.
+/// `md`, `markdown` (pulldown-cmark); `nw` (noweb); `org` (Org-mode);
+/// everything else gets a single block.
 pub fn parse_source_blocks(source: &str, extension: &str) -> Vec<SourceBlockEntry> {
     let raw = match extension {
         "adoc" | "asciidoc" => parse_adoc_raw(source),
         "md" | "markdown" => parse_markdown_raw(source),
+        "nw" => parse_noweb_raw(source),
+        "org" => parse_org_raw(source),
         _ => {
             let n = source.lines().count().max(1) as u32;
             vec![(1, n, "text", source.to_string())]
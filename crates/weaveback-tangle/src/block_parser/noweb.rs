@@ -0,0 +1,58 @@
+// weaveback-tangle/src/block_parser/noweb.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::lines::LineBlocks;
+
+/// Parse a classic noweb document into code chunks and documentation.
+pub(in crate::block_parser) fn parse_noweb_raw(source: &str) -> Vec<(u32, u32, &'static str, String)> {
+    let mut out = LineBlocks::new();
+    let mut code: Option<(u32, String)> = None;
+    let mut last_line = 0u32;
+
+    for (idx, line) in source.lines().enumerate() {
+        let lineno = idx as u32 + 1;
+        last_line = lineno;
+        let starts_code = is_noweb_definition(line);
+        if (starts_code || is_noweb_doc_start(line))
+            && let Some((start, content)) = code.take()
+        {
+            out.push(start, lineno - 1, "code", content);
+        }
+        if starts_code {
+            out.flush();
+            code = Some((lineno, format!("{line}\n")));
+        } else if let Some((_, content)) = code.as_mut() {
+            content.push_str(line);
+            content.push('\n');
+        } else if is_latex_section(line) {
+            out.push(lineno, lineno, "section", line.to_string());
+        } else {
+            let doc = line.strip_prefix('@').filter(|_| is_noweb_doc_start(line)).unwrap_or(line);
+            out.text(lineno, doc);
+        }
+    }
+    if let Some((start, content)) = code {
+        out.push(start, last_line, "code", content);
+    }
+    out.finish(source)
+}
+
+pub(in crate::block_parser) fn is_noweb_definition(line: &str) -> bool {
+    let t = line.trim_end();
+    t.starts_with("<<") && t.ends_with(">>=") && t.len() > 5
+}
+
+fn is_noweb_doc_start(line: &str) -> bool {
+    line.strip_prefix('@')
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+fn is_latex_section(line: &str) -> bool {
+    let t = line.trim_start();
+    ["\\chapter", "\\section", "\\subsection", "\\subsubsection", "\\paragraph"]
+        .iter()
+        .any(|cmd| {
+            t.strip_prefix(cmd)
+                .is_some_and(|rest| rest.starts_with('{') || rest.starts_with("*{"))
+        })
+}
@@ -0,0 +1,85 @@
+// weaveback-tangle/src/block_parser/org.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::lines::LineBlocks;
+
+/// Parse an Org-mode document into headlines, source blocks and paragraphs.
+pub(in crate::block_parser) fn parse_org_raw(source: &str) -> Vec<(u32, u32, &'static str, String)> {
+    let mut out = LineBlocks::new();
+    // Affiliated keyword lines waiting to see whether a block follows.
+    let mut keywords: Option<(u32, String)> = None;
+    // Open block: start line, content, and the end keyword that closes it.
+    let mut block: Option<(u32, String, &'static str)> = None;
+    let mut last_line = 0u32;
+
+    for (idx, line) in source.lines().enumerate() {
+        let lineno = idx as u32 + 1;
+        last_line = lineno;
+        let trimmed = line.trim_start();
+        if let Some((start, mut content, end)) = block.take() {
+            content.push_str(line);
+            content.push('\n');
+            if org_keyword_is(trimmed, end) {
+                out.push(start, lineno, "code", content);
+            } else {
+                block = Some((start, content, end));
+            }
+            continue;
+        }
+        let end = if org_keyword_is(trimmed, "begin_src") {
+            Some("end_src")
+        } else if org_keyword_is(trimmed, "begin_example") {
+            Some("end_example")
+        } else {
+            None
+        };
+        if let Some(end) = end {
+            let (start, mut content) = keywords.take().unwrap_or((lineno, String::new()));
+            content.push_str(line);
+            content.push('\n');
+            out.flush();
+            block = Some((start, content, end));
+            continue;
+        }
+        if org_keyword_is(trimmed, "name:") || org_keyword_is(trimmed, "header:") {
+            out.flush();
+            let (_, content) = keywords.get_or_insert_with(|| (lineno, String::new()));
+            content.push_str(line);
+            content.push('\n');
+            continue;
+        }
+        if let Some((start, content)) = keywords.take() {
+            for (i, kw) in content.lines().enumerate() {
+                out.text(start + i as u32, kw);
+            }
+        }
+        if is_org_headline(line) {
+            out.push(lineno, lineno, "section", line.to_string());
+        } else {
+            out.text(lineno, line);
+        }
+    }
+    if let Some((start, content, _)) = block {
+        out.push(start, last_line, "code", content);
+    }
+    if let Some((start, content)) = keywords {
+        for (i, kw) in content.lines().enumerate() {
+            out.text(start + i as u32, kw);
+        }
+    }
+    out.finish(source)
+}
+
+fn org_keyword_is(line: &str, keyword: &str) -> bool {
+    line.strip_prefix("#+")
+        .and_then(|rest| Some((rest.get(..keyword.len())?, &rest[keyword.len()..])))
+        .is_some_and(|(head, rest)| {
+            head.eq_ignore_ascii_case(keyword)
+                && (keyword.ends_with(':') || rest.is_empty() || rest.starts_with([' ', '\t']))
+        })
+}
+
+pub(in crate::block_parser) fn is_org_headline(line: &str) -> bool {
+    let rest = line.trim_start_matches('*');
+    rest.len() < line.len() && rest.starts_with(' ')
+}
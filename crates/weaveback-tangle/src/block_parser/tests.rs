@@ -190,6 +190,32 @@ fn adoc_utf8_before_code_keeps_line_range() {
     assert_eq!(code.line_end, 6);
 }
 
+#[test]
+fn noweb_code_chunks_end_at_doc_lines_and_definitions() {
+    let src = "\\section{Intro}\nSome prose.\n\n<<a>>=\nalpha\n<<b>>=\nbeta\n@ More prose.\n";
+    let blocks = parse_source_blocks(src, "nw");
+    let spans: Vec<_> = blocks.iter().map(|b| (b.block_type.as_str(), b.line_start, b.line_end)).collect();
+    assert_eq!(
+        spans,
+        vec![("section", 1, 1), ("para", 2, 2), ("code", 4, 5), ("code", 6, 7), ("para", 8, 8)]
+    );
+    assert!(is_noweb_definition("<<@file out.c>>="));
+    assert!(!is_noweb_definition("<<ref>>"));
+}
+
+#[test]
+fn org_src_blocks_include_their_affiliated_keywords() {
+    let src = "* Heading\nIntro.\n\n#+name: helper\n#+BEGIN_SRC rust :tangle src/a.rs\nfn a() {}\n#+END_SRC\n** Sub\n#+begin_example\nunterminated\n";
+    let blocks = parse_source_blocks(src, "org");
+    let spans: Vec<_> = blocks.iter().map(|b| (b.block_type.as_str(), b.line_start, b.line_end)).collect();
+    assert_eq!(
+        spans,
+        vec![("section", 1, 1), ("para", 2, 2), ("code", 4, 7), ("section", 8, 8), ("code", 9, 10)]
+    );
+    assert!(is_org_headline("*** Deep"));
+    assert!(!is_org_headline("*bold* text"));
+}
+
 #[test]
 fn block_index_is_sequential() {
     let src = "# H\n\nPara one.\n\nPara two.\n";
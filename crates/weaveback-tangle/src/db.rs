@@ -5,6 +5,7 @@ use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
 use std::path::Path;
 
 mod schema;
+mod migrations;
 mod types;
 mod open;
 mod baselines;
@@ -15,16 +16,25 @@ mod macro_map;
 mod config;
 mod source_blocks;
 mod merge;
+mod bundle;
+mod maintenance;
+mod pass_outputs;
 mod snapshots_defs;
 mod fts;
 
-pub use fts::{BlockForEmbedding, FtsResult, TaggedBlock};
+pub use bundle::{BUNDLE_FORMAT, BUNDLE_VERSION, BundleBlob, BundleCounts, BundleRecord};
+pub use maintenance::{CheckIssue, CheckReport, GcReport, PathRole};
+pub use fts::{ANN_MIN_VECTORS, BlockForEmbedding, CodeFtsResult, FtsResult, TaggedBlock};
+pub(in crate::db) use fts::pack_vector;
 pub use types::BlockForTagging;
 pub use open::WeavebackDb;
+pub use migrations::{MIGRATIONS, Migration, SCHEMA_VERSION, pending_migrations};
 pub use types::*;
 
+pub(in crate::db) use noweb_map::line_runs;
 pub(in crate::db) use open::{apply_schema, intern_file};
 pub(in crate::db) use schema::CREATE_SCHEMA;
+pub(in crate::db) use migrations::{check_not_newer, is_blank, migrate, user_version};
 
 #[cfg(test)]
 mod tests;
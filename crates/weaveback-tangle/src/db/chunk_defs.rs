@@ -2,6 +2,7 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 use super::*;
+use crate::is_transclusion;
 
 impl WeavebackDb {
     pub fn set_chunk_defs(&mut self, entries: &[ChunkDefEntry]) -> Result<(), DbError> {
@@ -72,6 +73,9 @@ impl WeavebackDb {
             .optional()?)
     }
 
+    /// Chunk definitions of `src_file`, or of every source, in file and line
+    /// order.  `<<@from …>>` pseudo-chunks are left out: they are recorded
+    /// only so that incremental runs notice edits to transcluded files.
     pub fn list_chunk_defs(&self, src_file: Option<&str>) -> Result<Vec<ChunkDefEntry>, DbError> {
         fn map_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ChunkDefEntry> {
             Ok(ChunkDefEntry {
@@ -82,21 +86,22 @@ impl WeavebackDb {
                 def_end:    row.get::<_, u32>(4)?,
             })
         }
-        if let Some(f) = src_file {
+        let defs = if let Some(f) = src_file {
             let mut stmt = self.conn.prepare(
                 "SELECT f.path, cdef.chunk_name, cdef.nth, cdef.def_start, cdef.def_end
                  FROM chunk_defs cdef JOIN files f ON f.id = cdef.src_file
                  WHERE f.path = ?1 ORDER BY f.path, cdef.def_start",
             )?;
-            Ok(stmt.query_map(params![f], map_row)?.collect::<Result<Vec<_>, _>>()?)
+            stmt.query_map(params![f], map_row)?.collect::<Result<Vec<_>, _>>()?
         } else {
             let mut stmt = self.conn.prepare(
                 "SELECT f.path, cdef.chunk_name, cdef.nth, cdef.def_start, cdef.def_end
                  FROM chunk_defs cdef JOIN files f ON f.id = cdef.src_file
                  ORDER BY f.path, cdef.def_start",
             )?;
-            Ok(stmt.query_map([], map_row)?.collect::<Result<Vec<_>, _>>()?)
-        }
+            stmt.query_map([], map_row)?.collect::<Result<Vec<_>, _>>()?
+        };
+        Ok(defs.into_iter().filter(|def| !is_transclusion(&def.chunk_name)).collect())
     }
 
     pub fn find_chunk_defs_by_name(&self, chunk_name: &str) -> Result<Vec<ChunkDefEntry>, DbError> {
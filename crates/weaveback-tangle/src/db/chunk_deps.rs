@@ -2,6 +2,7 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 use super::*;
+use crate::is_transclusion;
 
 impl WeavebackDb {
     /// Write direct chunk→chunk dependency edges.
@@ -46,7 +47,8 @@ impl WeavebackDb {
     }
 
     /// Return `(to_chunk, src_file)` pairs for all chunks that `chunk_name`
-    /// directly references.
+    /// directly references.  Edges to `<<@from …>>` transclusions are left
+    /// out, as in `query_all_chunk_deps`.
     pub fn query_chunk_deps(
         &self,
         chunk_name: &str,
@@ -59,7 +61,8 @@ impl WeavebackDb {
         let rows = stmt.query_map(params![chunk_name], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
         })?;
-        rows.collect::<Result<_, _>>().map_err(DbError::Sql)
+        let deps: Vec<(String, String)> = rows.collect::<Result<_, _>>()?;
+        Ok(deps.into_iter().filter(|(to, _)| !is_transclusion(to)).collect())
     }
 
     /// Return `(from_chunk, src_file)` pairs for all chunks that directly
@@ -81,7 +84,10 @@ impl WeavebackDb {
 
     /// Return every `(from_chunk, to_chunk, src_file)` triple stored in the
     /// graph, ordered by `from_chunk` then `to_chunk`.  Used by
-    /// `weaveback graph` to export the full DOT representation.
+    /// `weaveback graph` to export the full DOT representation.  Edges to
+    /// `<<@from …>>` transclusions are stored for incremental runs (see
+    /// `query_reverse_deps`) but are not part of the chunk graph, so they are
+    /// left out.
     pub fn query_all_chunk_deps(&self) -> Result<Vec<(String, String, String)>, DbError> {
         let mut stmt = self.conn.prepare_cached(
             "SELECT cd.from_chunk, cd.to_chunk, f.path
@@ -95,7 +101,8 @@ impl WeavebackDb {
                 row.get::<_, String>(2)?,
             ))
         })?;
-        rows.collect::<Result<_, _>>().map_err(DbError::Sql)
+        let deps: Vec<(String, String, String)> = rows.collect::<Result<_, _>>()?;
+        Ok(deps.into_iter().filter(|(_, to, _)| !is_transclusion(to)).collect())
     }
 
     /// Return the distinct output files that contain lines attributed to
@@ -55,10 +55,11 @@ impl WeavebackDb {
         src_line: u32,
     ) -> Result<Option<(String, u32)>, DbError> {
         let mut stmt = self.conn.prepare_cached(
-            "SELECT f_out.path, nm.out_line FROM noweb_map nm
+            "SELECT f_out.path, nm.out_start + (?2 - nm.src_start) FROM noweb_map nm
              JOIN files f_out ON f_out.id = nm.out_file
              JOIN files f_src ON f_src.id = nm.src_file
-             WHERE f_src.path = ?1 AND nm.src_line = ?2
+             WHERE f_src.path = ?1 AND nm.src_start <= ?2
+               AND nm.src_start + (nm.out_end - nm.out_start) >= ?2
              LIMIT 1",
         )?;
         Ok(stmt.query_row(params![src_file, src_line], |row| {
@@ -87,17 +88,25 @@ impl WeavebackDb {
         src_file: &str,
     ) -> Result<Vec<(u32, String, u32)>, DbError> {
         let mut stmt = self.conn.prepare_cached(
-            "SELECT nm.src_line, f_out.path, nm.out_line FROM noweb_map nm
+            "SELECT nm.src_start, f_out.path, nm.out_start, nm.out_end FROM noweb_map nm
              JOIN files f_out ON f_out.id = nm.out_file
              JOIN files f_src ON f_src.id = nm.src_file
              WHERE f_src.path = ?1",
         )?;
         let rows = stmt.query_map(params![src_file], |row| {
-            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            Ok((
+                row.get::<_, u32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, u32>(2)?,
+                row.get::<_, u32>(3)?,
+            ))
         })?;
         let mut res = Vec::new();
         for row in rows {
-            res.push(row?);
+            let (src_start, out_file, out_start, out_end) = row?;
+            for out_line in out_start..=out_end {
+                res.push((src_start + (out_line - out_start), out_file.clone(), out_line));
+            }
         }
         Ok(res)
     }
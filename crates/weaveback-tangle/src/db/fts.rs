@@ -3,10 +3,14 @@
 
 use super::*;
 
+mod ann;
+mod code;
 mod embeddings;
 mod helpers;
 mod prose;
 mod tags;
 mod types;
 
-pub use types::{BlockForEmbedding, FtsResult, SemanticResult, TaggedBlock};
+pub use ann::ANN_MIN_VECTORS;
+pub(in crate::db) use helpers::pack_vector;
+pub use types::{BlockForEmbedding, CodeFtsResult, FtsResult, SemanticResult, TaggedBlock};
@@ -0,0 +1,156 @@
+// weaveback-tangle/src/db/fts/code.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::helpers::normalise_snapshot_path;
+use super::*;
+
+/// Split one identifier at `_` and at lower-to-upper case boundaries
+/// (keeping acronyms together), lower-cased.
+fn identifier_parts(identifier: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    for segment in identifier.split('_').filter(|s| !s.is_empty()) {
+        let chars: Vec<char> = segment.chars().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (prev, cur) = (chars[i - 1], chars[i]);
+            let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            let boundary = cur.is_uppercase()
+                && (prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower));
+            if boundary {
+                parts.push(chars[start..i].iter().collect::<String>().to_lowercase());
+                start = i;
+            }
+        }
+        parts.push(chars[start..].iter().collect::<String>().to_lowercase());
+    }
+    parts
+}
+
+/// The `terms` column of a chunk body: the parts of every identifier that
+/// has more than one.
+pub(in crate::db) fn identifier_terms(body: &str) -> String {
+    let mut terms: Vec<String> = Vec::new();
+    for identifier in body.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        let parts = identifier_parts(identifier);
+        if parts.len() > 1 {
+            terms.extend(parts);
+        }
+    }
+    terms.join(" ")
+}
+
+impl WeavebackDb {
+    /// Rebuild `prose_fts` and `code_fts`.  `root` overrides the CWD for
+    /// path normalisation.
+    pub fn rebuild_fts(&mut self, root: Option<&std::path::Path>) -> Result<(), DbError> {
+        self.rebuild_prose_fts(root)?;
+        self.rebuild_code_fts(root)
+    }
+
+    /// Rebuild the `code_fts` index from `src_snapshots` + `chunk_defs`.
+    pub fn rebuild_code_fts(&mut self, root: Option<&std::path::Path>) -> Result<(), DbError> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM code_fts", [])?;
+        let cwd = root.map(|p| p.to_path_buf()).unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+
+        let snapshots: Vec<(String, String)> = {
+            let mut stmt = tx.prepare("SELECT path, content FROM src_snapshots")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?;
+            let mut seen = std::collections::HashSet::new();
+            rows.filter_map(|r| r.ok())
+                .filter_map(|(path, bytes)| {
+                    let path = normalise_snapshot_path(&path, &cwd);
+                    String::from_utf8(bytes).ok().map(|s| (path, s))
+                })
+                .filter(|(path, _)| seen.insert(path.clone()))
+                .collect()
+        };
+
+        for (path, source) in &snapshots {
+            let lines: Vec<&str> = source.lines().collect();
+            let mut stmt = tx.prepare_cached(
+                "SELECT cdef.chunk_name, cdef.nth, cdef.def_start, cdef.def_end
+                 FROM chunk_defs cdef JOIN files f ON f.id = cdef.src_file
+                 WHERE f.path = ?1
+                 ORDER BY cdef.def_start",
+            )?;
+            let defs: Vec<(String, u32, u32, u32)> = stmt
+                .query_map(params![path], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                })?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            let mut ins = tx.prepare_cached(
+                "INSERT INTO code_fts (content, terms, src_file, chunk_name, nth, line_start, line_end)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for (chunk_name, nth, def_start, def_end) in defs {
+                // The body lies between the open marker (`def_start`) and
+                // the close marker (`def_end`), both 1-indexed.
+                let lo = def_start as usize;
+                let hi = (def_end as usize).saturating_sub(1).min(lines.len());
+                if lo >= hi { continue; }
+                let content = lines[lo..hi].join("\n");
+                if content.trim().is_empty() { continue; }
+                ins.execute(params![
+                    content,
+                    identifier_terms(&content),
+                    path,
+                    chunk_name,
+                    nth,
+                    def_start + 1,
+                    def_end - 1
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// BM25-ranked full-text search over chunk bodies.
+    pub fn search_code(&self, query: &str, limit: usize) -> Result<Vec<CodeFtsResult>, DbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT src_file, chunk_name, nth, line_start, line_end,
+                    snippet(code_fts, 0, '**', '**', '…', 16)
+             FROM code_fts
+             WHERE code_fts MATCH ?1
+             ORDER BY rank
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![query, limit as i64], |row| {
+            Ok(CodeFtsResult {
+                src_file:        row.get(0)?,
+                chunk_name:      row.get(1)?,
+                nth:             row.get(2)?,
+                line_start:      row.get(3)?,
+                line_end:        row.get(4)?,
+                snippet:         row.get(5)?,
+                generated_files: Vec::new(),
+            })
+        })?;
+        let mut results = rows.collect::<Result<Vec<_>, _>>()?;
+
+        let mut outputs = self.conn.prepare_cached(
+            "SELECT DISTINCT f_out.path
+             FROM noweb_map nm
+             JOIN files f_out ON f_out.id = nm.out_file
+             JOIN files f_src ON f_src.id = nm.src_file
+             WHERE f_src.path = ?1 AND nm.chunk_name = ?2
+               AND nm.src_start BETWEEN ?3 AND ?4
+             ORDER BY f_out.path",
+        )?;
+        for result in &mut results {
+            // noweb_map source lines are 0-indexed.
+            let files = outputs.query_map(
+                params![result.src_file, result.chunk_name, result.line_start - 1, result.line_end - 1],
+                |row| row.get(0),
+            )?;
+            result.generated_files = files.collect::<Result<_, _>>()?;
+        }
+        Ok(results)
+    }
+}
@@ -1,7 +1,7 @@
 // weaveback-tangle/src/db/fts/embeddings.rs
 // I'd Really Rather You Didn't edit this generated file.
 
-use super::helpers::{cosine_similarity, normalise_snapshot_path, prose_snippet};
+use super::helpers::{cosine_similarity, normalise_snapshot_path, pack_vector, prose_snippet, unpack_vector};
 use super::*;
 
 impl WeavebackDb {
@@ -32,7 +32,8 @@ impl WeavebackDb {
         Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
 
-    /// Store an embedding vector for a prose block. Overwrites any previous entry.
+    /// Store an embedding vector for a prose block. Overwrites any previous
+    /// entry.  The vector joins the nearest list of its model's index.
     pub fn set_block_embedding(
         &mut self,
         src_file: &str,
@@ -42,58 +43,115 @@ impl WeavebackDb {
         vector: &[f32],
     ) -> Result<(), DbError> {
         let file_id = intern_file(&self.conn, src_file)?;
-        let vector_json = serde_json::to_string(vector)?;
+        let list_id = self.nearest_list(model, vector)?;
         self.conn.execute(
             "INSERT OR REPLACE INTO block_embeddings
-             (src_file, block_index, content_hash, model, vector_json)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
-            params![file_id, block_index, content_hash, model, vector_json],
+             (src_file, block_index, content_hash, model, vector, list_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![file_id, block_index, content_hash, model, pack_vector(vector), list_id],
         )?;
         Ok(())
     }
 
-    /// Brute-force cosine search over stored prose-block embeddings.
+    /// Cosine search over stored prose-block embeddings.  Probes the
+    /// embedding index when one covers the query's dimension, and falls back
+    /// to the exact scan when the probed lists yield fewer than `limit` hits.
     pub fn search_prose_by_embedding(
         &self,
         query_embedding: &[f32],
         limit: usize,
     ) -> Result<Vec<SemanticResult>, DbError> {
-        let cwd = std::env::current_dir().unwrap_or_default();
-        let mut snapshot_cache: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-        let mut stmt = self.conn.prepare(
-            "SELECT f.path, sb.block_type, sb.line_start, sb.line_end,
-                    COALESCE(bt.tags, ''), be.vector_json
-             FROM block_embeddings be
-             JOIN files f ON f.id = be.src_file
-             JOIN source_blocks sb
-               ON sb.src_file = be.src_file AND sb.block_index = be.block_index
-             LEFT JOIN block_tags bt
-               ON bt.src_file = be.src_file AND bt.block_index = be.block_index
-             WHERE sb.block_type IN ('section', 'para')",
-        )?;
-        let rows = stmt.query_map([], |row| {
+        if let Some(lists) = self.probe_lists(query_embedding)? {
+            let results = self.semantic_search(query_embedding, Some(&lists), limit)?;
+            if results.len() >= limit {
+                return Ok(results);
+            }
+        }
+        self.semantic_search(query_embedding, None, limit)
+    }
+
+    /// Brute-force cosine search over every stored prose-block embedding.
+    pub fn search_prose_by_embedding_exact(
+        &self,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<SemanticResult>, DbError> {
+        self.semantic_search(query_embedding, None, limit)
+    }
+
+    /// Every embedded section and paragraph, with its vector.
+    pub(crate) const EXACT_SEMANTIC_SQL: &str =
+        "SELECT f.path, sb.block_type, sb.line_start, sb.line_end,
+                COALESCE(bt.tags, ''), be.vector
+         FROM block_embeddings be
+         JOIN files f ON f.id = be.src_file
+         JOIN source_blocks sb
+           ON sb.src_file = be.src_file AND sb.block_index = be.block_index
+         LEFT JOIN block_tags bt
+           ON bt.src_file = be.src_file AND bt.block_index = be.block_index
+         WHERE sb.block_type IN ('section', 'para')";
+
+    /// Like `EXACT_SEMANTIC_SQL`, restricted to the lists in the JSON array
+    /// `?1` and the vectors in no list.  Both arms are lookups on
+    /// `idx_block_embeddings_list`, so vectors outside the probed lists are
+    /// never read.
+    pub(crate) const PROBED_SEMANTIC_SQL: &str =
+        "SELECT f.path, sb.block_type, sb.line_start, sb.line_end,
+                COALESCE(bt.tags, ''), be.vector
+         FROM (SELECT src_file, block_index, vector FROM block_embeddings
+               WHERE list_id IN (SELECT value FROM json_each(?1))
+               UNION ALL
+               SELECT src_file, block_index, vector FROM block_embeddings
+               WHERE list_id IS NULL) be
+         JOIN files f ON f.id = be.src_file
+         JOIN source_blocks sb
+           ON sb.src_file = be.src_file AND sb.block_index = be.block_index
+         LEFT JOIN block_tags bt
+           ON bt.src_file = be.src_file AND bt.block_index = be.block_index
+         WHERE sb.block_type IN ('section', 'para')";
+
+    /// Score the vectors in `lists` (and those in no list), or all vectors
+    /// when `lists` is `None`, and return the best `limit` whose text can
+    /// still be read from the source snapshots.
+    fn semantic_search(
+        &self,
+        query_embedding: &[f32],
+        lists: Option<&[i64]>,
+        limit: usize,
+    ) -> Result<Vec<SemanticResult>, DbError> {
+        let lists_json = lists.map(serde_json::to_string).transpose()?;
+        let sql = if lists.is_some() { Self::PROBED_SEMANTIC_SQL } else { Self::EXACT_SEMANTIC_SQL };
+        let mut stmt = self.conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(lists_json.iter()), |row| {
             Ok((
                 row.get::<_, String>(0)?,
                 row.get::<_, String>(1)?,
                 row.get::<_, u32>(2)?,
                 row.get::<_, u32>(3)?,
                 row.get::<_, String>(4)?,
-                row.get::<_, String>(5)?,
+                row.get::<_, Vec<u8>>(5)?,
             ))
         })?;
 
-        let mut results = Vec::new();
+        let mut scored = Vec::new();
         for row in rows {
-            let (src_file, block_type, line_start, line_end, tags, vector_json) = row?;
-            let block_embedding: Vec<f32> = serde_json::from_str(&vector_json)?;
-            let score = cosine_similarity(query_embedding, &block_embedding);
-            if !score.is_finite() || score <= 0.0 {
-                continue;
+            let (src_file, block_type, line_start, line_end, tags, vector) = row?;
+            let score = cosine_similarity(query_embedding, &unpack_vector(&vector));
+            if score.is_finite() && score > 0.0 {
+                scored.push((score, src_file, block_type, line_start, line_end, tags));
             }
+        }
+        scored.sort_by(|lhs, rhs| rhs.0.partial_cmp(&lhs.0).unwrap_or(std::cmp::Ordering::Equal));
 
-            let snapshot = if let Some(cached) = snapshot_cache.get(&src_file) {
-                cached.clone()
-            } else {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let mut snapshot_cache: std::collections::HashMap<String, Option<String>> =
+            std::collections::HashMap::new();
+        let mut results = Vec::new();
+        for (score, src_file, block_type, line_start, line_end, tags) in scored {
+            if results.len() >= limit {
+                break;
+            }
+            if !snapshot_cache.contains_key(&src_file) {
                 let bytes = self.get_src_snapshot(&src_file)?
                     .or_else(|| {
                         let alt = normalise_snapshot_path(&src_file, &cwd);
@@ -103,11 +161,10 @@ impl WeavebackDb {
                             self.get_src_snapshot(&alt).ok().flatten()
                         }
                     });
-                let Some(bytes) = bytes else { continue; };
-                let Ok(source) = String::from_utf8(bytes) else { continue; };
-                snapshot_cache.insert(src_file.clone(), source.clone());
-                source
-            };
+                let source = bytes.and_then(|bytes| String::from_utf8(bytes).ok());
+                snapshot_cache.insert(src_file.clone(), source);
+            }
+            let Some(snapshot) = &snapshot_cache[&src_file] else { continue; };
 
             let lines: Vec<&str> = snapshot.lines().collect();
             let lo = (line_start as usize).saturating_sub(1);
@@ -126,9 +183,6 @@ impl WeavebackDb {
                 score,
             });
         }
-
-        results.sort_by(|lhs, rhs| rhs.score.partial_cmp(&lhs.score).unwrap_or(std::cmp::Ordering::Equal));
-        results.truncate(limit);
         Ok(results)
     }
 }
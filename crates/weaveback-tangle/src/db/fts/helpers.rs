@@ -23,6 +23,18 @@ pub(super) fn prose_snippet(content: &str) -> String {
     }
 }
 
+/// Pack a vector as little-endian `f32`s, the `block_embeddings.vector` and
+/// `embedding_lists.centroid` layout.
+pub(in crate::db) fn pack_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+/// Inverse of `pack_vector`; a trailing partial `f32` is ignored.
+pub(in crate::db) fn unpack_vector(bytes: &[u8]) -> Vec<f32> {
+    let (floats, _) = bytes.as_chunks::<4>();
+    floats.iter().map(|b| f32::from_le_bytes(*b)).collect()
+}
+
 pub(super) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.is_empty() || a.len() != b.len() {
         return 0.0;
@@ -14,6 +14,21 @@ pub struct FtsResult {
     pub tags:       String,
 }
 
+/// A chunk body returned by `search_code`.
+#[derive(Debug, Clone)]
+pub struct CodeFtsResult {
+    pub src_file:        String,
+    pub chunk_name:      String,
+    pub nth:             u32,
+    /// First and last body line (1-indexed), excluding the chunk markers.
+    pub line_start:      u32,
+    pub line_end:        u32,
+    /// Short excerpt with matched terms wrapped in `**...**`.
+    pub snippet:         String,
+    /// Generated files the chunk body was tangled into.
+    pub generated_files: Vec<String>,
+}
+
 /// A tagged block returned by `list_block_tags`.
 #[derive(Debug, Clone)]
 pub struct TaggedBlock {
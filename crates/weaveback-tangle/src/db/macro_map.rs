@@ -15,12 +15,14 @@ impl WeavebackDb {
         let file_id = intern_file(&self.conn, driver_file)?;
         let tx = self.conn.transaction()?;
         {
+            tx.execute("DELETE FROM macro_map WHERE driver_file = ?1", params![file_id])?;
             let mut stmt = tx.prepare_cached(
-                "INSERT OR REPLACE INTO macro_map (driver_file, expanded_line, data)
-                 VALUES (?1, ?2, ?3)",
+                "INSERT INTO macro_map (driver_file, expanded_start, expanded_end, data)
+                 VALUES (?1, ?2, ?3, ?4)",
             )?;
-            for (line, bytes) in entries {
-                stmt.execute(params![file_id, *line, bytes.as_slice()])?;
+            let runs = line_runs(entries, |head: &Vec<u8>, _, bytes| bytes == head);
+            for (start, end, bytes) in runs {
+                stmt.execute(params![file_id, start, end, bytes.as_slice()])?;
             }
         }
         tx.commit()?;
@@ -35,8 +37,14 @@ impl WeavebackDb {
         Ok(self
             .conn
             .query_row(
-                "SELECT mm.data FROM macro_map mm JOIN files f ON f.id = mm.driver_file
-                 WHERE f.path = ?1 AND mm.expanded_line = ?2",
+                "WITH hit AS (
+                     SELECT mm.expanded_end, mm.data
+                     FROM macro_map mm JOIN files f ON f.id = mm.driver_file
+                     WHERE f.path = ?1 AND mm.expanded_start <= ?2
+                     ORDER BY mm.expanded_start DESC
+                     LIMIT 1
+                 )
+                 SELECT data FROM hit WHERE expanded_end >= ?2",
                 params![driver_file, expanded_line],
                 |row| row.get(0),
             )
@@ -0,0 +1,341 @@
+// weaveback-tangle/src/db/maintenance.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+
+/// What a path stored in the database names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathRole {
+    /// A literate source (or macro driver) file.
+    Source,
+    /// A generated file, as keyed in `noweb_map`.
+    Output,
+    /// A `gen_baselines` key, relative to the `gen` directory.
+    Baseline,
+}
+
+/// Tables whose rows belong to a source file, with their file-id column.
+const SOURCE_TABLES: &[(&str, &str)] = &[
+    ("chunk_defs", "src_file"),
+    ("chunk_deps", "src_file"),
+    ("source_blocks", "src_file"),
+    ("block_tags", "src_file"),
+    ("block_embeddings", "src_file"),
+    ("var_defs", "src_file"),
+    ("macro_defs", "src_file"),
+    ("macro_calls", "src_file"),
+    ("literate_source_config", "src_file"),
+    ("noweb_map", "src_file"),
+    ("macro_map", "driver_file"),
+];
+
+/// What `gc` removed, or would remove with `dry_run`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct GcReport {
+    pub dry_run: bool,
+    /// Source files that no longer exist.
+    pub missing_sources: Vec<String>,
+    /// Output files that no longer exist.
+    pub missing_outputs: Vec<String>,
+    /// Baselines of outputs that are gone or no longer produced.
+    pub stale_baselines: Vec<String>,
+    /// Rows deleted, by table.
+    pub deleted_rows: std::collections::BTreeMap<String, usize>,
+}
+
+impl GcReport {
+    fn deleted(&mut self, table: &str, rows: usize) {
+        if rows > 0 {
+            *self.deleted_rows.entry(table.to_string()).or_default() += rows;
+        }
+    }
+}
+
+/// `(id, path)` of every file whose id appears in one of `columns`.
+fn files_in(conn: &Connection, columns: &[(&str, &str)]) -> Result<Vec<(i64, String)>, DbError> {
+    let ids = columns
+        .iter()
+        .map(|(table, column)| format!("SELECT {column} FROM {table}"))
+        .collect::<Vec<_>>()
+        .join(" UNION ");
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, path FROM files WHERE id IN ({ids}) ORDER BY path"
+    ))?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect::<Result<_, _>>().map_err(DbError::Sql)
+}
+
+impl WeavebackDb {
+    /// Remove the rows of files that no longer exist or are no longer
+    /// produced.  `exists` answers for each stored path.
+    pub fn gc(
+        &mut self,
+        exists: &dyn Fn(PathRole, &str) -> bool,
+        dry_run: bool,
+    ) -> Result<GcReport, DbError> {
+        let mut report = GcReport { dry_run, ..GcReport::default() };
+        let tx = self.conn.transaction()?;
+
+        for (id, path) in files_in(&tx, &[("noweb_map", "out_file")])? {
+            if !exists(PathRole::Output, &path) {
+                let rows = tx.execute("DELETE FROM noweb_map WHERE out_file = ?1", params![id])?;
+                report.deleted("noweb_map", rows);
+                report.missing_outputs.push(path);
+            }
+        }
+
+        for (id, path) in files_in(&tx, SOURCE_TABLES)? {
+            if exists(PathRole::Source, &path) {
+                continue;
+            }
+            for (table, column) in SOURCE_TABLES {
+                let rows = tx.execute(&format!("DELETE FROM {table} WHERE {column} = ?1"), params![id])?;
+                report.deleted(table, rows);
+            }
+            let rows = tx.execute("DELETE FROM prose_fts WHERE src_file = ?1", params![path])?;
+            report.deleted("prose_fts", rows);
+            let rows = tx.execute("DELETE FROM code_fts WHERE src_file = ?1", params![path])?;
+            report.deleted("code_fts", rows);
+            report.missing_sources.push(path);
+        }
+        let snapshots: Vec<String> = tx
+            .prepare("SELECT path FROM src_snapshots ORDER BY path")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for path in snapshots {
+            if !exists(PathRole::Source, &path) {
+                let rows = tx.execute("DELETE FROM src_snapshots WHERE path = ?1", params![path])?;
+                report.deleted("src_snapshots", rows);
+                if !report.missing_sources.contains(&path) {
+                    report.missing_sources.push(path);
+                }
+            }
+        }
+
+        let produced: std::collections::HashSet<String> = tx
+            .prepare("SELECT path FROM pass_outputs")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        let baselines: Vec<String> = tx
+            .prepare("SELECT path FROM gen_baselines ORDER BY path")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for path in baselines {
+            let unproduced = !produced.is_empty() && !produced.contains(&path);
+            if unproduced || !exists(PathRole::Baseline, &path) {
+                let rows = tx.execute("DELETE FROM gen_baselines WHERE path = ?1", params![path])?;
+                report.deleted("gen_baselines", rows);
+                report.stale_baselines.push(path);
+            }
+        }
+
+        let referenced = SOURCE_TABLES
+            .iter()
+            .chain(&[("noweb_map", "out_file")])
+            .map(|(table, column)| format!("SELECT {column} FROM {table}"))
+            .collect::<Vec<_>>()
+            .join(" UNION ");
+        let rows = tx.execute(&format!("DELETE FROM files WHERE id NOT IN ({referenced})"), [])?;
+        report.deleted("files", rows);
+
+        if !dry_run {
+            tx.commit()?;
+        }
+        Ok(report)
+    }
+}
+
+/// One problem found by `check`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CheckIssue {
+    pub kind: &'static str,
+    pub table: String,
+    pub path: String,
+    pub detail: String,
+}
+
+/// Everything `check` found; empty when the database is consistent.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct CheckReport {
+    pub issues: Vec<CheckIssue>,
+}
+
+impl CheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn push(&mut self, kind: &'static str, table: &str, path: &str, detail: String) {
+        self.issues.push(CheckIssue {
+            kind,
+            table: table.to_string(),
+            path: path.to_string(),
+            detail,
+        });
+    }
+}
+
+/// Line counts of files read through `read`, each read at most once.
+struct LineCounts<'a> {
+    read: &'a dyn Fn(PathRole, &str) -> Option<Vec<u8>>,
+    known: std::collections::HashMap<(bool, String), Option<u32>>,
+}
+
+impl LineCounts<'_> {
+    fn get(&mut self, role: PathRole, path: &str) -> Option<u32> {
+        let read = self.read;
+        *self
+            .known
+            .entry((role == PathRole::Source, path.to_string()))
+            .or_insert_with(|| read(role, path).map(|bytes| String::from_utf8_lossy(&bytes).lines().count() as u32))
+    }
+}
+
+impl WeavebackDb {
+    /// Validate foreign keys, stored line ranges against the current
+    /// sources and outputs, and baselines against the generated files.
+    /// `read` returns the content of a stored path, or `None` if it cannot
+    /// be read.
+    pub fn check(&self, read: &dyn Fn(PathRole, &str) -> Option<Vec<u8>>) -> Result<CheckReport, DbError> {
+        let mut report = CheckReport::default();
+        let mut lines = LineCounts { read, known: Default::default() };
+
+        let mut stmt = self.conn.prepare(
+            "SELECT \"table\", parent, COUNT(*) FROM pragma_foreign_key_check
+             GROUP BY \"table\", parent ORDER BY \"table\"",
+        )?;
+        let violations = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?;
+        for violation in violations {
+            let (table, parent, rows) = violation?;
+            report.push("foreign_key", &table, "", format!("{rows} row(s) refer to missing {parent} rows"));
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT f_out.path, nm.out_start, nm.out_end, f_src.path, nm.src_start
+             FROM noweb_map nm
+             JOIN files f_out ON f_out.id = nm.out_file
+             JOIN files f_src ON f_src.id = nm.src_file
+             ORDER BY f_out.path, nm.out_start",
+        )?;
+        let ranges = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, u32>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, u32>(4)?,
+            ))
+        })?;
+        let mut previous: Option<(String, u32)> = None;
+        for range in ranges {
+            let (out_file, out_start, out_end, src_file, src_start) = range?;
+            if out_end < out_start {
+                report.push("bad_range", "noweb_map", &out_file, format!("lines {out_start}..={out_end}"));
+                continue;
+            }
+            if let Some((file, end)) = &previous
+                && *file == out_file
+                && *end >= out_start
+            {
+                report.push("overlapping_range", "noweb_map", &out_file, format!("line {out_start} is mapped twice"));
+            }
+            previous = Some((out_file.clone(), out_end));
+            match lines.get(PathRole::Output, &out_file) {
+                None => {}
+                Some(count) if out_end >= count => report.push(
+                    "line_out_of_range",
+                    "noweb_map",
+                    &out_file,
+                    format!("output lines {out_start}..={out_end}, file has {count}"),
+                ),
+                Some(_) => {}
+            }
+            let src_end = src_start + (out_end - out_start);
+            match lines.get(PathRole::Source, &src_file) {
+                None => {}
+                Some(count) if src_end >= count => report.push(
+                    "line_out_of_range",
+                    "noweb_map",
+                    &src_file,
+                    format!("source lines {src_start}..={src_end} (for {out_file}), file has {count}"),
+                ),
+                Some(_) => {}
+            }
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT f.path, mm.expanded_start, mm.expanded_end
+             FROM macro_map mm JOIN files f ON f.id = mm.driver_file
+             ORDER BY f.path, mm.expanded_start",
+        )?;
+        let ranges = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?, row.get::<_, u32>(2)?))
+        })?;
+        let mut previous: Option<(String, u32)> = None;
+        for range in ranges {
+            let (driver, start, end) = range?;
+            if end < start {
+                report.push("bad_range", "macro_map", &driver, format!("lines {start}..={end}"));
+            } else if let Some((file, previous_end)) = &previous
+                && *file == driver
+                && *previous_end >= start
+            {
+                report.push("overlapping_range", "macro_map", &driver, format!("line {start} is mapped twice"));
+            }
+            previous = Some((driver, end));
+        }
+
+        for (table, start, end) in [("chunk_defs", "def_start", "def_end"), ("source_blocks", "line_start", "line_end")] {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT f.path, MAX(t.{end}) FROM {table} t JOIN files f ON f.id = t.src_file
+                 GROUP BY f.path ORDER BY f.path"
+            ))?;
+            let spans = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?)))?;
+            for span in spans {
+                let (path, last) = span?;
+                if let Some(count) = lines.get(PathRole::Source, &path)
+                    && last > count
+                {
+                    report.push(
+                        "line_out_of_range",
+                        table,
+                        &path,
+                        format!("{start}..{end} reach line {last}, file has {count}"),
+                    );
+                }
+            }
+        }
+
+        let mut mapped: Vec<(PathRole, String)> = Vec::new();
+        for (id_column, role) in [("src_file", PathRole::Source), ("out_file", PathRole::Output)] {
+            for (_, path) in files_in(&self.conn, &[("noweb_map", id_column)])? {
+                mapped.push((role, path));
+            }
+        }
+        for (role, path) in mapped {
+            if lines.get(role, &path).is_none() {
+                let kind = if role == PathRole::Source { "missing_source" } else { "missing_output" };
+                report.push(kind, "noweb_map", &path, "cannot be read".to_string());
+            }
+        }
+
+        let mut stmt = self.conn.prepare("SELECT path, content FROM gen_baselines ORDER BY path")?;
+        let baselines = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?)))?;
+        for baseline in baselines {
+            let (path, content) = baseline?;
+            match read(PathRole::Baseline, &path) {
+                None => report.push("baseline_missing", "gen_baselines", &path, "generated file is missing".to_string()),
+                Some(on_disk) if on_disk != content => report.push(
+                    "baseline_modified",
+                    "gen_baselines",
+                    &path,
+                    "generated file differs from its baseline".to_string(),
+                ),
+                Some(_) => {}
+            }
+        }
+        Ok(report)
+    }
+}
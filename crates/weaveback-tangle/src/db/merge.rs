@@ -39,6 +39,30 @@ impl WeavebackDb {
                 "INSERT OR IGNORE INTO target.files (path) SELECT path FROM files;"
             )?;
 
+            // Orphans pruned in this run lose their baselines and source maps;
+            // every pass seen in this run replaces its recorded output set.
+            self.conn.execute_batch("
+                DELETE FROM target.gen_baselines
+                 WHERE path IN (SELECT path FROM pruned_outputs);
+
+                DELETE FROM target.noweb_map
+                 WHERE out_file IN (
+                    SELECT t.id
+                      FROM target.files t
+                      JOIN pruned_outputs po ON po.out_file = t.path
+                 );
+
+                DELETE FROM target.pass_outputs
+                 WHERE pass_key IN (
+                    SELECT pass_key FROM pass_outputs
+                    UNION
+                    SELECT pass_key FROM pruned_outputs
+                 );
+
+                INSERT OR REPLACE INTO target.pass_outputs (pass_key, path, pass_name)
+                SELECT pass_key, path, pass_name FROM pass_outputs;
+            ")?;
+
             // Tables without file IDs: simple copy.
             self.conn.execute_batch(
                 "INSERT OR REPLACE INTO target.gen_baselines SELECT * FROM gen_baselines;
@@ -115,6 +139,34 @@ impl WeavebackDb {
                         ON t.path = ss.path
                         OR ss.path LIKE ('%/' || t.path)
                  );
+
+                DELETE FROM target.macro_calls
+                 WHERE src_file IN (
+                    SELECT t.id
+                      FROM target.files t
+                      JOIN src_snapshots ss
+                        ON t.path = ss.path
+                        OR ss.path LIKE ('%/' || t.path)
+                 );
+            ")?;
+
+            // Range tables: the run's map of a file replaces the target's.
+            self.conn.execute_batch("
+                DELETE FROM target.noweb_map
+                 WHERE out_file IN (
+                    SELECT t.id
+                      FROM target.files t
+                      JOIN files f ON f.path = t.path
+                     WHERE f.id IN (SELECT out_file FROM noweb_map)
+                 );
+
+                DELETE FROM target.macro_map
+                 WHERE driver_file IN (
+                    SELECT t.id
+                      FROM target.files t
+                      JOIN files f ON f.path = t.path
+                     WHERE f.id IN (SELECT driver_file FROM macro_map)
+                 );
             ")?;
 
             // Tables with file IDs: remap via path lookup in target.files.
@@ -123,10 +175,10 @@ impl WeavebackDb {
                 SELECT
                     (SELECT t.id FROM target.files t
                      WHERE t.path = (SELECT path FROM files WHERE id = nm.out_file)),
-                    nm.out_line,
+                    nm.out_start, nm.out_end,
                     (SELECT t.id FROM target.files t
                      WHERE t.path = (SELECT path FROM files WHERE id = nm.src_file)),
-                    nm.chunk_name, nm.src_line, nm.indent, nm.confidence
+                    nm.chunk_name, nm.src_start, nm.indent, nm.confidence
                 FROM noweb_map nm;
             ")?;
 
@@ -135,7 +187,7 @@ impl WeavebackDb {
                 SELECT
                     (SELECT t.id FROM target.files t
                      WHERE t.path = (SELECT path FROM files WHERE id = mm.driver_file)),
-                    mm.expanded_line, mm.data
+                    mm.expanded_start, mm.expanded_end, mm.data
                 FROM macro_map mm;
             ")?;
 
@@ -157,6 +209,15 @@ impl WeavebackDb {
                 FROM macro_defs md;
             ")?;
 
+            self.conn.execute_batch("
+                INSERT OR REPLACE INTO target.macro_calls
+                SELECT mc.macro_name,
+                    (SELECT t.id FROM target.files t
+                     WHERE t.path = (SELECT path FROM files WHERE id = mc.src_file)),
+                    mc.pos, mc.length
+                FROM macro_calls mc;
+            ")?;
+
             self.conn.execute_batch("
                 INSERT OR REPLACE INTO target.chunk_deps
                 SELECT cd.from_chunk, cd.to_chunk,
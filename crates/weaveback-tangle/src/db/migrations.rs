@@ -0,0 +1,278 @@
+// weaveback-tangle/src/db/migrations.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+
+/// Schema version written by this build: the `PRAGMA user_version` of an
+/// up-to-date database.
+pub const SCHEMA_VERSION: u32 = 6;
+
+/// One step from schema `version - 1` to `version`.
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    apply: fn(&Connection) -> Result<(), DbError>,
+}
+
+/// Every migration step, in order; step `n` is `MIGRATIONS[n - 1]`.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "store file paths as integer ids",
+        apply: drop_text_path_tables,
+    },
+    Migration {
+        version: 2,
+        description: "add the tags column to prose_fts",
+        apply: drop_prose_fts_without_tags,
+    },
+    Migration {
+        version: 3,
+        description: "store noweb_map and macro_map as line ranges",
+        apply: range_encode_source_maps,
+    },
+    Migration {
+        version: 4,
+        description: "store block embeddings as packed f32 blobs",
+        apply: pack_block_embeddings,
+    },
+    Migration {
+        version: 5,
+        description: "add the macro_calls table",
+        apply: create_macro_calls,
+    },
+    Migration {
+        version: 6,
+        description: "add the pass_name column to pass_outputs",
+        apply: add_pass_name,
+    },
+];
+
+/// The steps a database at `version` still needs.
+pub fn pending_migrations(version: u32) -> &'static [Migration] {
+    &MIGRATIONS[(version.min(SCHEMA_VERSION) as usize)..]
+}
+
+fn drop_text_path_tables(conn: &Connection) -> Result<(), DbError> {
+    let col_type: Option<String> = conn.query_row(
+        "SELECT type FROM pragma_table_info('noweb_map') WHERE name='out_file'",
+        [],
+        |row| row.get(0),
+    ).optional()?;
+    if col_type.as_deref() == Some("TEXT") {
+        conn.execute_batch("
+            DROP TABLE IF EXISTS noweb_map;
+            DROP TABLE IF EXISTS macro_map;
+            DROP TABLE IF EXISTS var_defs;
+            DROP TABLE IF EXISTS macro_defs;
+            DROP TABLE IF EXISTS chunk_deps;
+            DROP TABLE IF EXISTS chunk_defs;
+            DROP TABLE IF EXISTS literate_source_config;
+            DROP TABLE IF EXISTS source_blocks;
+        ")?;
+    }
+    Ok(())
+}
+
+fn drop_prose_fts_without_tags(conn: &Connection) -> Result<(), DbError> {
+    let tags: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('prose_fts') WHERE name='tags'",
+        [],
+        |row| row.get(0),
+    )?;
+    if tags == 0 {
+        conn.execute("DROP TABLE IF EXISTS prose_fts", [])?;
+    }
+    Ok(())
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool, DbError> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        params![table, column],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Convert per-line `noweb_map` and `macro_map` rows into ranges.  Lines
+/// `out_line - ROW_NUMBER()` apart within a partition form one run: the
+/// classic gaps-and-islands grouping.  For `noweb_map` the partition also
+/// includes `out_line - src_line`, so the source lines of a run are
+/// consecutive too.
+fn range_encode_source_maps(conn: &Connection) -> Result<(), DbError> {
+    if has_column(conn, "noweb_map", "out_line")? {
+        conn.execute_batch("
+            ALTER TABLE noweb_map RENAME TO noweb_map_lines;
+            DROP INDEX IF EXISTS idx_noweb_map_src;
+            CREATE TABLE noweb_map (
+                out_file   INTEGER NOT NULL REFERENCES files(id),
+                out_start  INTEGER NOT NULL,
+                out_end    INTEGER NOT NULL,
+                src_file   INTEGER NOT NULL REFERENCES files(id),
+                chunk_name TEXT    NOT NULL,
+                src_start  INTEGER NOT NULL,
+                indent     TEXT    NOT NULL,
+                confidence TEXT    NOT NULL DEFAULT 'exact',
+                PRIMARY KEY (out_file, out_start)
+            ) STRICT, WITHOUT ROWID;
+            INSERT INTO noweb_map
+            SELECT out_file, MIN(out_line), MAX(out_line), src_file, chunk_name,
+                   MIN(src_line), indent, confidence
+            FROM (
+                SELECT *, out_line - ROW_NUMBER() OVER (
+                    PARTITION BY out_file, src_file, chunk_name, indent, confidence,
+                                 out_line - src_line
+                    ORDER BY out_line
+                ) AS run
+                FROM noweb_map_lines
+            )
+            GROUP BY out_file, src_file, chunk_name, indent, confidence,
+                     out_line - src_line, run;
+            DROP TABLE noweb_map_lines;
+        ")?;
+    }
+    if has_column(conn, "macro_map", "expanded_line")? {
+        conn.execute_batch("
+            ALTER TABLE macro_map RENAME TO macro_map_lines;
+            CREATE TABLE macro_map (
+                driver_file    INTEGER NOT NULL REFERENCES files(id),
+                expanded_start INTEGER NOT NULL,
+                expanded_end   INTEGER NOT NULL,
+                data           BLOB    NOT NULL,
+                PRIMARY KEY (driver_file, expanded_start)
+            ) STRICT;
+            INSERT INTO macro_map
+            SELECT driver_file, MIN(expanded_line), MAX(expanded_line), data
+            FROM (
+                SELECT *, expanded_line - ROW_NUMBER() OVER (
+                    PARTITION BY driver_file, data
+                    ORDER BY expanded_line
+                ) AS run
+                FROM macro_map_lines
+            )
+            GROUP BY driver_file, data, run;
+            DROP TABLE macro_map_lines;
+        ")?;
+    }
+    Ok(())
+}
+
+/// Rewrite `block_embeddings.vector_json` as packed `vector` BLOBs.
+fn pack_block_embeddings(conn: &Connection) -> Result<(), DbError> {
+    if !has_column(conn, "block_embeddings", "vector_json")? {
+        return Ok(());
+    }
+    conn.execute_batch("
+        ALTER TABLE block_embeddings RENAME TO block_embeddings_json;
+        CREATE TABLE block_embeddings (
+            src_file     INTEGER NOT NULL REFERENCES files(id),
+            block_index  INTEGER NOT NULL,
+            content_hash BLOB    NOT NULL,
+            model        TEXT    NOT NULL,
+            vector       BLOB    NOT NULL,
+            list_id      INTEGER,
+            PRIMARY KEY (src_file, block_index)
+        ) STRICT, WITHOUT ROWID;
+    ")?;
+    {
+        let mut select = conn.prepare(
+            "SELECT src_file, block_index, content_hash, model, vector_json
+             FROM block_embeddings_json",
+        )?;
+        let mut insert = conn.prepare(
+            "INSERT INTO block_embeddings (src_file, block_index, content_hash, model, vector)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        let rows = select.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Vec<u8>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (src_file, block_index, content_hash, model, vector_json) = row?;
+            let Ok(vector) = serde_json::from_str::<Vec<f32>>(&vector_json) else {
+                continue;
+            };
+            insert.execute(params![src_file, block_index, content_hash, model, pack_vector(&vector)])?;
+        }
+    }
+    conn.execute("DROP TABLE block_embeddings_json", [])?;
+    Ok(())
+}
+
+fn create_macro_calls(conn: &Connection) -> Result<(), DbError> {
+    conn.execute_batch("
+        CREATE TABLE IF NOT EXISTS macro_calls (
+            macro_name TEXT    NOT NULL,
+            src_file   INTEGER NOT NULL REFERENCES files(id),
+            pos        INTEGER NOT NULL,
+            length     INTEGER NOT NULL,
+            PRIMARY KEY (macro_name, src_file, pos)
+        ) STRICT, WITHOUT ROWID;
+    ")?;
+    Ok(())
+}
+
+/// Add `pass_outputs.pass_name`.  Databases from before `pass_outputs`
+/// existed get the whole table, with the column, from the schema.
+fn add_pass_name(conn: &Connection) -> Result<(), DbError> {
+    if has_column(conn, "pass_outputs", "pass_key")?
+        && !has_column(conn, "pass_outputs", "pass_name")?
+    {
+        conn.execute("ALTER TABLE pass_outputs ADD COLUMN pass_name TEXT", [])?;
+    }
+    Ok(())
+}
+
+pub(in crate::db) fn user_version(conn: &Connection) -> Result<u32, DbError> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+fn set_user_version(conn: &Connection, version: u32) -> Result<(), DbError> {
+    conn.pragma_update(None, "user_version", version)?;
+    Ok(())
+}
+
+/// Refuse a database written by a newer weaveback.
+pub(in crate::db) fn check_not_newer(version: u32) -> Result<(), DbError> {
+    if version > SCHEMA_VERSION {
+        return Err(DbError::NewerSchema { found: version, supported: SCHEMA_VERSION });
+    }
+    Ok(())
+}
+
+/// Whether the database has no tables yet.
+pub(in crate::db) fn is_blank(conn: &Connection) -> Result<bool, DbError> {
+    let tables: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(tables == 0)
+}
+
+/// Create or migrate the schema; returns the steps that were applied.
+pub(in crate::db) fn migrate(conn: &Connection) -> Result<Vec<&'static Migration>, DbError> {
+    let version = user_version(conn)?;
+    check_not_newer(version)?;
+    if is_blank(conn)? {
+        conn.execute_batch(CREATE_SCHEMA)?;
+        set_user_version(conn, SCHEMA_VERSION)?;
+        return Ok(Vec::new());
+    }
+    let pending = pending_migrations(version);
+    for step in pending {
+        let tx = conn.unchecked_transaction()?;
+        (step.apply)(&tx)?;
+        set_user_version(&tx, step.version)?;
+        tx.commit()?;
+    }
+    conn.execute_batch(CREATE_SCHEMA)?;
+    Ok(pending.iter().collect())
+}
@@ -3,6 +3,91 @@
 
 use super::*;
 
+/// Group `(line, item)` pairs into runs `(first, last, item of first)`.
+/// A line joins the current run when it directly follows the run's last line
+/// and `continues(first_item, offset, item)` holds for its offset from
+/// `first`.  When a line appears more than once, the last pair wins.
+pub(in crate::db) fn line_runs<T>(
+    pairs: &[(u32, T)],
+    continues: impl Fn(&T, u32, &T) -> bool,
+) -> Vec<(u32, u32, &T)> {
+    let lines: std::collections::BTreeMap<u32, &T> =
+        pairs.iter().map(|(line, item)| (*line, item)).collect();
+    let mut runs: Vec<(u32, u32, &T)> = Vec::new();
+    for (line, item) in lines {
+        match runs.last_mut() {
+            Some((first, last, head))
+                if *last + 1 == line && continues(head, line - *first, item) =>
+            {
+                *last = line;
+            }
+            _ => runs.push((line, line, item)),
+        }
+    }
+    runs
+}
+
+/// Whether `entry` is the line `offset` lines below `head` in the same run.
+fn continues_noweb_run(head: &NowebMapEntry, offset: u32, entry: &NowebMapEntry) -> bool {
+    entry.src_line == head.src_line + offset
+        && entry.src_file == head.src_file
+        && entry.chunk_name == head.chunk_name
+        && entry.indent == head.indent
+        && entry.confidence == head.confidence
+}
+
+/// One `noweb_map` row read back with its source path.
+struct NowebRange {
+    out_start: u32,
+    out_end: u32,
+    /// The entry of `out_start`.
+    head: NowebMapEntry,
+}
+
+impl NowebRange {
+    /// Read `out_start, out_end, src path, chunk_name, src_start, indent,
+    /// confidence`.
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        Ok(NowebRange {
+            out_start: row.get(0)?,
+            out_end: row.get(1)?,
+            head: NowebMapEntry {
+                src_file: row.get(2)?,
+                chunk_name: row.get(3)?,
+                src_line: row.get(4)?,
+                indent: row.get(5)?,
+                confidence: row
+                    .get::<_, String>(6)
+                    .map(|s| Confidence::parse(&s))
+                    .unwrap_or_default(),
+            },
+        })
+    }
+
+    /// The entry of `out_line`, which must lie in the range.
+    fn entry_at(&self, out_line: u32) -> NowebMapEntry {
+        NowebMapEntry {
+            src_line: self.head.src_line + (out_line - self.out_start),
+            ..self.head.clone()
+        }
+    }
+
+    fn lines(&self) -> impl Iterator<Item = (u32, NowebMapEntry)> + '_ {
+        (self.out_start..=self.out_end).map(|line| (line, self.entry_at(line)))
+    }
+}
+
+/// Expand range rows back into one pair per line.
+fn expand_noweb_ranges(
+    rows: impl Iterator<Item = rusqlite::Result<NowebRange>>,
+) -> Result<Vec<(u32, NowebMapEntry)>, DbError> {
+    let mut lines = Vec::new();
+    for range in rows {
+        lines.extend(range?.lines());
+    }
+    Ok(lines)
+}
+
 impl WeavebackDb {
     pub fn set_noweb_entries(
         &mut self,
@@ -24,15 +109,17 @@ impl WeavebackDb {
         }
         let tx = self.conn.transaction()?;
         {
+            tx.execute("DELETE FROM noweb_map WHERE out_file = ?1", params![out_file_id])?;
             let mut stmt = tx.prepare_cached(
-                "INSERT OR REPLACE INTO noweb_map
-                 (out_file, out_line, src_file, chunk_name, src_line, indent, confidence)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT INTO noweb_map
+                 (out_file, out_start, out_end, src_file, chunk_name, src_start, indent, confidence)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             )?;
-            for (line, e) in entries {
+            for (out_start, out_end, e) in line_runs(entries, continues_noweb_run) {
                 stmt.execute(params![
                     out_file_id,
-                    *line,
+                    out_start,
+                    out_end,
                     src_ids[e.src_file.as_str()],
                     e.chunk_name,
                     e.src_line,
@@ -50,29 +137,27 @@ impl WeavebackDb {
         out_file: &str,
         out_line: u32,
     ) -> Result<Option<NowebMapEntry>, DbError> {
-        Ok(self
+        let range = self
             .conn
             .query_row(
-                "SELECT f_src.path, nm.chunk_name, nm.src_line, nm.indent, nm.confidence
-                 FROM noweb_map nm
-                 JOIN files f_out ON f_out.id = nm.out_file
-                 JOIN files f_src ON f_src.id = nm.src_file
-                 WHERE f_out.path = ?1 AND nm.out_line = ?2",
+                "WITH hit AS (
+                     SELECT nm.*
+                     FROM noweb_map nm
+                     JOIN files f_out ON f_out.id = nm.out_file
+                     WHERE f_out.path = ?1 AND nm.out_start <= ?2
+                     ORDER BY nm.out_start DESC
+                     LIMIT 1
+                 )
+                 SELECT hit.out_start, hit.out_end, f_src.path, hit.chunk_name,
+                        hit.src_start, hit.indent, hit.confidence
+                 FROM hit
+                 JOIN files f_src ON f_src.id = hit.src_file
+                 WHERE hit.out_end >= ?2",
                 params![out_file, out_line],
-                |row| {
-                    Ok(NowebMapEntry {
-                        src_file: row.get(0)?,
-                        chunk_name: row.get(1)?,
-                        src_line: row.get::<_, u32>(2)?,
-                        indent: row.get(3)?,
-                        confidence: row
-                            .get::<_, String>(4)
-                            .map(|s| Confidence::parse(&s))
-                            .unwrap_or_default(),
-                    })
-                },
+                NowebRange::from_row,
             )
-            .optional()?)
+            .optional()?;
+        Ok(range.map(|range| range.entry_at(out_line)))
     }
 
     pub fn get_noweb_entry_by_suffix(
@@ -81,31 +166,23 @@ impl WeavebackDb {
         out_line: u32,
     ) -> Result<Option<NowebMapEntry>, DbError> {
         let suffix_pattern = format!("%/{}", out_file_suffix.trim_start_matches("./"));
-        Ok(self
+        let range = self
             .conn
             .query_row(
-                "SELECT f_src.path, nm.chunk_name, nm.src_line, nm.indent, nm.confidence
+                "SELECT nm.out_start, nm.out_end, f_src.path, nm.chunk_name,
+                        nm.src_start, nm.indent, nm.confidence
                  FROM noweb_map nm
                  JOIN files f_out ON f_out.id = nm.out_file
                  JOIN files f_src ON f_src.id = nm.src_file
-                 WHERE (f_out.path = ?1 OR f_out.path LIKE ?2) AND nm.out_line = ?3
+                 WHERE (f_out.path = ?1 OR f_out.path LIKE ?2)
+                   AND nm.out_start <= ?3 AND nm.out_end >= ?3
                  ORDER BY length(f_out.path)
                  LIMIT 1",
                 params![out_file_suffix, suffix_pattern, out_line],
-                |row| {
-                    Ok(NowebMapEntry {
-                        src_file: row.get(0)?,
-                        chunk_name: row.get(1)?,
-                        src_line: row.get::<_, u32>(2)?,
-                        indent: row.get(3)?,
-                        confidence: row
-                            .get::<_, String>(4)
-                            .map(|s| Confidence::parse(&s))
-                            .unwrap_or_default(),
-                    })
-                },
+                NowebRange::from_row,
             )
-            .optional()?)
+            .optional()?;
+        Ok(range.map(|range| range.entry_at(out_line)))
     }
 
     pub fn get_noweb_entries_for_file_by_suffix(
@@ -121,28 +198,15 @@ impl WeavebackDb {
                  ORDER BY length(path)
                  LIMIT 1
              )
-             SELECT nm.out_line, f_src.path, nm.chunk_name, nm.src_line, nm.indent, nm.confidence
+             SELECT nm.out_start, nm.out_end, f_src.path, nm.chunk_name,
+                    nm.src_start, nm.indent, nm.confidence
              FROM noweb_map nm
              JOIN chosen c ON c.id = nm.out_file
              JOIN files f_src ON f_src.id = nm.src_file
-             ORDER BY nm.out_line",
+             ORDER BY nm.out_start",
         )?;
-        let rows = stmt.query_map(params![out_file_suffix, suffix_pattern], |row| {
-            Ok((
-                row.get::<_, u32>(0)?,
-                NowebMapEntry {
-                    src_file: row.get(1)?,
-                    chunk_name: row.get(2)?,
-                    src_line: row.get::<_, u32>(3)?,
-                    indent: row.get(4)?,
-                    confidence: row
-                        .get::<_, String>(5)
-                        .map(|s| Confidence::parse(&s))
-                        .unwrap_or_default(),
-                },
-            ))
-        })?;
-        rows.collect::<Result<Vec<_>, _>>().map_err(DbError::Sql)
+        let rows = stmt.query_map(params![out_file_suffix, suffix_pattern], NowebRange::from_row)?;
+        expand_noweb_ranges(rows)
     }
 
     pub fn get_noweb_entries_for_file(
@@ -150,28 +214,46 @@ impl WeavebackDb {
         out_file: &str,
     ) -> Result<Vec<(u32, NowebMapEntry)>, DbError> {
         let mut stmt = self.conn.prepare_cached(
-            "SELECT nm.out_line, f_src.path, nm.chunk_name, nm.src_line, nm.indent, nm.confidence
+            "SELECT nm.out_start, nm.out_end, f_src.path, nm.chunk_name,
+                    nm.src_start, nm.indent, nm.confidence
              FROM noweb_map nm
              JOIN files f_out ON f_out.id = nm.out_file
              JOIN files f_src ON f_src.id = nm.src_file
              WHERE f_out.path = ?1
-             ORDER BY nm.out_line",
+             ORDER BY nm.out_start",
+        )?;
+        let rows = stmt.query_map(params![out_file], NowebRange::from_row)?;
+        expand_noweb_ranges(rows)
+    }
+
+    /// Every range as `(out_file, out_start, out_end, entry of out_start)`,
+    /// in output path and line order.
+    pub fn list_noweb_ranges(&self) -> Result<Vec<(String, u32, u32, NowebMapEntry)>, DbError> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT f_out.path, nm.out_start, nm.out_end, f_src.path, nm.chunk_name,
+                    nm.src_start, nm.indent, nm.confidence
+             FROM noweb_map nm
+             JOIN files f_out ON f_out.id = nm.out_file
+             JOIN files f_src ON f_src.id = nm.src_file
+             ORDER BY f_out.path, nm.out_start",
         )?;
-        let rows = stmt.query_map(params![out_file], |row| {
+        let rows = stmt.query_map([], |row| {
             Ok((
-                row.get::<_, u32>(0)?,
+                row.get::<_, String>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, u32>(2)?,
                 NowebMapEntry {
-                    src_file: row.get(1)?,
-                    chunk_name: row.get(2)?,
-                    src_line: row.get::<_, u32>(3)?,
-                    indent: row.get(4)?,
+                    src_file: row.get(3)?,
+                    chunk_name: row.get(4)?,
+                    src_line: row.get(5)?,
+                    indent: row.get(6)?,
                     confidence: row
-                        .get::<_, String>(5)
+                        .get::<_, String>(7)
                         .map(|s| Confidence::parse(&s))
                         .unwrap_or_default(),
                 },
             ))
         })?;
-        rows.collect::<Result<Vec<_>, _>>().map_err(DbError::Sql)
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
 }
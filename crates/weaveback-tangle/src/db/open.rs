@@ -17,72 +17,93 @@ pub(in crate::db) fn intern_file(conn: &Connection, path: &str) -> Result<i64, D
     )?)
 }
 
-/// Detect whether the db uses the pre-file-ID schema (noweb_map.out_file is TEXT).
-fn needs_file_id_migration(conn: &Connection) -> Result<bool, DbError> {
-    let col_type: Option<String> = conn.query_row(
-        "SELECT type FROM pragma_table_info('noweb_map') WHERE name='out_file'",
-        [],
-        |row| row.get(0),
-    ).optional()?;
-    Ok(col_type.as_deref() == Some("TEXT"))
-}
-
-/// Detect whether `prose_fts` is missing the `tags` column (pre-0.9.2 schema).
-fn needs_prose_fts_tags_migration(conn: &Connection) -> Result<bool, DbError> {
-    let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM pragma_table_info('prose_fts') WHERE name='tags'",
-        [],
-        |row| row.get(0),
-    ).unwrap_or(0);
-    Ok(count == 0)
-}
-
 pub(in crate::db) fn apply_schema(conn: &Connection) -> Result<(), DbError> {
-    // If the db was created with the old TEXT-based file columns, drop those
-    // tables so CREATE_SCHEMA recreates them with integer IDs.  The db is a
-    // disposable build artefact; gen_baselines and src_snapshots (which store
-    // the per-file baselines and source snapshots) are left intact.
-    if needs_file_id_migration(conn)? {
-        conn.execute_batch("
-            DROP TABLE IF EXISTS noweb_map;
-            DROP TABLE IF EXISTS macro_map;
-            DROP TABLE IF EXISTS var_defs;
-            DROP TABLE IF EXISTS macro_defs;
-            DROP TABLE IF EXISTS chunk_deps;
-            DROP TABLE IF EXISTS chunk_defs;
-            DROP TABLE IF EXISTS literate_source_config;
-            DROP TABLE IF EXISTS source_blocks;
-        ")?;
-    }
-
-    // FTS5 virtual tables cannot be altered; drop and recreate when the schema
-    // gains a new column.  rebuild_prose_fts always repopulates from source.
-    if needs_prose_fts_tags_migration(conn)? {
-        conn.execute("DROP TABLE IF EXISTS prose_fts", [])?;
-    }
-
-    conn.execute_batch(CREATE_SCHEMA).map_err(DbError::Sql)?;
+    migrate(conn)?;
     Ok(())
 }
 
 impl WeavebackDb {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DbError> {
+        Ok(Self::open_migrated(path)?.0)
+    }
+
+    /// `open`, also returning the migration steps that were applied.
+    pub fn open_migrated<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<&'static Migration>), DbError> {
         let conn = Connection::open(path)?;
         conn.pragma_update(None, "journal_mode", "WAL")?;
         conn.pragma_update(None, "synchronous", "NORMAL")?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
-        apply_schema(&conn)?;
-        Ok(Self { conn })
+        let applied = migrate(&conn)?;
+        Ok((Self { conn }, applied))
     }
 
+    /// Open for reading.  The schema must be current: a database that still
+    /// needs migrating is an `OlderSchema` error rather than failing later
+    /// on a missing table or column.
     pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self, DbError> {
+        let db = Self::open_read_only_unmigrated(path)?;
+        let version = db.schema_version()?;
+        if version < SCHEMA_VERSION && !is_blank(&db.conn)? {
+            return Err(DbError::OlderSchema { found: version, expected: SCHEMA_VERSION });
+        }
+        Ok(db)
+    }
+
+    /// `open_read_only` for inspecting a database that may still need
+    /// migrating; only a newer schema is refused.
+    pub fn open_read_only_unmigrated<P: AsRef<Path>>(path: P) -> Result<Self, DbError> {
         let conn = Connection::open_with_flags(
             path,
             OpenFlags::SQLITE_OPEN_READ_ONLY,
         )?;
+        check_not_newer(user_version(&conn)?)?;
         Ok(Self { conn })
     }
 
+    /// The migration steps `open` would apply to the database at `path`,
+    /// without changing it.  A missing database needs none.
+    pub fn pending_migrations_at<P: AsRef<Path>>(path: P) -> Result<&'static [Migration], DbError> {
+        if !path.as_ref().exists() {
+            return Ok(&[]);
+        }
+        let db = Self::open_read_only_unmigrated(path)?;
+        if is_blank(&db.conn)? {
+            return Ok(&[]);
+        }
+        Ok(pending_migrations(db.schema_version()?))
+    }
+
+    /// The database's `PRAGMA user_version`.
+    pub fn schema_version(&self) -> Result<u32, DbError> {
+        user_version(&self.conn)
+    }
+
+    /// Row count of every table, by name, skipping SQLite's internal tables
+    /// and the shadow tables behind FTS indexes.
+    pub fn table_row_counts(&self) -> Result<Vec<(String, i64)>, DbError> {
+        let names: Vec<String> = self
+            .conn
+            .prepare(
+                "SELECT name FROM pragma_table_list
+                 WHERE schema = 'main' AND type IN ('table', 'virtual')
+                   AND name NOT LIKE 'sqlite_%'
+                 ORDER BY name",
+            )?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        names
+            .into_iter()
+            .map(|name| {
+                let count = self.conn.query_row(
+                    &format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\"")),
+                    [],
+                    |row| row.get(0),
+                )?;
+                Ok((name, count))
+            })
+            .collect()
+    }
+
     pub fn open_temp() -> Result<Self, DbError> {
         let conn = Connection::open_in_memory()?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
@@ -14,20 +14,22 @@ CREATE TABLE IF NOT EXISTS gen_baselines (
 
 CREATE TABLE IF NOT EXISTS noweb_map (
     out_file   INTEGER NOT NULL REFERENCES files(id),
-    out_line   INTEGER NOT NULL,
+    out_start  INTEGER NOT NULL,
+    out_end    INTEGER NOT NULL,
     src_file   INTEGER NOT NULL REFERENCES files(id),
     chunk_name TEXT    NOT NULL,
-    src_line   INTEGER NOT NULL,
+    src_start  INTEGER NOT NULL,
     indent     TEXT    NOT NULL,
     confidence TEXT    NOT NULL DEFAULT 'exact',
-    PRIMARY KEY (out_file, out_line)
+    PRIMARY KEY (out_file, out_start)
 ) STRICT, WITHOUT ROWID;
 
 CREATE TABLE IF NOT EXISTS macro_map (
-    driver_file   INTEGER NOT NULL REFERENCES files(id),
-    expanded_line INTEGER NOT NULL,
-    data          BLOB    NOT NULL,
-    PRIMARY KEY (driver_file, expanded_line)
+    driver_file    INTEGER NOT NULL REFERENCES files(id),
+    expanded_start INTEGER NOT NULL,
+    expanded_end   INTEGER NOT NULL,
+    data           BLOB    NOT NULL,
+    PRIMARY KEY (driver_file, expanded_start)
 ) STRICT;
 
 CREATE TABLE IF NOT EXISTS src_snapshots (
@@ -51,6 +53,14 @@ CREATE TABLE IF NOT EXISTS macro_defs (
     PRIMARY KEY (macro_name, src_file, pos)
 ) STRICT, WITHOUT ROWID;
 
+CREATE TABLE IF NOT EXISTS macro_calls (
+    macro_name TEXT    NOT NULL,
+    src_file   INTEGER NOT NULL REFERENCES files(id),
+    pos        INTEGER NOT NULL,
+    length     INTEGER NOT NULL,
+    PRIMARY KEY (macro_name, src_file, pos)
+) STRICT, WITHOUT ROWID;
+
 CREATE TABLE IF NOT EXISTS chunk_deps (
     from_chunk TEXT    NOT NULL,
     to_chunk   TEXT    NOT NULL,
@@ -105,12 +115,39 @@ CREATE TABLE IF NOT EXISTS block_embeddings (
     block_index  INTEGER NOT NULL,
     content_hash BLOB    NOT NULL,
     model        TEXT    NOT NULL,
-    vector_json  TEXT    NOT NULL,
+    vector       BLOB    NOT NULL,
+    list_id      INTEGER,
     PRIMARY KEY (src_file, block_index)
 ) STRICT, WITHOUT ROWID;
 
+CREATE TABLE IF NOT EXISTS embedding_lists (
+    id       INTEGER PRIMARY KEY,
+    model    TEXT NOT NULL,
+    centroid BLOB NOT NULL
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS embedding_index (
+    model   TEXT    PRIMARY KEY,
+    vectors INTEGER NOT NULL
+) STRICT;
+
+CREATE TABLE IF NOT EXISTS pass_outputs (
+    pass_key  TEXT NOT NULL,
+    path      TEXT NOT NULL,
+    pass_name TEXT,
+    PRIMARY KEY (pass_key, path)
+) STRICT, WITHOUT ROWID;
+
+CREATE TABLE IF NOT EXISTS pruned_outputs (
+    pass_key TEXT NOT NULL,
+    path     TEXT NOT NULL,
+    out_file TEXT NOT NULL,
+    PRIMARY KEY (pass_key, path)
+) STRICT, WITHOUT ROWID;
+
 CREATE INDEX IF NOT EXISTS idx_chunk_deps_to ON chunk_deps(to_chunk);
-CREATE INDEX IF NOT EXISTS idx_noweb_map_src ON noweb_map(src_file, src_line);
+CREATE INDEX IF NOT EXISTS idx_noweb_map_src ON noweb_map(src_file, src_start);
+CREATE INDEX IF NOT EXISTS idx_block_embeddings_list ON block_embeddings(list_id);
 
 CREATE VIRTUAL TABLE IF NOT EXISTS prose_fts USING fts5(
     content,
@@ -121,4 +158,15 @@ CREATE VIRTUAL TABLE IF NOT EXISTS prose_fts USING fts5(
     line_end   UNINDEXED,
     tokenize  = 'porter unicode61'
 );
+
+CREATE VIRTUAL TABLE IF NOT EXISTS code_fts USING fts5(
+    content,
+    terms,
+    src_file   UNINDEXED,
+    chunk_name UNINDEXED,
+    nth        UNINDEXED,
+    line_start UNINDEXED,
+    line_end   UNINDEXED,
+    tokenize  = \"unicode61 tokenchars '_'\"
+);
 ";
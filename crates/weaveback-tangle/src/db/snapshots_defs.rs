@@ -100,4 +100,40 @@ impl WeavebackDb {
         Ok(rows.collect::<Result<Vec<_>, _>>()?
         )
     }
+
+    /// Every `%def` site as `(macro_name, src_file, pos)`, by name then file.
+    pub fn list_macro_defs(&self) -> Result<Vec<(String, String, u32)>, DbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT md.macro_name, f.path, md.pos
+             FROM macro_defs md JOIN files f ON f.id = md.src_file
+             ORDER BY md.macro_name, f.path, md.pos",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub fn record_macro_call(
+        &self,
+        macro_name: &str,
+        src_file: &str,
+        pos: u32,
+        length: u32,
+    ) -> Result<(), DbError> {
+        let file_id = intern_file(&self.conn, src_file)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO macro_calls (macro_name, src_file, pos, length)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![macro_name, file_id, pos, length],
+        )?;
+        Ok(())
+    }
+
+    /// Names of the macros with at least one recorded call, sorted.
+    pub fn called_macros(&self) -> Result<Vec<String>, DbError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT macro_name FROM macro_calls ORDER BY macro_name")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
 }
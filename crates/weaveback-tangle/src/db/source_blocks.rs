@@ -76,4 +76,26 @@ impl WeavebackDb {
         })?;
         Ok(rows.collect::<Result<Vec<_>, _>>()?)
     }
+
+    /// Every stored block with its source path, in path and line order.
+    pub fn list_source_blocks(&self) -> Result<Vec<(String, StoredBlockInfo)>, DbError> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT f.path, sb.block_index, sb.block_type, sb.line_start, sb.line_end, sb.content_hash
+             FROM source_blocks sb JOIN files f ON f.id = sb.src_file
+             ORDER BY f.path, sb.line_start",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                StoredBlockInfo {
+                    block_index:  row.get::<_, u32>(1)?,
+                    block_type:   row.get(2)?,
+                    line_start:   row.get::<_, u32>(3)?,
+                    line_end:     row.get::<_, u32>(4)?,
+                    content_hash: row.get::<_, Vec<u8>>(5)?,
+                },
+            ))
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
 }
@@ -3,4 +3,8 @@
 
 use super::*;
 
+mod bundle;
+mod maintenance;
 mod merge;
+mod migrations;
+mod search;
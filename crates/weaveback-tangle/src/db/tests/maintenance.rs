@@ -0,0 +1,119 @@
+// weaveback-tangle/src/db/tests/maintenance.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+use std::collections::HashMap;
+
+fn entry(src_file: &str, src_line: u32) -> NowebMapEntry {
+    NowebMapEntry {
+        src_file: src_file.to_string(),
+        chunk_name: "@file out.rs".to_string(),
+        src_line,
+        indent: String::new(),
+        confidence: Confidence::Exact,
+    }
+}
+
+/// Two sources, each tangled into one output, and a third baseline that no
+/// pass produces any more.
+fn project_db() -> WeavebackDb {
+    let mut db = WeavebackDb::open_temp().unwrap();
+    for (src, out) in [("src/kept.wvb", "gen/kept.rs"), ("src/gone.wvb", "gen/gone.rs")] {
+        db.set_noweb_entries(out, &[(0, entry(src, 2)), (1, entry(src, 3))]).unwrap();
+        db.set_src_snapshot(src, b"a\nb\nc\nd\n").unwrap();
+        db.set_chunk_defs(&[ChunkDefEntry {
+            src_file: src.to_string(),
+            chunk_name: "@file out.rs".to_string(),
+            nth: 0,
+            def_start: 2,
+            def_end: 4,
+        }])
+        .unwrap();
+    }
+    db.set_baseline("kept.rs", b"c\nd\n").unwrap();
+    db.set_baseline("gone.rs", b"c\nd\n").unwrap();
+    db.set_baseline("old.rs", b"x\n").unwrap();
+    db.set_pass_outputs("pass:main", None, &["kept.rs".to_string(), "gone.rs".to_string()])
+        .unwrap();
+    db
+}
+
+fn disk(files: &[(PathRole, &str, &str)]) -> HashMap<(bool, String), Vec<u8>> {
+    files
+        .iter()
+        .map(|(role, path, text)| ((*role == PathRole::Baseline, path.to_string()), text.as_bytes().to_vec()))
+        .collect()
+}
+
+#[test]
+fn gc_removes_rows_of_deleted_and_unproduced_files() {
+    let on_disk = disk(&[
+        (PathRole::Source, "src/kept.wvb", ""),
+        (PathRole::Output, "gen/kept.rs", ""),
+        (PathRole::Baseline, "kept.rs", ""),
+        (PathRole::Baseline, "old.rs", ""),
+    ]);
+    let exists = |role: PathRole, path: &str| {
+        on_disk.contains_key(&(role == PathRole::Baseline, path.to_string()))
+    };
+    let mut db = project_db();
+
+    let preview = db.gc(&exists, true).unwrap();
+    assert!(preview.dry_run);
+    assert_eq!(preview.missing_sources, ["src/gone.wvb"]);
+    assert_eq!(db.list_baselines().unwrap().len(), 3, "dry run must not delete");
+
+    let report = db.gc(&exists, false).unwrap();
+    assert_eq!(report.missing_sources, ["src/gone.wvb"]);
+    assert_eq!(report.missing_outputs, ["gen/gone.rs"]);
+    assert_eq!(report.stale_baselines, ["gone.rs", "old.rs"]);
+    assert_eq!(report.deleted_rows["noweb_map"], 1);
+    assert_eq!(report.deleted_rows["files"], 2);
+    assert_eq!(report, GcReport { dry_run: false, ..preview });
+
+    assert!(db.get_noweb_entry("gen/gone.rs", 0).unwrap().is_none());
+    assert!(db.get_noweb_entry("gen/kept.rs", 1).unwrap().is_some());
+    assert!(db.get_src_snapshot("src/gone.wvb").unwrap().is_none());
+    assert_eq!(db.list_baselines().unwrap().len(), 1);
+    assert!(db.gc(&exists, false).unwrap().deleted_rows.is_empty());
+}
+
+#[test]
+fn check_reports_stale_ranges_and_baselines() {
+    let db = project_db();
+    let clean = disk(&[
+        (PathRole::Source, "src/kept.wvb", "a\nb\nc\nd\n"),
+        (PathRole::Source, "src/gone.wvb", "a\nb\nc\nd\n"),
+        (PathRole::Output, "gen/kept.rs", "c\nd\n"),
+        (PathRole::Output, "gen/gone.rs", "c\nd\n"),
+        (PathRole::Baseline, "kept.rs", "c\nd\n"),
+        (PathRole::Baseline, "gone.rs", "c\nd\n"),
+        (PathRole::Baseline, "old.rs", "x\n"),
+    ]);
+    let read = |files: &HashMap<(bool, String), Vec<u8>>, role: PathRole, path: &str| {
+        files.get(&(role == PathRole::Baseline, path.to_string())).cloned()
+    };
+    assert!(db.check(&|role, path| read(&clean, role, path)).unwrap().is_ok());
+
+    let mut stale = clean.clone();
+    stale.insert((false, "src/kept.wvb".to_string()), b"a\nb\n".to_vec());
+    stale.remove(&(false, "gen/gone.rs".to_string()));
+    stale.insert((true, "kept.rs".to_string()), b"edited\n".to_vec());
+    stale.remove(&(true, "old.rs".to_string()));
+    let report = db.check(&|role, path| read(&stale, role, path)).unwrap();
+    let found: Vec<(&str, &str, &str)> = report
+        .issues
+        .iter()
+        .map(|issue| (issue.kind, issue.table.as_str(), issue.path.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("line_out_of_range", "noweb_map", "src/kept.wvb"),
+            ("line_out_of_range", "chunk_defs", "src/kept.wvb"),
+            ("missing_output", "noweb_map", "gen/gone.rs"),
+            ("baseline_modified", "gen_baselines", "kept.rs"),
+            ("baseline_missing", "gen_baselines", "old.rs"),
+        ]
+    );
+}
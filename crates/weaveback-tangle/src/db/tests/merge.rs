@@ -70,3 +70,59 @@ fn merge_into_replaces_stale_noweb_rows_for_touched_sources() {
         ]
     );
 }
+
+#[test]
+fn merge_into_drops_rows_of_pruned_outputs() {
+    let temp = TempDir::new().unwrap();
+    let target_path = temp.path().join("target.db");
+    let entry = NowebMapEntry {
+        src_file: "src/doc.adoc".into(),
+        chunk_name: "@file old.rs".into(),
+        src_line: 3,
+        indent: String::new(),
+        confidence: Confidence::Exact,
+    };
+
+    let mut target = WeavebackDb::open(&target_path).unwrap();
+    target.set_baseline("old.rs", b"old").unwrap();
+    target.set_baseline("kept.rs", b"kept").unwrap();
+    target.set_noweb_entries("/gen/old.rs", &[(0, entry.clone())]).unwrap();
+    target.set_noweb_entries("/gen/kept.rs", &[(0, entry)]).unwrap();
+    target
+        .set_pass_outputs("pass", None, &["kept.rs".to_string(), "old.rs".to_string()])
+        .unwrap();
+    drop(target);
+
+    let mut fresh = WeavebackDb::open_temp().unwrap();
+    fresh.set_pass_outputs("pass", None, &["kept.rs".to_string()]).unwrap();
+    fresh.record_pruned_output("pass", "old.rs", "/gen/old.rs").unwrap();
+    fresh.merge_into(&target_path).unwrap();
+
+    let merged = WeavebackDb::open_read_only(&target_path).unwrap();
+    assert!(merged.get_baseline("old.rs").unwrap().is_none());
+    assert!(merged.get_baseline("kept.rs").unwrap().is_some());
+    assert!(merged.get_noweb_entry("/gen/old.rs", 0).unwrap().is_none());
+    assert!(merged.get_noweb_entry("/gen/kept.rs", 0).unwrap().is_some());
+    assert_eq!(merged.get_pass_outputs("pass").unwrap(), vec!["kept.rs".to_string()]);
+    assert!(merged.list_pruned_outputs().unwrap().is_empty());
+}
+
+#[test]
+fn merge_into_clears_pass_whose_outputs_were_all_pruned() {
+    let temp = TempDir::new().unwrap();
+    let target_path = temp.path().join("target.db");
+
+    let mut target = WeavebackDb::open(&target_path).unwrap();
+    target.set_pass_outputs("pass", None, &["gone.rs".to_string()]).unwrap();
+    target.set_pass_outputs("other", None, &["other.rs".to_string()]).unwrap();
+    drop(target);
+
+    let mut fresh = WeavebackDb::open_temp().unwrap();
+    fresh.set_pass_outputs("pass", None, &[]).unwrap();
+    fresh.record_pruned_output("pass", "gone.rs", "/gen/gone.rs").unwrap();
+    fresh.merge_into(&target_path).unwrap();
+
+    let merged = WeavebackDb::open_read_only(&target_path).unwrap();
+    assert!(merged.get_pass_outputs("pass").unwrap().is_empty());
+    assert_eq!(merged.get_pass_outputs("other").unwrap(), vec!["other.rs".to_string()]);
+}
@@ -0,0 +1,213 @@
+// weaveback-tangle/src/db/tests/migrations.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+use tempfile::TempDir;
+
+fn legacy_db(path: &Path) {
+    let conn = Connection::open(path).unwrap();
+    conn.execute_batch("
+        CREATE TABLE gen_baselines (path TEXT PRIMARY KEY NOT NULL, content BLOB NOT NULL);
+        INSERT INTO gen_baselines VALUES ('out.rs', x'6869');
+        CREATE TABLE noweb_map (out_file TEXT, out_line INTEGER, src_file TEXT,
+                                chunk_name TEXT, src_line INTEGER, indent TEXT);
+        CREATE VIRTUAL TABLE prose_fts USING fts5(content, src_file UNINDEXED);
+    ").unwrap();
+}
+
+#[test]
+fn migrations_are_numbered_in_order() {
+    assert_eq!(MIGRATIONS.len() as u32, SCHEMA_VERSION);
+    for (i, step) in MIGRATIONS.iter().enumerate() {
+        assert_eq!(step.version, i as u32 + 1);
+    }
+    assert_eq!(pending_migrations(0).len(), MIGRATIONS.len());
+    assert!(pending_migrations(SCHEMA_VERSION).is_empty());
+}
+
+#[test]
+fn new_databases_start_at_the_current_version() {
+    let temp = TempDir::new().unwrap();
+    let (db, applied) = WeavebackDb::open_migrated(temp.path().join("new.db")).unwrap();
+    assert!(applied.is_empty());
+    assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+    assert_eq!(WeavebackDb::open_temp().unwrap().schema_version().unwrap(), SCHEMA_VERSION);
+}
+
+#[test]
+fn unversioned_databases_are_migrated_and_keep_baselines() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("old.db");
+    legacy_db(&path);
+
+    assert_eq!(WeavebackDb::pending_migrations_at(&path).unwrap().len(), 6);
+    let (db, applied) = WeavebackDb::open_migrated(&path).unwrap();
+    let versions: Vec<u32> = applied.iter().map(|m| m.version).collect();
+    assert_eq!(versions, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+    assert_eq!(db.get_baseline("out.rs").unwrap().as_deref(), Some(&b"hi"[..]));
+    assert!(db.get_noweb_entries_for_file("out.rs").unwrap().is_empty());
+    drop(db);
+    assert!(WeavebackDb::pending_migrations_at(&path).unwrap().is_empty());
+}
+
+#[test]
+fn per_line_source_maps_are_converted_to_ranges() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("v2.db");
+    drop(WeavebackDb::open(&path).unwrap());
+    let conn = Connection::open(&path).unwrap();
+    conn.execute_batch("
+        DROP TABLE noweb_map;
+        DROP TABLE macro_map;
+        CREATE TABLE noweb_map (
+            out_file INTEGER NOT NULL, out_line INTEGER NOT NULL,
+            src_file INTEGER NOT NULL, chunk_name TEXT NOT NULL,
+            src_line INTEGER NOT NULL, indent TEXT NOT NULL,
+            confidence TEXT NOT NULL DEFAULT 'exact',
+            PRIMARY KEY (out_file, out_line)
+        ) STRICT, WITHOUT ROWID;
+        CREATE INDEX idx_noweb_map_src ON noweb_map(src_file, src_line);
+        CREATE TABLE macro_map (
+            driver_file INTEGER NOT NULL, expanded_line INTEGER NOT NULL,
+            data BLOB NOT NULL, PRIMARY KEY (driver_file, expanded_line)
+        ) STRICT;
+        INSERT INTO files (id, path) VALUES (1, 'out.rs'), (2, 'src.adoc');
+        -- main: lines 0-2 from 10-12, a reference to helper at 3-4, main resumes at 5.
+        INSERT INTO noweb_map VALUES
+            (1, 0, 2, 'main', 10, '', 'exact'),
+            (1, 1, 2, 'main', 11, '', 'exact'),
+            (1, 2, 2, 'main', 12, '', 'exact'),
+            (1, 3, 2, 'helper', 20, '    ', 'exact'),
+            (1, 4, 2, 'helper', 21, '    ', 'exact'),
+            (1, 5, 2, 'main', 14, '', 'exact');
+        INSERT INTO macro_map VALUES (2, 0, x'01'), (2, 1, x'01'), (2, 2, x'02');
+        PRAGMA user_version = 2;
+    ").unwrap();
+    drop(conn);
+
+    let (db, applied) = WeavebackDb::open_migrated(&path).unwrap();
+    assert_eq!(applied.iter().map(|m| m.version).collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+    let counts: std::collections::HashMap<String, i64> =
+        db.table_row_counts().unwrap().into_iter().collect();
+    assert_eq!((counts["noweb_map"], counts["macro_map"]), (3, 2));
+
+    let lines = db.get_noweb_entries_for_file("out.rs").unwrap();
+    let summary: Vec<(u32, &str, u32)> = lines
+        .iter()
+        .map(|(line, e)| (*line, e.chunk_name.as_str(), e.src_line))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (0, "main", 10), (1, "main", 11), (2, "main", 12),
+            (3, "helper", 20), (4, "helper", 21), (5, "main", 14),
+        ]
+    );
+    assert_eq!(db.get_noweb_entry("out.rs", 4).unwrap().unwrap().indent, "    ");
+    assert_eq!(db.get_output_location("src.adoc", 11).unwrap(), Some(("out.rs".to_string(), 1)));
+    assert_eq!(db.get_macro_map_bytes("src.adoc", 1).unwrap(), Some(vec![1]));
+    assert_eq!(db.get_macro_map_bytes("src.adoc", 2).unwrap(), Some(vec![2]));
+    assert_eq!(db.get_macro_map_bytes("src.adoc", 3).unwrap(), None);
+}
+
+#[test]
+fn json_embeddings_are_packed() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("v3.db");
+    drop(WeavebackDb::open(&path).unwrap());
+    let conn = Connection::open(&path).unwrap();
+    conn.execute_batch("
+        DROP TABLE block_embeddings;
+        CREATE TABLE block_embeddings (
+            src_file INTEGER NOT NULL, block_index INTEGER NOT NULL,
+            content_hash BLOB NOT NULL, model TEXT NOT NULL, vector_json TEXT NOT NULL,
+            PRIMARY KEY (src_file, block_index)
+        ) STRICT, WITHOUT ROWID;
+        INSERT INTO files (id, path) VALUES (1, 'a.adoc');
+        INSERT INTO block_embeddings VALUES
+            (1, 0, x'00', 'm', '[0.5,-1.0,2.25]'),
+            (1, 1, x'00', 'm', 'not json');
+        PRAGMA user_version = 3;
+    ").unwrap();
+    drop(conn);
+
+    let (db, applied) = WeavebackDb::open_migrated(&path).unwrap();
+    assert_eq!(applied.iter().map(|m| m.version).collect::<Vec<_>>(), vec![4, 5, 6]);
+    let vectors: Vec<(u32, Vec<u8>)> = db
+        .conn
+        .prepare("SELECT block_index, vector FROM block_embeddings")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(vectors, vec![(0, pack_vector(&[0.5, -1.0, 2.25]))]);
+}
+
+#[test]
+fn pass_outputs_gain_a_pass_name() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("v5.db");
+    drop(WeavebackDb::open(&path).unwrap());
+    let conn = Connection::open(&path).unwrap();
+    conn.execute_batch("
+        DROP TABLE pass_outputs;
+        CREATE TABLE pass_outputs (
+            pass_key TEXT NOT NULL, path TEXT NOT NULL,
+            PRIMARY KEY (pass_key, path)
+        );
+        INSERT INTO pass_outputs VALUES ('src', 'main.rs');
+        PRAGMA user_version = 5;
+    ").unwrap();
+    drop(conn);
+
+    let (mut db, applied) = WeavebackDb::open_migrated(&path).unwrap();
+    assert_eq!(applied.iter().map(|m| m.version).collect::<Vec<_>>(), vec![6]);
+    assert_eq!(db.get_pass_outputs("src").unwrap(), vec!["main.rs".to_string()]);
+    assert_eq!(db.get_pass_name("src").unwrap(), None);
+
+    db.set_pass_outputs("src", Some("core"), &["main.rs".to_string()]).unwrap();
+    assert_eq!(db.get_pass_name("src").unwrap().as_deref(), Some("core"));
+}
+
+#[test]
+fn older_databases_are_refused_read_only_until_migrated() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("v1.db");
+    drop(WeavebackDb::open(&path).unwrap());
+    Connection::open(&path).unwrap().pragma_update(None, "user_version", 1).unwrap();
+
+    match WeavebackDb::open_read_only(&path).err() {
+        Some(err @ DbError::OlderSchema { found: 1, expected: SCHEMA_VERSION }) => {
+            assert!(err.to_string().contains("run `wb-query db migrate`"), "{err}");
+        }
+        other => panic!("expected OlderSchema, got {other:?}"),
+    }
+    let db = WeavebackDb::open_read_only_unmigrated(&path).unwrap();
+    assert_eq!(db.schema_version().unwrap(), 1);
+    drop(db);
+
+    drop(WeavebackDb::open(&path).unwrap());
+    assert_eq!(WeavebackDb::open_read_only(&path).unwrap().schema_version().unwrap(), SCHEMA_VERSION);
+}
+
+#[test]
+fn newer_databases_are_refused() {
+    let temp = TempDir::new().unwrap();
+    let path = temp.path().join("future.db");
+    drop(WeavebackDb::open(&path).unwrap());
+    Connection::open(&path)
+        .unwrap()
+        .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+        .unwrap();
+
+    for result in [WeavebackDb::open(&path).err(), WeavebackDb::open_read_only(&path).err()] {
+        match result {
+            Some(DbError::NewerSchema { found, supported }) => {
+                assert_eq!((found, supported), (SCHEMA_VERSION + 1, SCHEMA_VERSION));
+            }
+            other => panic!("expected NewerSchema, got {other:?}"),
+        }
+    }
+}
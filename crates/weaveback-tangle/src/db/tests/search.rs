@@ -0,0 +1,26 @@
+// weaveback-tangle/src/db/tests/search.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+
+fn query_plan(db: &WeavebackDb, sql: &str) -> Vec<String> {
+    db.conn
+        .prepare(&format!("EXPLAIN QUERY PLAN {sql}"))
+        .unwrap()
+        .query_map(params!["[1,2]"], |row| row.get::<_, String>(3))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn probed_semantic_search_reads_embeddings_through_the_list_index() {
+    let db = WeavebackDb::open_temp().unwrap();
+    let plan = query_plan(&db, WeavebackDb::PROBED_SEMANTIC_SQL);
+    let reads: Vec<&String> = plan.iter().filter(|step| step.contains("block_embeddings")).collect();
+    assert_eq!(reads.len(), 2, "{plan:?}");
+    assert!(
+        reads.iter().all(|step| step.contains("USING INDEX idx_block_embeddings_list")),
+        "{plan:?}"
+    );
+}
@@ -9,6 +9,14 @@ pub enum DbError {
     Sql(#[from] rusqlite::Error),
     #[error("json error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("database schema version {found} is newer than this weaveback supports ({supported}); upgrade weaveback")]
+    NewerSchema { found: u32, supported: u32 },
+    #[error("database schema version {found} is older than this weaveback expects ({expected}); run `wb-query db migrate`")]
+    OlderSchema { found: u32, expected: u32 },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid bundle: {0}")]
+    Bundle(String),
 }
 
 /// How reliably a post-formatter output line was traced back to its source.
@@ -0,0 +1,84 @@
+// weaveback-tangle/src/front_matter/tests.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+
+fn base() -> TangleConfig {
+    TangleConfig {
+        sigil: '%',
+        open_delim: "<[".to_string(),
+        close_delim: "]>".to_string(),
+        chunk_end: "@@".to_string(),
+        comment_markers: vec!["//".to_string()],
+    }
+}
+
+#[test]
+fn yaml_block_overrides_only_weaveback_keys() {
+    let text = "---\ntitle: Notes\nweaveback-open-delim: \"<<\"\nweaveback-close-delim: '>>'\nweaveback-sigil: ¤\n---\n# Notes\n";
+    let fm = parse_front_matter(text).unwrap();
+    assert!(fm.overrides_syntax());
+    let cfg = fm.apply(&base());
+    assert_eq!((cfg.open_delim.as_str(), cfg.close_delim.as_str()), ("<<", ">>"));
+    assert_eq!(cfg.chunk_end, "@@");
+    assert_eq!(cfg.sigil, '¤');
+}
+
+#[test]
+fn asciidoc_attributes_are_read_from_the_whole_document_header() {
+    let text = ":weaveback-chunk-end: @\n:weaveback-comment-markers: #, //\n// layout\n:toc: left\n= Title\nA. Author <a@example.org>\nv1.0, 2026-01-01\n:weaveback-open-delim: <<\n\n:weaveback-sigil: ^\n";
+    let fm = parse_front_matter(text).unwrap();
+    assert_eq!(fm.chunk_end.as_deref(), Some("@"));
+    assert_eq!(fm.comment_markers, Some(vec!["#".to_string(), "//".to_string()]));
+    assert_eq!(fm.open_delim.as_deref(), Some("<<"), "attributes after `= Title` count");
+    assert_eq!(fm.sigil, None, "the header ends at the first blank line");
+
+    let text = "= Title\nA. Author\nv1.0\nA paragraph.\n:weaveback-open-delim: <<\n";
+    assert_eq!(parse_front_matter(text).unwrap().open_delim, None);
+}
+
+#[test]
+fn yaml_subset_skips_foreign_nesting_and_rejects_the_rest() {
+    let text = "---\ntags:\n  - literate\n- tangle\n# comment\nweaveback-chunk-end: '@' # end marker\nweaveback-open-delim: << # open\n---\n";
+    let fm = parse_front_matter(text).unwrap();
+    assert_eq!(fm.chunk_end.as_deref(), Some("@"));
+    assert_eq!(fm.open_delim.as_deref(), Some("<<"));
+
+    for (text, line, message) in [
+        ("---\nweaveback-comment-markers: [//, \"#\"]\n---\n", 2, "must be a scalar"),
+        ("---\nweaveback-comment-markers:\n  - //\n---\n", 2, "on the same line"),
+        ("---\nweaveback-chunk-end: |\n  @\n---\n", 2, "single-line scalar"),
+        ("---\nweaveback-sigil: \"^\" x\n---\n", 2, "after its closing quote"),
+        ("---\ntitle: x\nloose text\n---\n", 3, "expected a `key: value` entry"),
+        ("---\n  indented: x\n---\n", 2, "nested YAML"),
+    ] {
+        let err = parse_front_matter(text).unwrap_err();
+        assert_eq!(err.line, line, "{text:?}");
+        assert!(err.message.contains(message), "{text:?}: {err}");
+    }
+}
+
+#[test]
+fn documents_without_front_matter_keep_the_base_syntax() {
+    for text in ["", "= Title\n", "---\nweaveback-open-delim: <<\nno closing line\n"] {
+        let fm = parse_front_matter(text).unwrap();
+        assert_eq!(fm, FrontMatter::default(), "{text:?}");
+        assert_eq!(fm.apply(&base()).open_delim, "<[");
+    }
+}
+
+#[test]
+fn invalid_entries_name_the_line() {
+    let fm = parse_front_matter(":weaveback-sigil: ^^\n").unwrap();
+    assert_eq!(fm.sigil, Some('^'));
+
+    let err = parse_front_matter("---\ntitle: x\nweaveback-sigil: %$\n---\n").unwrap_err();
+    assert_eq!(err.line, 3);
+    assert!(err.message.contains("one character"), "{err}");
+
+    let err = parse_front_matter(":weaveback-open: <<\n").unwrap_err();
+    assert_eq!(err.to_string(), "front matter line 1: unknown key `weaveback-open`");
+
+    let err = parse_front_matter(":weaveback-chunk-end: \"\"\n").unwrap_err();
+    assert!(err.message.contains("must not be empty"), "{err}");
+}
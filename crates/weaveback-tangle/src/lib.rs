@@ -6,6 +6,7 @@ pub mod db;
 pub mod noweb;
 pub mod safe_writer;
 pub mod lookup;
+pub mod front_matter;
 
 #[cfg(test)]
 mod tests;
@@ -32,8 +33,9 @@ impl From<std::io::Error> for WeavebackError {
     }
 }
 
+pub use crate::front_matter::{parse_front_matter, FrontMatter, FrontMatterError};
 pub use crate::block_parser::{parse_source_blocks, SourceBlockEntry};
 pub use crate::db::{WeavebackDb, NowebMapEntry, ChunkDefEntry, FtsResult, BlockForTagging, TaggedBlock};
-pub use crate::noweb::{ChunkDefinitionMatch, Clip, NowebSyntax, tangle_check};
+pub use crate::noweb::{ChunkDefinitionMatch, Clip, NowebSyntax, is_collection_reference, is_transclusion, tangle_check};
 pub use crate::safe_writer::SafeFileWriter;
 pub use crate::safe_writer::SafeWriterConfig;
@@ -41,55 +41,92 @@ pub fn find_line_col(text: &str, byte_offset: usize) -> (u32, u32) {
     (line_1, col_1)
 }
 
-/// Attempt to find a noweb-map entry for `out_file`.  Tries the raw path,
-/// then the normalized path, then a canonical absolute path when the file
-/// exists on disk.
-pub fn find_best_noweb_entry(
-    db: &WeavebackDb,
-    out_file: &str,
-    out_line_0: u32,
-    resolver: &PathResolver,
-) -> Result<Option<NowebMapEntry>, DbError> {
+/// One form of an output path to look up: a `files` key, or a suffix that
+/// `get_noweb_entry_by_suffix` matches against whole path components.
+enum OutPathCandidate {
+    Key(String),
+    Suffix(String),
+}
+
+/// The forms of `out_file` the noweb lookups try, in order.  Shared by
+/// `find_best_noweb_entry` and `find_best_noweb_entries` so that both resolve
+/// a path the same way.
+fn out_path_candidates<'a>(
+    out_file: &'a str,
+    resolver: &'a PathResolver,
+) -> impl Iterator<Item = OutPathCandidate> + 'a {
     // Try 1: Exact match as provided.
-    if let Some(entry) = db.get_noweb_entry(out_file, out_line_0)? {
-        return Ok(Some(entry));
-    }
+    let exact = std::iter::once(out_file.to_string());
 
     // Try 2: Normalized via PathResolver.
     let norm = resolver.normalize(out_file);
-    if norm != out_file && let Some(entry) = db.get_noweb_entry(&norm, out_line_0)? {
-        return Ok(Some(entry));
-    }
+    let normalized = (norm != out_file).then(|| norm.clone());
 
     // Try 3: Canonical on-disk path. This helps external tools like cargo and
-    // llvm-cov that often report absolute paths.
-    let abs = resolver.resolve_gen(out_file);
-    if let Ok(canon) = abs.canonicalize() {
+    // llvm-cov that often report absolute paths.  Only touches the file system
+    // when the earlier forms missed.
+    let canonical = std::iter::once_with(move || {
+        let canon = resolver.resolve_gen(out_file).canonicalize().ok()?;
         let canon_str = canon.to_string_lossy().into_owned();
-        if canon_str != out_file && canon_str != norm
-            && let Some(entry) = db.get_noweb_entry(&canon_str, out_line_0)? {
-            return Ok(Some(entry));
-        }
-    }
+        (canon_str != out_file && canon_str != norm).then_some(canon_str)
+    })
+    .flatten();
 
     // Try 4: progressively strip leading path components and match by suffix.
     // This bridges current repo layouts like `crates/weaveback/src/main.rs`
     // to db keys like `weaveback/src/main.rs`.
-    let parts = normalized_path_components(out_file);
-    if parts.len() >= MIN_DISTINCTIVE_SUFFIX_COMPONENTS
-        && let Some(entry) = db.get_noweb_entry_by_suffix(out_file, out_line_0)? {
-        return Ok(Some(entry));
-    }
-    for sub_str in distinctive_suffix_candidates(out_file) {
-        if sub_str != out_file
-            && let Some(entry) = db.get_noweb_entry_by_suffix(&sub_str, out_line_0)? {
-            return Ok(Some(entry));
+    let whole = (normalized_path_components(out_file).len() >= MIN_DISTINCTIVE_SUFFIX_COMPONENTS)
+        .then(|| out_file.to_string());
+    let suffixes = whole.into_iter().chain(distinctive_suffix_candidates(out_file));
+
+    exact
+        .chain(normalized)
+        .chain(canonical)
+        .map(OutPathCandidate::Key)
+        .chain(suffixes.map(OutPathCandidate::Suffix))
+}
+
+/// Attempt to find a noweb-map entry for `out_file`.  Tries the raw path,
+/// then the normalized path, then a canonical absolute path when the file
+/// exists on disk, then distinctive suffixes.
+pub fn find_best_noweb_entry(
+    db: &WeavebackDb,
+    out_file: &str,
+    out_line_0: u32,
+    resolver: &PathResolver,
+) -> Result<Option<NowebMapEntry>, DbError> {
+    for candidate in out_path_candidates(out_file, resolver) {
+        let entry = match &candidate {
+            OutPathCandidate::Key(path) => db.get_noweb_entry(path, out_line_0)?,
+            OutPathCandidate::Suffix(suffix) => db.get_noweb_entry_by_suffix(suffix, out_line_0)?,
+        };
+        if entry.is_some() {
+            return Ok(entry);
         }
     }
-
     Ok(None)
 }
 
+/// Every noweb-map entry of `out_file`, keyed by 0-indexed output line, with
+/// the same path fallbacks as `find_best_noweb_entry`.  Empty when no form of
+/// the path is mapped.
+pub fn find_best_noweb_entries(
+    db: &WeavebackDb,
+    out_file: &str,
+    resolver: &PathResolver,
+) -> Result<Vec<(u32, NowebMapEntry)>, DbError> {
+    for candidate in out_path_candidates(out_file, resolver) {
+        let entries = match &candidate {
+            OutPathCandidate::Key(path) => db.get_noweb_entries_for_file(path)?,
+            OutPathCandidate::Suffix(suffix) => db.get_noweb_entries_for_file_by_suffix(suffix)?,
+        };
+        if !entries.is_empty() {
+            return Ok(entries);
+        }
+    }
+    Ok(Vec::new())
+}
+
 /// Attempt to find a source configuration for `src_file`. Tries the raw path,
 /// then strips/adds common prefixes.
 pub fn find_best_source_config(
@@ -133,6 +133,33 @@ fn find_best_noweb_entry_rejects_ambiguous_two_component_suffixes() {
     assert!(got.is_none());
 }
 
+#[test]
+fn find_best_noweb_entries_returns_whole_file_by_suffix() {
+    let mut db = WeavebackDb::open_temp().expect("temp db");
+    let entry = |chunk: &str, src_line: u32| NowebMapEntry {
+        src_file: "crates/weaveback/src/weaveback.adoc".to_string(),
+        chunk_name: chunk.to_string(),
+        src_line,
+        indent: String::new(),
+        confidence: Confidence::Exact,
+    };
+    db.set_noweb_entries(
+        "/tmp/wb-pass-root/weaveback/src/main.rs",
+        &[(0, entry("main", 10)), (1, entry("main", 11)), (2, entry("helper", 40))],
+    )
+    .expect("set noweb entries");
+
+    let resolver = PathResolver::new(PathBuf::from("."), PathBuf::from("crates"));
+    let entries = find_best_noweb_entries(&db, "crates/weaveback/src/main.rs", &resolver)
+        .expect("lookup ok");
+    let summary: Vec<(u32, &str, u32)> = entries
+        .iter()
+        .map(|(line, e)| (*line, e.chunk_name.as_str(), e.src_line))
+        .collect();
+    assert_eq!(summary, vec![(0, "main", 10), (1, "main", 11), (2, "helper", 40)]);
+    assert!(find_best_noweb_entries(&db, "other.rs", &resolver).unwrap().is_empty());
+}
+
 // ── find_line_col ──────────────────────────────────────────────────────
 
 #[test]
@@ -2,6 +2,7 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 use weaveback_tangle::{WeavebackError, Clip, SafeFileWriter, SafeWriterConfig};
+use weaveback_tangle::safe_writer::recover_commit;
 use clap::Parser;
 use miette::Diagnostic;
 use std::collections::HashMap;
@@ -129,12 +130,17 @@ fn run(args: Args) -> Result<(), Error> {
         })
         .collect();
 
+    if let Some(recovery) = recover_commit(&args.db)? {
+        eprintln!("{}: {recovery}", args.db.display());
+    }
+
     let safe_writer = SafeFileWriter::with_config(
         &args.gen_dir,
         SafeWriterConfig {
             formatters,
             allow_home: args.allow_home,
             force_generated: args.force_generated,
+            atomic_commit: true,
             ..SafeWriterConfig::default()
         },
     )?;
@@ -3,12 +3,13 @@
 
 use memchr;
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::io::{self, Read, Write};
 use std::path::{Component, Path};
 
 use crate::db::{ChunkDefEntry, Confidence, NowebMapEntry};
+use crate::front_matter::{parse_front_matter, FrontMatter};
 use crate::safe_writer::SafeWriterError;
 use crate::WeavebackError;
 use crate::SafeFileWriter;
@@ -17,6 +18,8 @@ use log::debug;
 mod types;
 mod paths;
 mod store_read;
+mod org;
+mod transclude;
 mod expand;
 mod utils;
 mod writer;
@@ -25,10 +28,12 @@ mod remap;
 mod write_files;
 
 pub use clip::{tangle_check, Clip};
+pub use expand::is_collection_reference;
+pub use transclude::is_transclusion;
 pub use types::{ChunkDefinitionMatch, ChunkError, NowebSyntax};
 
 pub(in crate::noweb) use paths::{expand_tilde, path_is_safe};
 pub(in crate::noweb) use remap::remap_noweb_entries;
-pub(in crate::noweb) use store_read::ChunkStore;
-pub(in crate::noweb) use types::{ChunkDef, ChunkLocation, NamedChunk};
+pub(in crate::noweb) use store_read::{with_noweb_delimiters, ChunkStore};
+pub(in crate::noweb) use types::{guard_holds, ChunkDef, ChunkLocation, NamedChunk};
 pub(in crate::noweb) use writer::ChunkWriter;
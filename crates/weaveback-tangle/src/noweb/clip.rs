@@ -38,6 +38,25 @@ impl Clip {
         self.store.warn_unused = warn;
     }
 
+    /// Select the build profile `@if(profile=…)` guards are evaluated
+    /// against (`None`, the default, selects none).  Takes effect for files
+    /// read afterwards.
+    pub fn set_profile(&mut self, profile: Option<String>) {
+        self.store.profile = profile;
+    }
+
+    /// Resolve `<<@from path>>` references against `root` instead of the
+    /// working directory.
+    pub fn set_project_root(&mut self, root: &Path) {
+        self.store.transclude_root = root.to_path_buf();
+    }
+
+    /// Files that `<<@from …>>` references in the read sources transclude,
+    /// as written in the references.
+    pub fn transcluded_files(&self) -> Vec<String> {
+        self.store.transcluded_files()
+    }
+
     pub fn has_chunk(&self, name: &str) -> bool {
         self.store.has_chunk(name)
     }
@@ -2,6 +2,7 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 use super::*;
+use weaveback_core::glob::glob_match;
 
 /// Mutable state threaded through the recursive chunk expansion.
 struct ExpandState {
@@ -67,11 +68,43 @@ fn apply_ref_space_options(
     lines
 }
 
+/// Whether `reference` names a collection (`<<@glob pattern>>` or
+/// `<<@collect tag>>`) rather than a single chunk.
+pub fn is_collection_reference(reference: &str) -> bool {
+    reference.starts_with(GLOB_PREFIX) || reference.starts_with(COLLECT_PREFIX)
+}
+
+const GLOB_PREFIX: &str = "@glob ";
+const COLLECT_PREFIX: &str = "@collect ";
+
 /// Return type of `expand_with_map`: expanded lines, source-map entries,
 /// referenced chunk names, and direct dependency edges.
 type ExpandResult = (Vec<String>, Vec<NowebMapEntry>, HashSet<String>, Vec<(String, String, String)>);
 
 impl ChunkStore {
+    /// The chunks a collection reference expands to, sorted by name, or
+    /// `None` for a plain reference.  `@file` chunks and `consumer` itself are
+    /// never members.
+    fn collection_members<'a>(&'a self, reference: &str, consumer: &str) -> Option<Vec<&'a str>> {
+        if !is_collection_reference(reference) {
+            return None;
+        }
+        let tag = reference.strip_prefix(COLLECT_PREFIX).map(str::trim);
+        let pattern = reference.strip_prefix(GLOB_PREFIX).map_or("", str::trim);
+        let mut members: Vec<&str> = self
+            .chunks
+            .iter()
+            .filter(|(name, _)| !name.starts_with("@file ") && name.as_str() != consumer)
+            .filter(|(name, chunk)| match tag {
+                Some(tag) => chunk.tags.contains(tag),
+                None => glob_match(pattern, name),
+            })
+            .map(|(name, _)| name.as_str())
+            .collect();
+        members.sort_unstable();
+        Some(members)
+    }
+
     fn expand_inner(
         &self,
         chunk_name: &str,
@@ -152,7 +185,7 @@ impl ChunkStore {
             let mut def_result = Vec::new();
 
             for (line_count, line) in def.content.iter().enumerate() {
-                if let Some(slot_match) = self.syntax.parse_reference_line(line) {
+                if let Some(slot_match) = self.syntax_for(def.file_idx).parse_reference_line(line) {
                     let add_indent = slot_match.add_indent.as_str();
                     let modifier = slot_match.modifier.as_str();
                     let referenced_chunk = slot_match.referenced_chunk.as_str();
@@ -177,21 +210,50 @@ impl ChunkStore {
                         line: def.line + line_count,
                     };
 
-                    // Record the direct dependency edge before recursing.
-                    state.deps.insert((
-                        chunk_name.to_string(),
-                        referenced_chunk.trim().to_string(),
-                        src_file.clone(),
-                    ));
-
-                    let expanded = self.expand_inner(
-                        referenced_chunk.trim(),
-                        &new_indent,
-                        state,
-                        new_loc,
-                        child_options,
-                    )?;
-                    def_result.extend(apply_ref_space_options(expanded, child_options));
+                    let reference = referenced_chunk.trim();
+                    if is_transclusion(reference) {
+                        state.deps.insert((
+                            chunk_name.to_string(),
+                            reference.to_string(),
+                            src_file.clone(),
+                        ));
+                        let lines = self.transclude(reference, &new_indent, &new_loc)?;
+                        def_result.extend(apply_ref_space_options(lines, child_options));
+                        continue;
+                    }
+                    let targets = match self.collection_members(reference, chunk_name) {
+                        Some(mut members) => {
+                            // The edge to the pattern lets incremental runs
+                            // find collections a new chunk may join.
+                            state.deps.insert((
+                                chunk_name.to_string(),
+                                reference.to_string(),
+                                src_file.clone(),
+                            ));
+                            if child_options.reversed {
+                                members.reverse();
+                            }
+                            members
+                        }
+                        None => vec![reference],
+                    };
+                    for target in targets {
+                        // Record the direct dependency edge before recursing.
+                        state.deps.insert((
+                            chunk_name.to_string(),
+                            target.to_string(),
+                            src_file.clone(),
+                        ));
+
+                        let expanded = self.expand_inner(
+                            target,
+                            &new_indent,
+                            state,
+                            new_loc.clone(),
+                            child_options,
+                        )?;
+                        def_result.extend(apply_ref_space_options(expanded, child_options));
+                    }
                 } else {
                     let line_indent = if line.len() > def.base_indent {
                         &line[def.base_indent..]
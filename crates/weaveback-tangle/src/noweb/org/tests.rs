@@ -0,0 +1,35 @@
+// weaveback-tangle/src/noweb/org/tests.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+
+#[test]
+fn header_args_map_onto_chunk_names() {
+    let mut args = OrgBlockArgs::default();
+    args.parse_into(" rust :tangle \"src/main.rs\" :noweb-ref main :results silent");
+    assert_eq!(args.tangle.as_deref(), Some("src/main.rs"));
+    assert_eq!(args.noweb_ref.as_deref(), Some("main"));
+    assert_eq!(
+        args.chunk_names(Some("main-block".to_string())),
+        vec!["@file src/main.rs", "main", "main-block"]
+    );
+
+    let mut args = OrgBlockArgs::default();
+    args.parse_into("python :tangle no");
+    assert!(args.chunk_names(None).is_empty());
+}
+
+#[test]
+fn org_keywords_are_case_insensitive() {
+    assert_eq!(org_keyword("#+BEGIN_SRC rust", "begin_src"), Some(" rust"));
+    assert_eq!(org_keyword("#+begin_src", "begin_src"), Some(""));
+    assert_eq!(org_keyword("#+begin_srcx", "begin_src"), None);
+    assert_eq!(org_keyword("#+NAME: helper", "name:"), Some(" helper"));
+}
+
+#[test]
+fn comma_escapes_are_removed() {
+    assert_eq!(org_unescape("  ,* heading"), "  * heading\n");
+    assert_eq!(org_unescape(",#+begin_src"), "#+begin_src\n");
+    assert_eq!(org_unescape("a, b"), "a, b\n");
+}
@@ -7,6 +7,8 @@ pub struct ChunkStore {
     pub(super) chunks: HashMap<String, NamedChunk>,
     pub(super) file_chunks: Vec<String>,
     pub(super) syntax: NowebSyntax,
+    /// Syntax of files whose front matter overrides `syntax`, by file index.
+    pub(super) file_syntaxes: HashMap<usize, NowebSyntax>,
     pub(super) file_names: Vec<String>,
     /// When `true`, referencing an undefined chunk is a fatal error
     /// and `@file` redefinition without `@replace` is also a fatal error.
@@ -16,6 +18,12 @@ pub struct ChunkStore {
     /// referenced by any `@file` chunk (directly or transitively).
     /// Default `false`: unused-chunk warnings are suppressed.
     pub warn_unused: bool,
+    /// Build profile that `@if(profile=…)` guards are evaluated against.
+    /// Default `None`: only negated guards (`profile!=…`) hold.
+    pub profile: Option<String>,
+    /// Directory `<<@from path>>` references are resolved against; empty
+    /// (the default) means the working directory.
+    pub(super) transclude_root: std::path::PathBuf,
     /// Errors accumulated during `read()` that are promoted to hard errors
     /// when `strict_undefined` is `true`.  Checked by `Clip::write_files`.
     pub parse_errors: Vec<ChunkError>,
@@ -31,9 +39,12 @@ impl ChunkStore {
             chunks: HashMap::new(),
             file_chunks: Vec::new(),
             syntax: NowebSyntax::new(open_delim, close_delim, chunk_end, comment_markers),
+            file_syntaxes: HashMap::new(),
             file_names: Vec::new(),
             strict_undefined: false,
             warn_unused: false,
+            profile: None,
+            transclude_root: std::path::PathBuf::new(),
             parse_errors: Vec::new(),
         }
     }
@@ -44,6 +55,11 @@ impl ChunkStore {
         idx
     }
 
+    /// The syntax chunks of file `file_idx` are written in.
+    pub(super) fn syntax_for(&self, file_idx: usize) -> &NowebSyntax {
+        self.file_syntaxes.get(&file_idx).unwrap_or(&self.syntax)
+    }
+
     fn validate_chunk_name(&self, chunk_name: &str, is_file: bool) -> bool {
         if is_file {
             let path = chunk_name.strip_prefix("@file ").unwrap_or(chunk_name);
@@ -53,22 +69,89 @@ impl ChunkStore {
         }
     }
 }
+/// How a source file delimits its chunks, chosen by its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::noweb) enum SourceFormat {
+    /// Chunks between the configured open and chunk-end markers.
+    Delimited,
+    /// Classic noweb (`.nw`): a chunk also ends at the next `@` documentation
+    /// line, the next definition, or the end of the file.
+    Noweb,
+    /// Org-mode (`.org`): chunks are `#+begin_src` blocks.
+    Org,
+}
+
+impl SourceFormat {
+    pub(in crate::noweb) fn of(file_name: &str) -> Self {
+        match Path::new(file_name).extension().and_then(|e| e.to_str()) {
+            Some("nw") => Self::Noweb,
+            Some("org") => Self::Org,
+            _ => Self::Delimited,
+        }
+    }
+}
+
+/// `syntax` with noweb's `<<`/`>>` delimiters, which Org sources use whatever
+/// the pass or front matter configures, and classic noweb sources use unless
+/// they are written with the configured delimiters.
+pub(in crate::noweb) fn with_noweb_delimiters(syntax: &NowebSyntax) -> NowebSyntax {
+    syntax.with_overrides(&FrontMatter {
+        open_delim: Some("<<".to_string()),
+        close_delim: Some(">>".to_string()),
+        ..FrontMatter::default()
+    })
+}
+
+/// Whether `line` starts a classic noweb documentation chunk (`@` alone or
+/// followed by whitespace, including `@ %def` index lines).
+fn is_noweb_doc_line(line: &str) -> bool {
+    line.strip_prefix('@')
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
 impl ChunkStore {
     pub fn read(&mut self, text: &str, file_idx: usize) {
         debug!("Reading text for file_idx: {}", file_idx);
+        let format = self
+            .file_names
+            .get(file_idx)
+            .map_or(SourceFormat::Delimited, |name| SourceFormat::of(name));
+        if format == SourceFormat::Org {
+            self.read_org(text, file_idx);
+            return;
+        }
+        let classic = format == SourceFormat::Noweb;
+        let mut syntax = match parse_front_matter(text) {
+            Ok(front_matter) if front_matter.overrides_syntax() => {
+                Some(self.syntax.with_overrides(&front_matter))
+            }
+            _ => None,
+        };
+        if classic {
+            // A `.nw` file written with the configured delimiters keeps them;
+            // any other is read with noweb's own `<<`/`>>`.
+            let configured = syntax.as_ref().unwrap_or(&self.syntax);
+            if !text.lines().any(|line| configured.parse_definition_line(line).is_some()) {
+                syntax = Some(with_noweb_delimiters(configured));
+            }
+        }
+        let syntax = match syntax {
+            Some(syntax) => {
+                self.file_syntaxes.insert(file_idx, syntax.clone());
+                syntax
+            }
+            None => {
+                self.file_syntaxes.remove(&file_idx);
+                self.syntax.clone()
+            }
+        };
         let mut current_chunk: Option<(String, usize)> = None;
 
         for (line_no, line) in text.lines().enumerate() {
-            if self.syntax.parse_definition_line(line).is_none() {
+            let Some(def_match) = syntax.parse_definition_line(line) else {
                 // No open delimiter — can only be a close marker or content.
-                if self.syntax.is_close_line(line) {
-                    if let Some((ref cname, idx)) = current_chunk
-                        && let Some(chunk) = self.chunks.get_mut(cname)
-                        && let Some(def) = chunk.definitions.get_mut(idx)
-                    {
-                        def.def_end = Some(line_no);
-                    }
-                    current_chunk = None;
+                if syntax.is_close_line(line) || (classic && is_noweb_doc_line(line)) {
+                    self.end_definition(current_chunk.take(), line_no);
                 } else if let Some((ref cname, idx)) = current_chunk
                     && let Some(chunk) = self.chunks.get_mut(cname)
                 {
@@ -81,90 +164,125 @@ impl ChunkStore {
                     }
                 }
                 continue;
+            };
+            debug!(
+                "Found open pattern: indentation='{}', base_name='{}'",
+                def_match.indent_len, def_match.base_name
+            );
+            if classic {
+                self.end_definition(current_chunk.take(), line_no);
             }
-            if let Some(def_match) = self.syntax.parse_definition_line(line) {
-                debug!(
-                    "Found open pattern: indentation='{}', base_name='{}'",
-                    def_match.indent_len, def_match.base_name
-                );
-
-                let full_name = if def_match.is_file {
-                    format!("@file {}", def_match.base_name)
-                } else {
-                    def_match.base_name
-                };
-
-                if self.validate_chunk_name(&full_name, def_match.is_file) {
-                    if full_name.starts_with("@file ") {
-                        if self.chunks.contains_key(&full_name) && !def_match.is_replace {
-                            let location = ChunkLocation { file_idx, line: line_no };
-                            let err = ChunkError::FileChunkRedefinition {
-                                file_chunk: full_name.clone(),
-                                file_name: self
-                                    .file_names
-                                    .get(file_idx)
-                                    .cloned()
-                                    .unwrap_or_default(),
-                                location,
-                            };
-                            if self.strict_undefined {
-                                self.parse_errors.push(err);
-                            } else {
-                                eprintln!("{}", err);
-                            }
-                            continue;
-                        }
-                        if def_match.is_replace {
-                            self.chunks.remove(&full_name);
-                        }
-                    } else if def_match.is_replace {
-                        self.chunks.remove(&full_name);
-                    }
-
-                    let chunk = self
-                        .chunks
-                        .entry(full_name.clone())
-                        .or_insert_with(NamedChunk::new);
-                    let def_idx = chunk.definitions.len();
-                    chunk.definitions.push(ChunkDef::new(
-                        def_match.indent_len,
-                        file_idx,
-                        line_no,
-                    ));
-
-                    current_chunk = Some((full_name.clone(), def_idx));
-                    if full_name.starts_with("@file ") && !self.file_chunks.contains(&full_name) {
-                        self.file_chunks.push(full_name.clone());
-                    }
-                    debug!("Started chunk: {}", full_name);
-                }
+            if let Some(guard) = &def_match.guard
+                && !self.guard_active(guard, file_idx, line_no)
+            {
+                // Close an open chunk so the inactive body is not appended to it.
+                self.end_definition(current_chunk.take(), line_no);
                 continue;
             }
 
-            if self.syntax.is_close_line(line) {
-                if let Some((ref cname, idx)) = current_chunk
-                    && let Some(chunk) = self.chunks.get_mut(cname)
-                    && let Some(def) = chunk.definitions.get_mut(idx)
-                {
-                    def.def_end = Some(line_no);
+            let full_name = if def_match.is_file {
+                format!("@file {}", def_match.base_name)
+            } else {
+                def_match.base_name
+            };
+            if let Some(def_idx) = self.begin_definition(
+                &full_name,
+                def_match.is_replace,
+                false,
+                def_match.indent_len,
+                file_idx,
+                line_no,
+            ) {
+                if let Some(chunk) = self.chunks.get_mut(&full_name) {
+                    chunk.tags.extend(def_match.tags);
                 }
-                current_chunk = None;
-                continue;
+                current_chunk = Some((full_name, def_idx));
             }
+        }
+        if classic {
+            self.end_definition(current_chunk, text.lines().count());
+        }
 
-            if let Some((ref cname, idx)) = current_chunk
-                && let Some(chunk) = self.chunks.get_mut(cname)
-            {
-                let def = chunk.definitions.get_mut(idx)
-                    .expect("internal invariant: def_idx is valid");
-                if line.ends_with('\n') {
-                    def.content.push(line.to_string());
-                } else {
-                    def.content.push(format!("{}\n", line));
-                }
+        debug!("Finished reading. File chunks: {:?}", self.file_chunks);
+    }
+
+    /// Register a new definition of `full_name` whose open marker is on
+    /// `line_no`, returning its index within the chunk.
+    ///
+    /// `append` extends an existing `@file` chunk instead of treating the new
+    /// definition as a redefinition.  Returns `None` for invalid names and
+    /// rejected `@file` redefinitions.
+    pub(in crate::noweb) fn begin_definition(
+        &mut self,
+        full_name: &str,
+        is_replace: bool,
+        append: bool,
+        indent_len: usize,
+        file_idx: usize,
+        line_no: usize,
+    ) -> Option<usize> {
+        let is_file = full_name.starts_with("@file ");
+        if !self.validate_chunk_name(full_name, is_file) {
+            return None;
+        }
+        if is_file && self.chunks.contains_key(full_name) && !is_replace && !append {
+            let location = ChunkLocation { file_idx, line: line_no };
+            let err = ChunkError::FileChunkRedefinition {
+                file_chunk: full_name.to_string(),
+                file_name: self.file_names.get(file_idx).cloned().unwrap_or_default(),
+                location,
+            };
+            if self.strict_undefined {
+                self.parse_errors.push(err);
+            } else {
+                eprintln!("{}", err);
             }
+            return None;
+        }
+        if is_replace {
+            self.chunks.remove(full_name);
         }
 
-        debug!("Finished reading. File chunks: {:?}", self.file_chunks);
+        let chunk = self
+            .chunks
+            .entry(full_name.to_string())
+            .or_insert_with(NamedChunk::new);
+        let def_idx = chunk.definitions.len();
+        chunk.definitions.push(ChunkDef::new(indent_len, file_idx, line_no));
+        if is_file && !self.file_chunks.iter().any(|f| f == full_name) {
+            self.file_chunks.push(full_name.to_string());
+        }
+        debug!("Started chunk: {}", full_name);
+        Some(def_idx)
+    }
+
+    /// Whether the definition guarded by `guard` on `line_no` is active for
+    /// the selected profile.  A malformed guard is an `InvalidGuard` error
+    /// (collected in strict mode, printed otherwise) and disables it.
+    fn guard_active(&mut self, guard: &str, file_idx: usize, line_no: usize) -> bool {
+        if let Some(active) = guard_holds(guard, self.profile.as_deref()) {
+            return active;
+        }
+        let err = ChunkError::InvalidGuard {
+            guard: guard.to_string(),
+            file_name: self.file_names.get(file_idx).cloned().unwrap_or_default(),
+            location: ChunkLocation { file_idx, line: line_no },
+        };
+        if self.strict_undefined {
+            self.parse_errors.push(err);
+        } else {
+            eprintln!("{}", err);
+        }
+        false
+    }
+
+    /// Record `line_no` as the close line of the open definition, if any.
+    pub(in crate::noweb) fn end_definition(&mut self, open: Option<(String, usize)>, line_no: usize) {
+        if let Some((cname, idx)) = open
+            && let Some(chunk) = self.chunks.get_mut(&cname)
+            && let Some(def) = chunk.definitions.get_mut(idx)
+        {
+            def.def_end = Some(line_no);
+        }
     }
 }
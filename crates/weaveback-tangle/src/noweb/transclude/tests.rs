@@ -0,0 +1,49 @@
+// weaveback-tangle/src/noweb/transclude/tests.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+
+const LEGACY: &str = "fn a() {}\n\
+                      impl Parser {\n    \
+                          // tag::parse[]\n    \
+                          fn parse(&self) {\n    \
+                          // tag::inner[]\n        \
+                              step();\n    \
+                          // end::inner[]\n    \
+                          }\n    \
+                          // end::parse[]\n\
+                      }\n";
+
+#[test]
+fn references_split_into_path_and_selector() {
+    assert_eq!(split_reference("@from src/a.rs#parse"), Some(("src/a.rs", Some("parse"))));
+    assert_eq!(split_reference("@from src/a.rs"), Some(("src/a.rs", None)));
+    assert_eq!(split_reference("src/a.rs"), None);
+    assert_eq!(parse_line_range("L3-L5"), Some((3, 5)));
+    assert_eq!(parse_line_range("L3-5"), Some((3, 5)));
+    assert_eq!(parse_line_range("L7"), Some((7, 7)));
+    assert_eq!(parse_line_range("parse"), None);
+}
+
+#[test]
+fn tagged_regions_drop_markers_and_dedent() {
+    let (span, lines) = select_region(LEGACY, Some("parse")).unwrap();
+    assert_eq!(span, (2, 8));
+    assert_eq!(
+        lines,
+        vec![
+            (3, "fn parse(&self) {".to_string()),
+            (5, "    step();".to_string()),
+            (7, "}".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn line_ranges_are_one_indexed_and_checked() {
+    let (span, lines) = select_region(LEGACY, Some("L1-L2")).unwrap();
+    assert_eq!(span, (0, 1));
+    assert_eq!(lines, vec![(0, "fn a() {}".to_string()), (1, "impl Parser {".to_string())]);
+    assert!(select_region(LEGACY, Some("L9-L20")).unwrap_err().contains("outside the file"));
+    assert!(select_region(LEGACY, Some("missing")).unwrap_err().contains("tag::missing[]"));
+}
@@ -63,6 +63,19 @@ pub enum ChunkError {
         file_name: String,
         location: ChunkLocation,
     },
+    #[error("{file_name} line {}: invalid guard '@if({guard})' (expected profile=NAME or profile!=NAME)", .location.line + 1)]
+    InvalidGuard {
+        guard: String,
+        file_name: String,
+        location: ChunkLocation,
+    },
+    #[error("{file_name} line {}: cannot transclude '{reference}': {message}", .location.line + 1)]
+    Transclusion {
+        reference: String,
+        message: String,
+        file_name: String,
+        location: ChunkLocation,
+    },
 }
 
 impl From<WeavebackError> for ChunkError {
@@ -77,6 +90,10 @@ pub struct ChunkDefinitionMatch {
     pub base_name: String,
     pub is_replace: bool,
     pub is_file: bool,
+    /// Tags from `@tag(a, b)` modifiers, collected by `<<@collect a>>`.
+    pub tags: Vec<String>,
+    /// Condition of a trailing `@if(…)` guard, without the parentheses.
+    pub guard: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +103,39 @@ pub(in crate::noweb) struct ChunkReferenceMatch {
     pub(in crate::noweb) referenced_chunk: String,
 }
 
+/// Tag names from a run of `@tag(a, b)` modifiers.
+fn parse_tags(modifiers: &str) -> Vec<String> {
+    modifiers
+        .split("@tag(")
+        .filter_map(|group| group.split_once(')'))
+        .flat_map(|(names, _)| names.split(','))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Whether the guard `profile=a|b` (or `profile!=a|b`) holds when building
+/// `profile`; `None` if the guard is malformed.  With no profile selected,
+/// only negated guards hold.
+pub(in crate::noweb) fn guard_holds(guard: &str, profile: Option<&str>) -> Option<bool> {
+    let (key, negated, names) = match guard.split_once("!=") {
+        Some((key, names)) => (key, true, names),
+        None => {
+            let (key, names) = guard.split_once('=')?;
+            (key, false, names)
+        }
+    };
+    if key.trim() != "profile" {
+        return None;
+    }
+    let names: Vec<&str> = names.split(['|', ',']).map(str::trim).collect();
+    if names.iter().any(|name| name.is_empty()) {
+        return None;
+    }
+    let selected = profile.is_some_and(|profile| names.contains(&profile));
+    Some(selected != negated)
+}
+
 #[derive(Debug, Clone)]
 pub struct NowebSyntax {
     open_re: Regex,
@@ -93,6 +143,10 @@ pub struct NowebSyntax {
     close_re: Regex,
     open_bytes: Box<[u8]>,
     close_bytes: Box<[u8]>,
+    open_delim: String,
+    close_delim: String,
+    chunk_end: String,
+    comment_markers: Vec<String>,
 }
 
 impl NowebSyntax {
@@ -112,7 +166,7 @@ impl NowebSyntax {
             .join("|");
 
         let open_pattern = format!(
-            r"^(?P<indent>\s*)(?:{})?[ \t]*{}(?P<replace>@replace[ \t]+)?(?P<file>@file[ \t]+)?(?P<name>.+?){}=[ \t]*$",
+            r"^(?P<indent>\s*)(?:{})?[ \t]*{}(?P<tags>(?:@tag\([^)]*\)[ \t]+)*)(?P<replace>@replace[ \t]+)?(?P<file>@file[ \t]+)?(?P<name>.+?){}=(?:[ \t]+@if\((?P<guard>[^)]*)\))?[ \t]*$",
             escaped_comments, od, cd
         );
         let slot_pattern = format!(
@@ -131,9 +185,23 @@ impl NowebSyntax {
             close_re: Regex::new(&close_pattern).expect("Invalid close pattern"),
             open_bytes: open_delim.as_bytes().into(),
             close_bytes: chunk_end.as_bytes().into(),
+            open_delim: open_delim.to_string(),
+            close_delim: close_delim.to_string(),
+            chunk_end: chunk_end.to_string(),
+            comment_markers: comment_markers.to_vec(),
         }
     }
 
+    /// This syntax with a file's front-matter overrides applied.
+    pub fn with_overrides(&self, front_matter: &FrontMatter) -> Self {
+        Self::new(
+            front_matter.open_delim.as_deref().unwrap_or(&self.open_delim),
+            front_matter.close_delim.as_deref().unwrap_or(&self.close_delim),
+            front_matter.chunk_end.as_deref().unwrap_or(&self.chunk_end),
+            front_matter.comment_markers.as_deref().unwrap_or(&self.comment_markers),
+        )
+    }
+
     pub fn parse_definition_line(&self, line: &str) -> Option<ChunkDefinitionMatch> {
         memchr::memmem::find(line.as_bytes(), &self.open_bytes)?;
         let caps = self.open_re.captures(line)?;
@@ -142,6 +210,8 @@ impl NowebSyntax {
             base_name: caps.name("name").map_or("", |m| m.as_str()).to_string(),
             is_replace: caps.name("replace").is_some(),
             is_file: caps.name("file").is_some(),
+            tags: caps.name("tags").map_or_else(Vec::new, |m| parse_tags(m.as_str())),
+            guard: caps.name("guard").map(|m| m.as_str().trim().to_string()),
         })
     }
 
@@ -163,12 +233,15 @@ impl NowebSyntax {
 #[derive(Debug)]
 pub(in crate::noweb) struct NamedChunk {
     pub(in crate::noweb) definitions: Vec<ChunkDef>,
+    /// Union of the `@tag(…)` modifiers on all definitions.
+    pub(in crate::noweb) tags: BTreeSet<String>,
 }
 
 impl NamedChunk {
     pub(in crate::noweb) fn new() -> Self {
         Self {
             definitions: Vec::new(),
+            tags: BTreeSet::new(),
         }
     }
 }
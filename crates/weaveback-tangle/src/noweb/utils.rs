@@ -16,13 +16,15 @@ impl ChunkStore {
         self.chunks.clear();
         self.file_chunks.clear();
         self.file_names.clear();
+        self.file_syntaxes.clear();
     }
 
     /// Return a `ChunkDefEntry` for every chunk definition that has a recorded
-    /// close-marker line.  Definitions where `def_end` is `None` (file ended
-    /// without a close marker) are silently skipped.
+    /// close-marker line, plus one per readable `<<@from …>>` region.
+    /// Definitions where `def_end` is `None` (file ended without a close
+    /// marker) are silently skipped.
     pub fn chunk_defs(&self) -> Vec<ChunkDefEntry> {
-        let mut out = Vec::new();
+        let mut out = self.transclusion_defs();
         for (chunk_name, named_chunk) in &self.chunks {
             for (nth, def) in named_chunk.definitions.iter().enumerate() {
                 let Some(def_end_0) = def.def_end else { continue };
@@ -168,6 +168,38 @@ impl Clip {
             .collect()
     }
 
+    /// Return the `gen/`-relative paths of all `@file` chunks, i.e. the keys
+    /// under which `SafeFileWriter` stores their baselines.  Tilde paths are
+    /// written outside `gen/` without a baseline and are therefore omitted.
+    pub fn output_file_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .store
+            .get_file_chunks()
+            .iter()
+            .map(|name| name.strip_prefix("@file ").unwrap_or(name).trim())
+            .filter(|path| !std::path::Path::new(&expand_tilde(path)).is_absolute())
+            .map(str::to_string)
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    pub fn gen_base(&self) -> &Path {
+        self.writer.get_gen_base()
+    }
+
+    /// Content staged for each `@file` chunk when the writer runs in check
+    /// mode, keyed like `output_file_keys`.
+    pub fn staged_outputs(&self) -> &std::collections::BTreeMap<String, Vec<u8>> {
+        self.writer.staged_outputs()
+    }
+
+    /// Generated files whose hand edits were three-way merged in this run.
+    pub fn merged_outputs(&self) -> &[crate::safe_writer::MergedOutput] {
+        self.writer.merged_outputs()
+    }
+
     pub fn db(&self) -> &crate::db::WeavebackDb {
         self.writer.db()
     }
@@ -176,6 +208,12 @@ impl Clip {
         self.writer.db_mut()
     }
 
+    /// Delete a generated file together with this pass's outputs; see
+    /// `SafeFileWriter::remove_output`.
+    pub fn remove_output(&mut self, path: &Path) -> Result<(), WeavebackError> {
+        self.writer.remove_output(path).map_err(WeavebackError::SafeWriter)
+    }
+
     pub fn finish(self, target: &Path) -> Result<(), WeavebackError> {
         self.writer.finish(target).map_err(WeavebackError::SafeWriter)
     }
@@ -2,17 +2,24 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 use crate::db::{WeavebackDb, DbError};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
 mod accessors;
+mod commit;
 mod helpers;
+mod merge;
 mod paths;
 mod write_flow;
 
+pub use commit::{CommitJournal, JournalEntry, Recovery, journal_path, recover_commit};
+pub use merge::{Merge3, MergedOutput, has_conflict_markers, merge3};
+
+use commit::PendingOutput;
+
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -25,12 +32,16 @@ pub enum SafeWriterError {
     BackupFailed(PathBuf),
     #[error("File was modified externally: {0}")]
     ModifiedExternally(PathBuf),
+    #[error("File has unresolved merge conflicts: {0}")]
+    UnresolvedConflict(PathBuf),
     #[error("Security violation: {0}")]
     SecurityViolation(String),
     #[error("Formatter error: {0}")]
     FormatterError(String),
     #[error("Database error: {0}")]
     DbError(#[from] DbError),
+    #[error("Commit journal error: {0}")]
+    JournalError(String),
 }
 #[derive(Debug, Clone)]
 pub struct SafeWriterConfig {
@@ -43,6 +54,15 @@ pub struct SafeWriterConfig {
     /// Override modification detection for generated files and always rewrite
     /// them from the current literate source.
     pub force_generated: bool,
+    /// Stage and format outputs without touching `gen/`; the results are
+    /// collected in `staged_outputs` instead of being copied.
+    pub check: bool,
+    /// Three-way merge externally modified files with the new output instead
+    /// of refusing to overwrite them.
+    pub merge: bool,
+    /// Stage every output and put them in place only in `finish`, together
+    /// with the database merge, under a recovery journal.
+    pub atomic_commit: bool,
 }
 
 impl Default for SafeWriterConfig {
@@ -52,6 +72,9 @@ impl Default for SafeWriterConfig {
             formatters: HashMap::new(),
             allow_home: false,
             force_generated: false,
+            check: false,
+            merge: false,
+            atomic_commit: false,
         }
     }
 }
@@ -62,6 +85,16 @@ pub struct SafeFileWriter {
     /// Staging area: logical file name → temp file on disk.
     /// The NamedTempFile is kept alive here until after_write consumes it.
     staging: HashMap<String, NamedTempFile>,
+    /// Check mode only: logical file name → final staged content.
+    staged_outputs: BTreeMap<String, Vec<u8>>,
+    /// Persistent database consulted for baselines this run has not written.
+    prior: Option<WeavebackDb>,
+    /// Merge mode only: files whose hand edits were merged in this run.
+    merged_outputs: Vec<MergedOutput>,
+    /// Atomic-commit mode only: outputs waiting for `finish`.
+    pending: Vec<PendingOutput>,
+    /// Atomic-commit mode only: generated files `finish` deletes.
+    removals: Vec<PathBuf>,
 }
 
 impl SafeFileWriter {
@@ -73,12 +106,16 @@ impl SafeFileWriter {
         gen_base: P,
         config: SafeWriterConfig,
     ) -> Result<Self, SafeWriterError> {
-        fs::create_dir_all(gen_base.as_ref())
-            .map_err(|_| SafeWriterError::DirectoryCreationFailed(gen_base.as_ref().to_path_buf()))?;
-        let gen_base = gen_base
-            .as_ref()
-            .canonicalize()
-            .map_err(SafeWriterError::IoError)?;
+        let gen_base = if config.check && !gen_base.as_ref().exists() {
+            std::path::absolute(gen_base.as_ref()).map_err(SafeWriterError::IoError)?
+        } else {
+            fs::create_dir_all(gen_base.as_ref())
+                .map_err(|_| SafeWriterError::DirectoryCreationFailed(gen_base.as_ref().to_path_buf()))?;
+            gen_base
+                .as_ref()
+                .canonicalize()
+                .map_err(SafeWriterError::IoError)?
+        };
 
         let db = WeavebackDb::open_temp().map_err(SafeWriterError::DbError)?;
 
@@ -87,6 +124,11 @@ impl SafeFileWriter {
             db,
             config,
             staging: HashMap::new(),
+            staged_outputs: BTreeMap::new(),
+            prior: None,
+            merged_outputs: Vec::new(),
+            pending: Vec::new(),
+            removals: Vec::new(),
         })
     }
 }
@@ -21,15 +21,40 @@ impl SafeFileWriter {
         &mut self.db
     }
 
-    pub fn finish(self, target: &Path) -> Result<(), SafeWriterError> {
-        self.db.merge_into(target).map_err(SafeWriterError::DbError)?;
-        Ok(())
+    pub fn finish(mut self, target: &Path) -> Result<(), SafeWriterError> {
+        self.commit(target)
     }
 
     pub fn get_gen_base(&self) -> &Path {
         &self.gen_base
     }
 
+    /// Register the persistent database whose baselines `after_write` falls
+    /// back to for files this run has not written yet.
+    pub fn set_prior_db(&mut self, prior: WeavebackDb) {
+        self.prior = Some(prior);
+    }
+
+    /// Files whose hand edits were three-way merged in this run.
+    pub fn merged_outputs(&self) -> &[MergedOutput] {
+        &self.merged_outputs
+    }
+
+    pub(in crate::safe_writer) fn baseline(&self, key: &str) -> Result<Option<Vec<u8>>, SafeWriterError> {
+        if let Some(b) = self.db.get_baseline(key)? {
+            return Ok(Some(b));
+        }
+        match &self.prior {
+            Some(prior) => Ok(prior.get_baseline(key).ok().flatten()),
+            None => Ok(None),
+        }
+    }
+
+    /// Final content of every file staged in check mode, by logical name.
+    pub fn staged_outputs(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.staged_outputs
+    }
+
     /// Retrieve the stored baseline bytes for a relative path (test helper).
     #[cfg(test)]
     pub fn get_baseline_for_test(&self, path: &str) -> Option<Vec<u8>> {
@@ -35,7 +35,7 @@ impl SafeFileWriter {
     }
 
     pub(in crate::safe_writer) fn copy_if_different<P: AsRef<Path>>(
-        &self,
+        &mut self,
         source: P,
         destination: P,
     ) -> Result<(), SafeWriterError> {
@@ -43,9 +43,7 @@ impl SafeFileWriter {
         let destination = destination.as_ref();
 
         if !destination.exists() {
-            return self
-                .atomic_copy(source, destination)
-                .map_err(SafeWriterError::from);
+            return self.publish(source, destination);
         }
 
         let are_different = {
@@ -70,7 +68,7 @@ impl SafeFileWriter {
 
         if are_different {
             eprintln!("file {} changed", destination.display());
-            self.atomic_copy(source, destination)?;
+            self.publish(source, destination)?;
         }
 
         Ok(())
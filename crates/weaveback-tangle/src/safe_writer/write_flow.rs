@@ -14,9 +14,11 @@ impl SafeFileWriter {
         validate_filename(file_name.as_ref())?;
         let path = file_name.as_ref();
 
-        let dest_dir = path.parent().unwrap_or_else(|| Path::new(""));
-        fs::create_dir_all(self.gen_base.join(dest_dir))
-            .map_err(|_| SafeWriterError::DirectoryCreationFailed(self.gen_base.join(dest_dir)))?;
+        if !self.config.check {
+            let dest_dir = path.parent().unwrap_or_else(|| Path::new(""));
+            fs::create_dir_all(self.gen_base.join(dest_dir))
+                .map_err(|_| SafeWriterError::DirectoryCreationFailed(self.gen_base.join(dest_dir)))?;
+        }
 
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
         let suffix = if ext.is_empty() {
@@ -68,6 +70,15 @@ impl SafeFileWriter {
 
         self.normalize_trailing_whitespace(&tmp_path)?;
 
+        // Check mode: keep the staged content for comparison and leave gen/
+        // untouched.
+        if self.config.check {
+            let written = fs::read(&tmp_path)
+                .map_err(|_| SafeWriterError::BackupFailed(tmp_path.clone()))?;
+            self.staged_outputs.insert(key, written.clone());
+            return Ok(written);
+        }
+
         // Step 2: content-based modification detection.
         // When a stored baseline exists, compare the on-disk file against it:
         // any difference means the file was hand-edited since the last tangle.
@@ -75,25 +86,35 @@ impl SafeFileWriter {
         // what tangle is about to write: in a consistent literate project the
         // committed generated file should match the committed .adoc, so any
         // difference still indicates a hand-edit.
+        // In merge mode a hand edit against a known baseline is merged into
+        // the file instead (Step 3 is then already done).
+        let mut merged = false;
         if output_file.is_file() && !self.config.force_generated {
             let current = fs::read(&output_file)?;
-            let reference = match self.db.get_baseline(&key)? {
-                Some(b) => b,
+            let baseline = self.baseline(&key)?;
+            let reference = match &baseline {
+                Some(b) => b.clone(),
                 None => fs::read(&tmp_path)?,
             };
             if current != reference {
-                return Err(SafeWriterError::ModifiedExternally(output_file));
+                match baseline {
+                    Some(base) if self.config.merge => {
+                        self.merge_external_edit(&output_file, &base, &current, &tmp_path)?;
+                        merged = true;
+                    }
+                    _ => return Err(SafeWriterError::ModifiedExternally(output_file)),
+                }
             }
         }
 
         // Step 3: copy temp → output.
         // Normally skip the copy when content is identical (keeps build-system
         // timestamps stable).  When force_generated is set we always overwrite —
-        // that is the whole point of the flag.
+        // that is the whole point of the flag.  A merged file was already
+        // written by merge_external_edit.
         if self.config.force_generated {
-            self.atomic_copy(&tmp_path, &output_file)
-                .map_err(SafeWriterError::from)?;
-        } else {
+            self.publish(&tmp_path, &output_file)?;
+        } else if !merged {
             self.copy_if_different(&tmp_path, &output_file)?;
         }
 
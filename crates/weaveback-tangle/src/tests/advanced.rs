@@ -10,3 +10,6 @@ mod tangle_check;
 mod syntax;
 mod outputs;
 mod strict_write;
+mod collections;
+mod guards;
+mod transclude;
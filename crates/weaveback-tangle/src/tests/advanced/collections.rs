@@ -0,0 +1,93 @@
+// weaveback-tangle/src/tests/advanced/collections.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::super::*;
+
+/// A glob reference expands every matching chunk in name order, never the
+/// referencing chunk itself.
+#[test]
+fn test_glob_reference_expands_matches_in_name_order() {
+    let mut setup = TestSetup::new(&["#"]);
+    setup.clip.read(
+        "# <<tests/b>>=\nbeta\n# @\n\
+         # <<tests/a/nested>>=\nalpha\n# @\n\
+         # <<other>>=\nskipped\n# @\n\
+         # <<tests/all>>=\n  # <<@glob tests/*>>\n# @\n",
+        "glob.adoc",
+    );
+
+    let expanded = setup.clip.expand("tests/all", "").unwrap();
+    assert_eq!(expanded, vec!["  alpha\n", "  beta\n"]);
+}
+
+/// `<<@collect tag>>` gathers chunks tagged `@tag(tag)`; `@reversed` reverses
+/// the member order.
+#[test]
+fn test_collect_reference_gathers_tagged_chunks() {
+    let mut setup = TestSetup::new(&["#"]);
+    setup.clip.read(
+        "# <<@tag(handler, cli) json>>=\njson\n# @\n\
+         # <<@tag(handler) @replace yaml>>=\nyaml\n# @\n\
+         # <<@tag(cli) args>>=\nargs\n# @\n\
+         # <<handlers>>=\n# <<@collect handler>>\n# @\n\
+         # <<reversed>>=\n# <<@reversed @collect handler>>\n# @\n\
+         # <<none>>=\n# <<@collect missing>>\n# @\n",
+        "collect.adoc",
+    );
+    setup.clip.set_strict_undefined(true);
+
+    assert_eq!(setup.clip.expand("handlers", "").unwrap(), vec!["json\n", "yaml\n"]);
+    assert_eq!(setup.clip.expand("reversed", "").unwrap(), vec!["yaml\n", "json\n"]);
+    assert!(setup.clip.expand("none", "").unwrap().is_empty());
+}
+
+/// Each member is a recorded dependency, and so counts as referenced.
+#[test]
+fn test_collection_members_are_recorded_dependencies() {
+    let mut setup = TestSetup::new(&["#"]);
+    setup.clip.read(
+        "# <<@file out.txt>>=\n# <<@glob parts/???>>\n# @\n\
+         # <<parts/one>>=\n1\n# @\n\
+         # <<parts/two>>=\n2\n# @\n",
+        "deps.adoc",
+    );
+    setup.clip.write_files().unwrap();
+
+    let deps = setup.clip.db().list_all_chunk_deps().unwrap();
+    let mut edges: Vec<(&str, &str)> = deps
+        .iter()
+        .map(|(from, to, _)| (from.as_str(), to.as_str()))
+        .collect();
+    edges.sort_unstable();
+    assert_eq!(
+        edges,
+        vec![
+            ("@file out.txt", "@glob parts/???"),
+            ("@file out.txt", "parts/one"),
+            ("@file out.txt", "parts/two"),
+        ]
+    );
+
+    let mut referenced: std::collections::HashSet<String> =
+        setup.clip.get_file_chunks().into_iter().collect();
+    referenced.extend(deps.into_iter().map(|(_, to, _)| to));
+    assert!(setup.clip.check_unused_chunks(&referenced).is_empty());
+    assert!(crate::is_collection_reference("@glob parts/*"));
+    assert!(crate::is_collection_reference("@collect handler"));
+    assert!(!crate::is_collection_reference("parts/one"));
+}
+
+/// Without `@glob`, `*` and `?` are ordinary characters of a chunk name.
+#[test]
+fn test_wildcards_outside_glob_references_are_literal() {
+    let mut setup = TestSetup::new(&["#"]);
+    setup.clip.read(
+        "# <<ops/*>>=\nstar\n# @\n\
+         # <<ops/add>>=\nadd\n# @\n\
+         # <<user>>=\n# <<ops/*>>\n# @\n",
+        "literal.adoc",
+    );
+
+    assert_eq!(setup.clip.expand("user", "").unwrap(), vec!["star\n"]);
+    assert!(!crate::is_collection_reference("ops/*"));
+}
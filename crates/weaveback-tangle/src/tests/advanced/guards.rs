@@ -0,0 +1,59 @@
+// weaveback-tangle/src/tests/advanced/guards.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::super::*;
+use crate::{ChunkError, WeavebackError};
+
+const VARIANTS: &str = "# <<@file config.h>>= @if(profile=embedded|tiny)\n\
+                        #define HEAP 4096\n# @\n\
+                        # <<@file config.h>>= @if(profile!=embedded|tiny)\n\
+                        #define HEAP 0\n# @\n\
+                        # <<extras>>=\nbase\n# @\n\
+                        # <<extras>>= @if(profile=tiny)\ntiny\n# @\n";
+
+/// Only definitions whose guard holds for the selected profile are kept, so
+/// two guarded `@file` variants are not a redefinition.
+#[test]
+fn test_guards_select_definitions_for_the_profile() {
+    for (profile, heap, extras) in [
+        (Some("embedded"), "#define HEAP 4096\n", vec!["base\n"]),
+        (Some("tiny"), "#define HEAP 4096\n", vec!["base\n", "tiny\n"]),
+        (Some("hosted"), "#define HEAP 0\n", vec!["base\n"]),
+        (None, "#define HEAP 0\n", vec!["base\n"]),
+    ] {
+        let mut setup = TestSetup::new(&["#"]);
+        setup.clip.set_strict_undefined(true);
+        setup.clip.set_profile(profile.map(str::to_string));
+        setup.clip.read(VARIANTS, "variants.adoc");
+
+        assert_eq!(setup.clip.expand("@file config.h", "").unwrap(), vec![heap], "{profile:?}");
+        assert_eq!(setup.clip.expand("extras", "").unwrap(), extras, "{profile:?}");
+        setup.clip.write_files().unwrap();
+    }
+}
+
+/// An inactive definition's body is dropped, not appended to the chunk
+/// before it.
+#[test]
+fn test_inactive_body_does_not_leak_into_the_open_chunk() {
+    let mut setup = TestSetup::new(&["#"]);
+    setup.clip.read(
+        "# <<open>>=\nkept\n# <<open>>= @if(profile=other)\ndropped\n# @\n",
+        "leak.adoc",
+    );
+    assert_eq!(setup.clip.expand("open", "").unwrap(), vec!["kept\n"]);
+}
+
+#[test]
+fn test_malformed_guard_is_a_strict_parse_error() {
+    let mut setup = TestSetup::new(&["#"]);
+    setup.clip.set_strict_undefined(true);
+    setup.clip.set_profile(Some("embedded".to_string()));
+    setup.clip.read("# <<@file out.txt>>= @if(target=arm)\nbody\n# @\n", "bad.adoc");
+
+    assert!(!setup.clip.has_chunk("@file out.txt"));
+    match setup.clip.write_files().unwrap_err() {
+        WeavebackError::Chunk(ChunkError::InvalidGuard { guard, .. }) => assert_eq!(guard, "target=arm"),
+        other => panic!("expected InvalidGuard, got: {:?}", other),
+    }
+}
@@ -21,6 +21,27 @@ fn noweb_syntax_parse_definition_line_replace() {
     assert_eq!(m.base_name, "greet");
 }
 
+#[test]
+fn noweb_syntax_parse_definition_line_tags() {
+    use crate::noweb::NowebSyntax;
+    let syn = NowebSyntax::new("<<", ">>", "@", &["#".to_string()]);
+    let m = syn.parse_definition_line("# <<@tag(a, b) @tag(c) @replace greet>>=").unwrap();
+    assert_eq!(m.tags, vec!["a", "b", "c"]);
+    assert!(m.is_replace);
+    assert_eq!(m.base_name, "greet");
+    assert!(syn.parse_definition_line("# <<greet>>=").unwrap().tags.is_empty());
+}
+
+#[test]
+fn noweb_syntax_parse_definition_line_guard() {
+    use crate::noweb::NowebSyntax;
+    let syn = NowebSyntax::new("<<", ">>", "@", &["#".to_string()]);
+    let m = syn.parse_definition_line("# <<@file main.c>>= @if(profile=embedded|tiny)").unwrap();
+    assert_eq!(m.guard.as_deref(), Some("profile=embedded|tiny"));
+    assert_eq!(m.base_name, "main.c");
+    assert!(syn.parse_definition_line("# <<greet>>=").unwrap().guard.is_none());
+}
+
 #[test]
 fn noweb_syntax_parse_definition_line_returns_none_for_content() {
     use crate::noweb::NowebSyntax;
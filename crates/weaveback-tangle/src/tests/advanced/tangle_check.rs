@@ -32,3 +32,48 @@ fn tangle_check_handles_multiple_files() {
     assert_eq!(result["a.txt"], vec!["alpha\n"]);
     assert_eq!(result["b.txt"], vec!["beta\n"]);
 }
+
+#[test]
+fn tangle_check_honours_per_file_front_matter_delimiters() {
+    use crate::noweb::tangle_check;
+    let a = ":weaveback-open-delim: [[\n:weaveback-close-delim: ]]\n\n# [[@file a.txt]]=\n# [[body]]\n# @\n# [[body]]=\nalpha\n# @\n";
+    let b = "# <<@file b.txt>>=\n[[body]]\n# @\n";
+    let markers = vec!["#".to_string()];
+    let result = tangle_check(&[(a, "a.adoc"), (b, "b.adoc")], "<<", ">>", "@", &markers).unwrap();
+    assert_eq!(result["a.txt"], vec!["alpha\n"]);
+    assert_eq!(result["b.txt"], vec!["[[body]]\n"]);
+}
+
+#[test]
+fn tangle_check_reads_classic_noweb_chunks() {
+    use crate::noweb::tangle_check;
+    let src = "Intro.\n@ text\n<<@file out.c>>=\nint main() {\n  <<body>>\n}\n@ The body:\n<<body>>=\nreturn 0;\n<<body>>=\n/* unreachable */\n@ %def main\n";
+    let markers = vec!["#".to_string()];
+    let result = tangle_check(&[(src, "prog.nw")], "<<", ">>", "@", &markers).unwrap();
+    assert_eq!(
+        result["out.c"],
+        vec!["int main() {\n", "  return 0;\n", "  /* unreachable */\n", "}\n"]
+    );
+}
+
+#[test]
+fn classic_noweb_uses_noweb_delimiters_whatever_the_pass_configures() {
+    use crate::noweb::tangle_check;
+    let src = "<<@file out.c>>=\nint main() {\n  <<body>>\n}\n@ The body:\n<<body>>=\nreturn 0;\n@\n";
+    let markers = vec!["#".to_string()];
+    let result = tangle_check(&[(src, "prog.nw")], "<[", "]>", "@@", &markers).unwrap();
+    assert_eq!(result["out.c"], vec!["int main() {\n", "  return 0;\n", "}\n"]);
+}
+
+#[test]
+fn tangle_check_maps_org_header_arguments_onto_chunks() {
+    use crate::noweb::tangle_check;
+    let src = "* Program\n#+begin_src rust :tangle src/main.rs\nfn main() {\n    <<body>>\n}\n#+end_src\n\n#+NAME: body\n#+BEGIN_SRC rust\nprintln!(\"hi\");\n#+END_SRC\n#+begin_src rust :noweb-ref body\n,* not a headline\n#+end_src\n#+begin_src rust :tangle src/main.rs\n// more\n#+end_src\n";
+    let markers = vec!["//".to_string()];
+    // The pass syntax is `<[`/`]>`; Org references always use `<<`/`>>`.
+    let result = tangle_check(&[(src, "notes.org")], "<[", "]>", "@@", &markers).unwrap();
+    assert_eq!(
+        result["src/main.rs"],
+        vec!["fn main() {\n", "    println!(\"hi\");\n", "    * not a headline\n", "}\n", "// more\n"]
+    );
+}
@@ -0,0 +1,81 @@
+// weaveback-tangle/src/tests/advanced/transclude.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use std::fs;
+
+use super::super::*;
+use crate::{ChunkError, WeavebackError};
+
+const LEGACY: &str = "use std::io;\n\
+                      // tag::parse[]\n\
+                      fn parse() -> u32 {\n    \
+                          42\n\
+                      }\n\
+                      // end::parse[]\n";
+
+fn setup_with_legacy() -> TestSetup {
+    let mut setup = TestSetup::new(&["#"]);
+    let root = setup._temp_dir.path().to_path_buf();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/legacy.rs"), LEGACY).unwrap();
+    setup.clip.set_project_root(&root);
+    setup
+}
+
+/// A tagged region is indented like a chunk, mapped to its lines in the
+/// plain file, and recorded as a dependency and a definition.
+#[test]
+fn test_from_reference_transcludes_a_tagged_region() {
+    let mut setup = setup_with_legacy();
+    setup.clip.read(
+        "# <<@file lib.rs>>=\nmod legacy {\n    # <<@from src/legacy.rs#parse>>\n}\n# @\n",
+        "lib.adoc",
+    );
+    assert_eq!(
+        setup.clip.expand("@file lib.rs", "").unwrap(),
+        vec!["mod legacy {\n", "    fn parse() -> u32 {\n", "        42\n", "    }\n", "}\n"]
+    );
+    assert_eq!(setup.clip.transcluded_files(), vec!["src/legacy.rs"]);
+    setup.clip.write_files().unwrap();
+
+    let entry = setup.clip.db().get_noweb_entry_by_suffix("lib.rs", 2).unwrap().unwrap();
+    assert_eq!(entry.src_file, "src/legacy.rs");
+    assert_eq!(entry.chunk_name, "@from src/legacy.rs#parse");
+    assert_eq!(entry.src_line, 3);
+
+    // Recorded for incremental runs, but not listed as part of the chunk graph.
+    let db = setup.clip.db();
+    let users = db.query_reverse_deps("@from src/legacy.rs#parse").unwrap();
+    assert_eq!(users.iter().map(|(from, _)| from.as_str()).collect::<Vec<_>>(), ["@file lib.rs"]);
+    let defs = db.query_chunk_defs_overlapping("src/legacy.rs", 1, 100).unwrap();
+    assert_eq!(defs.len(), 1);
+    assert_eq!((defs[0].def_start, defs[0].def_end), (2, 6));
+    assert!(db.list_chunk_defs(Some("src/legacy.rs")).unwrap().is_empty());
+    assert!(db.query_all_chunk_deps().unwrap().iter().all(|(_, to, _)| !crate::is_transclusion(to)));
+    assert!(db.query_chunk_deps("@file lib.rs").unwrap().is_empty());
+}
+
+#[test]
+fn test_from_reference_accepts_line_ranges_and_whole_files() {
+    let mut setup = setup_with_legacy();
+    setup.clip.read(
+        "# <<one>>=\n# <<@from src/legacy.rs#L1>>\n# @\n\
+         # <<all>>=\n# <<@from src/legacy.rs>>\n# @\n",
+        "ranges.adoc",
+    );
+    assert_eq!(setup.clip.expand("one", "").unwrap(), vec!["use std::io;\n"]);
+    assert_eq!(setup.clip.expand("all", "").unwrap().len(), LEGACY.lines().count());
+}
+
+#[test]
+fn test_missing_region_is_a_transclusion_error() {
+    let mut setup = setup_with_legacy();
+    setup.clip.read("# <<bad>>=\n# <<@from src/legacy.rs#nope>>\n# @\n", "bad.adoc");
+    match setup.clip.expand("bad", "") {
+        Err(WeavebackError::Chunk(ChunkError::Transclusion { reference, message, .. })) => {
+            assert_eq!(reference, "@from src/legacy.rs#nope");
+            assert!(message.contains("tag::nope[]"), "{message}");
+        }
+        other => panic!("expected a transclusion error, got {other:?}"),
+    }
+}
@@ -2,6 +2,7 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 mod prose_fts;
+mod code_fts;
 mod tags;
 mod embeddings;
 mod baselines;
@@ -0,0 +1,72 @@
+// weaveback-tangle/src/tests/fts/code_fts.rs
+// I'd Really Rather You Didn't edit this generated file.
+
+use super::*;
+use crate::db::{ChunkDefEntry, Confidence, NowebMapEntry};
+
+const SOURCE: &str = concat!(
+    "= Opening\n",
+    "\n",
+    "The database waits when another writer holds the lock.\n",
+    "\n",
+    "// <<open-db>>=\n",
+    "let conn = Connection::open(path)?;\n",
+    "conn.busy_timeout(Duration::from_secs(5))?;\n",
+    "// @\n",
+    "\n",
+    "// <<writer>>=\n",
+    "let writer = SafeFileWriter::new(HTTPServer::root());\n",
+    "// @\n",
+);
+
+fn code_db() -> WeavebackDb {
+    let mut db = WeavebackDb::open_temp().unwrap();
+    db.set_src_snapshot("src/db.adoc", SOURCE.as_bytes()).unwrap();
+    db.set_source_blocks("src/db.adoc", &[
+        block(0, "section", 1, 1),
+        block(1, "para",    3, 3),
+    ]).unwrap();
+    db.set_chunk_defs(&[
+        ChunkDefEntry { src_file: "src/db.adoc".into(), chunk_name: "open-db".into(), nth: 0, def_start: 5, def_end: 8 },
+        ChunkDefEntry { src_file: "src/db.adoc".into(), chunk_name: "writer".into(), nth: 0, def_start: 10, def_end: 12 },
+    ]).unwrap();
+    let mapped = |src_line| NowebMapEntry {
+        src_file: "src/db.adoc".into(),
+        chunk_name: "open-db".into(),
+        src_line,
+        indent: String::new(),
+        confidence: Confidence::Exact,
+    };
+    db.set_noweb_entries("src/db.rs", &[(0, mapped(5)), (1, mapped(6))]).unwrap();
+    db.rebuild_fts(None).unwrap();
+    db
+}
+
+#[test]
+fn code_fts_finds_identifier_in_chunk_body() {
+    let db = code_db();
+    assert!(db.search_prose("busy_timeout", 10).unwrap().is_empty());
+
+    let hits = db.search_code("busy_timeout", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+    let hit = &hits[0];
+    assert_eq!(hit.src_file, "src/db.adoc");
+    assert_eq!(hit.chunk_name, "open-db");
+    assert_eq!((hit.line_start, hit.line_end), (6, 7));
+    assert!(hit.snippet.contains("**busy_timeout**"), "{}", hit.snippet);
+    assert_eq!(hit.generated_files, vec!["src/db.rs".to_string()]);
+}
+
+#[test]
+fn code_fts_matches_identifier_parts() {
+    let db = code_db();
+    for query in ["timeout", "file writer", "http server", "safefilewriter"] {
+        let hits = db.search_code(query, 10).unwrap();
+        assert_eq!(hits.len(), 1, "query {query:?}");
+    }
+    assert_eq!(db.search_code("timeout", 10).unwrap()[0].chunk_name, "open-db");
+    assert_eq!(db.search_code("http server", 10).unwrap()[0].chunk_name, "writer");
+    assert!(db.search_code("writer", 10).unwrap()[0].generated_files.is_empty());
+    // Marker lines are not part of the body.
+    assert!(db.search_code("\"open-db\"", 10).unwrap().is_empty());
+}
@@ -2,6 +2,7 @@
 // I'd Really Rather You Didn't edit this generated file.
 
 use super::*;
+use crate::db::ANN_MIN_VECTORS;
 
 #[test]
 fn test_get_blocks_needing_embeddings_returns_all_when_none_stored() {
@@ -61,3 +62,78 @@ fn test_search_prose_by_embedding_ranks_semantic_match() {
     assert_eq!(results[0].line_start, 3);
     assert_eq!(results[0].tags, "fruit");
 }
+
+/// Deterministic pseudo-random floats in `[-1, 1)`.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 40) as f32 / (1u64 << 24) as f32) * 2.0 - 1.0
+    }
+
+    /// A point near `topic`.
+    fn near(&mut self, topic: &[f32]) -> Vec<f32> {
+        topic.iter().map(|x| x + 0.6 * self.next()).collect()
+    }
+}
+
+#[test]
+fn test_embedding_index_recall_against_exact_search() {
+    const BLOCKS: u32 = 2000;
+    const QUERIES: usize = 50;
+    const LIMIT: usize = 10;
+    let mut rng = Lcg(7);
+    let topics: Vec<Vec<f32>> = (0..40).map(|_| (0..32).map(|_| rng.next()).collect()).collect();
+
+    let mut db = WeavebackDb::open_temp().unwrap();
+    let source: String = (0..BLOCKS).map(|i| format!("block {i}\n")).collect();
+    db.set_src_snapshot("a.adoc", source.as_bytes()).unwrap();
+    let blocks: Vec<_> = (0..BLOCKS).map(|i| block(i, "para", i + 1, i + 1)).collect();
+    db.set_source_blocks("a.adoc", &blocks).unwrap();
+    for i in 0..BLOCKS {
+        let vector = rng.near(&topics[i as usize % topics.len()]);
+        db.set_block_embedding("a.adoc", i, &[0u8; 32], "m", &vector).unwrap();
+    }
+
+    assert!(db.update_embedding_index("m").unwrap());
+    assert!(!db.update_embedding_index("m").unwrap(), "an unchanged index is not retrained");
+    let counts: std::collections::HashMap<String, i64> =
+        db.table_row_counts().unwrap().into_iter().collect();
+    assert_eq!(counts["embedding_lists"], 45);
+
+    let mut found = 0;
+    for q in 0..QUERIES {
+        let query = rng.near(&topics[q % topics.len()]);
+        let exact = db.search_prose_by_embedding_exact(&query, LIMIT).unwrap();
+        let approximate = db.search_prose_by_embedding(&query, LIMIT).unwrap();
+        assert_eq!(approximate.len(), LIMIT);
+        found += approximate
+            .iter()
+            .filter(|hit| exact.iter().any(|e| e.line_start == hit.line_start))
+            .count();
+    }
+    let recall = found as f32 / (QUERIES * LIMIT) as f32;
+    assert!(recall >= 0.9, "recall@{LIMIT} = {recall}");
+}
+
+#[test]
+fn test_embedding_index_needs_the_minimum_vector_count() {
+    let mut db = WeavebackDb::open_temp().unwrap();
+    let mut rng = Lcg(3);
+    let mut embed = |db: &mut WeavebackDb, i: u32| {
+        let vector: Vec<f32> = (0..4).map(|_| rng.next()).collect();
+        db.set_block_embedding("a.adoc", i, &[0u8; 32], "m", &vector).unwrap();
+    };
+    for i in 1..ANN_MIN_VECTORS as u32 {
+        embed(&mut db, i);
+    }
+    assert!(!db.update_embedding_index("m").unwrap());
+    assert_eq!(db.rebuild_embedding_index("m").unwrap(), 0);
+
+    embed(&mut db, 0);
+    assert!(db.update_embedding_index("m").unwrap());
+    let counts: std::collections::HashMap<String, i64> =
+        db.table_row_counts().unwrap().into_iter().collect();
+    assert_eq!((counts["embedding_lists"], counts["embedding_index"]), (16, 1));
+}
@@ -111,3 +111,52 @@ fn db_get_all_output_mappings() {
     let mappings = db.get_all_output_mappings("s.adoc").unwrap();
     assert_eq!(mappings.len(), 2);
 }
+
+#[test]
+fn db_noweb_entries_are_stored_as_line_ranges() {
+    use crate::db::{Confidence, NowebMapEntry};
+    let mut db = WeavebackDb::open_temp().unwrap();
+    let entry = |chunk: &str, src_line: u32| NowebMapEntry {
+        src_file: "src.adoc".to_string(),
+        chunk_name: chunk.to_string(),
+        src_line,
+        indent: String::new(),
+        confidence: Confidence::Exact,
+    };
+    // Two runs of `outer` around a run of `inner`, then a gap in the source.
+    let entries: Vec<(u32, NowebMapEntry)> = vec![
+        (0, entry("outer", 4)),
+        (1, entry("outer", 5)),
+        (2, entry("inner", 9)),
+        (3, entry("inner", 10)),
+        (4, entry("outer", 7)),
+        (5, entry("outer", 9)),
+    ];
+    db.set_noweb_entries("gen/out.rs", &entries).unwrap();
+    let rows: std::collections::HashMap<String, i64> =
+        db.table_row_counts().unwrap().into_iter().collect();
+    assert_eq!(rows["noweb_map"], 4);
+
+    let summary = |db: &WeavebackDb| -> Vec<(u32, String, u32)> {
+        db.get_noweb_entries_for_file("gen/out.rs")
+            .unwrap()
+            .into_iter()
+            .map(|(line, e)| (line, e.chunk_name, e.src_line))
+            .collect()
+    };
+    let expected: Vec<(u32, String, u32)> = entries
+        .iter()
+        .map(|(line, e)| (*line, e.chunk_name.clone(), e.src_line))
+        .collect();
+    assert_eq!(summary(&db), expected);
+    assert_eq!(db.get_noweb_entry("gen/out.rs", 3).unwrap().unwrap().src_line, 10);
+    assert!(db.get_noweb_entry("gen/out.rs", 6).unwrap().is_none());
+    assert_eq!(
+        db.get_output_location("src.adoc", 10).unwrap(),
+        Some(("gen/out.rs".to_string(), 3))
+    );
+
+    // A new map replaces the old one, including lines it no longer has.
+    db.set_noweb_entries("gen/out.rs", &entries[..2]).unwrap();
+    assert_eq!(summary(&db), expected[..2].to_vec());
+}
@@ -81,3 +81,15 @@ fn db_macro_defs_roundtrip() {
     assert_eq!(defs.len(), 1);
     assert_eq!(defs[0].0, "src.adoc");
 }
+
+#[test]
+fn db_macro_calls_list_called_names() {
+    let db = WeavebackDb::open_temp().unwrap();
+    db.record_macro_def("used", "src.adoc", 0, 10).unwrap();
+    db.record_macro_def("unused", "src.adoc", 20, 10).unwrap();
+    db.record_macro_call("used", "doc.adoc", 5, 7).unwrap();
+    db.record_macro_call("used", "doc.adoc", 5, 7).unwrap();
+    assert_eq!(db.called_macros().unwrap(), ["used"]);
+    let defs: Vec<String> = db.list_macro_defs().unwrap().into_iter().map(|d| d.0).collect();
+    assert_eq!(defs, ["unused", "used"]);
+}
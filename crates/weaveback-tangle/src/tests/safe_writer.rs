@@ -5,6 +5,8 @@ mod basic;
 mod modification;
 mod paths;
 mod formatters;
+mod merge;
+mod commit;
 
 use super::*;
 use crate::SafeWriterError;
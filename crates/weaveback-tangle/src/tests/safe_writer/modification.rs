@@ -131,6 +131,49 @@ fn test_force_generated_writes_identical_content() -> Result<(), WeavebackError>
     Ok(())
 }
 
+#[test]
+fn test_check_mode_stages_without_creating_gen() -> Result<(), WeavebackError> {
+    let temp = TempDir::new().unwrap();
+    let gen_dir = temp.path().join("gen");
+    let mut writer = SafeFileWriter::with_config(
+        &gen_dir,
+        SafeWriterConfig {
+            check: true,
+            ..SafeWriterConfig::default()
+        },
+    ).unwrap();
+
+    write_file(&mut writer, &PathBuf::from("sub/out.txt"), "Staged content")?;
+
+    assert!(!gen_dir.exists(), "check mode must not create gen/");
+    assert_eq!(
+        writer.staged_outputs().get("sub/out.txt").map(Vec::as_slice),
+        Some("Staged content".as_bytes())
+    );
+    Ok(())
+}
+
+#[test]
+fn test_check_mode_leaves_modified_file_alone() -> Result<(), WeavebackError> {
+    let temp = TempDir::new().unwrap();
+    let gen_dir = temp.path().join("gen");
+    fs::create_dir_all(&gen_dir)?;
+    fs::write(gen_dir.join("test.txt"), "Edited by hand")?;
+    let mut writer = SafeFileWriter::with_config(
+        &gen_dir,
+        SafeWriterConfig {
+            check: true,
+            ..SafeWriterConfig::default()
+        },
+    ).unwrap();
+
+    write_file(&mut writer, &PathBuf::from("test.txt"), "Regenerated content")?;
+
+    assert_eq!(fs::read_to_string(gen_dir.join("test.txt"))?, "Edited by hand");
+    assert!(writer.get_baseline_for_test("test.txt").is_none());
+    Ok(())
+}
+
 #[test]
 fn test_baseline_always_written() -> Result<(), WeavebackError> {
     let (_temp, mut writer) = create_test_writer();